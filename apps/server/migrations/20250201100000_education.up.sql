@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS education (
+	id BIGSERIAL PRIMARY KEY,
+	user_id BIGINT NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+	institution VARCHAR(255) NOT NULL,
+	degree VARCHAR(255),
+	field_of_study VARCHAR(255),
+	start_date DATE NOT NULL,
+	end_date DATE,
+	grade VARCHAR(100),
+	activities TEXT,
+	description TEXT,
+	position INTEGER
+);
+
+CREATE INDEX IF NOT EXISTS education_user_id_idx ON education (user_id);
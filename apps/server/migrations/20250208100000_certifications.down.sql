@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS certification_skills;
+DROP TABLE IF EXISTS certifications;
@@ -0,0 +1,22 @@
+CREATE TABLE IF NOT EXISTS certifications (
+	id BIGSERIAL PRIMARY KEY,
+	user_id BIGINT NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+	name VARCHAR(255) NOT NULL,
+	issuing_organization VARCHAR(255) NOT NULL,
+	issue_date DATE NOT NULL,
+	expiry_date DATE,
+	credential_id VARCHAR(255),
+	credential_url VARCHAR(2048),
+	-- The expiry date a reminder was last sent for, so changing the date re-arms the reminder
+	reminder_sent_for DATE,
+	CHECK (expiry_date IS NULL OR expiry_date >= issue_date)
+);
+
+CREATE INDEX IF NOT EXISTS certifications_user_id_idx ON certifications (user_id);
+CREATE INDEX IF NOT EXISTS certifications_expiry_date_idx ON certifications (expiry_date);
+
+CREATE TABLE IF NOT EXISTS certification_skills (
+	certification_id BIGINT NOT NULL REFERENCES certifications (id) ON DELETE CASCADE,
+	skill_id BIGINT NOT NULL REFERENCES skills (id) ON DELETE CASCADE,
+	PRIMARY KEY (certification_id, skill_id)
+);
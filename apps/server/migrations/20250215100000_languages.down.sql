@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS user_languages;
+DROP TABLE IF EXISTS languages;
+DROP TYPE IF EXISTS cefr_level;
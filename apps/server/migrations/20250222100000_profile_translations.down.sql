@@ -0,0 +1,7 @@
+DROP TABLE IF EXISTS experience_translations;
+DROP TABLE IF EXISTS user_translations;
+
+ALTER TABLE users
+	DROP COLUMN IF EXISTS headline,
+	DROP COLUMN IF EXISTS bio,
+	DROP COLUMN IF EXISTS default_locale;
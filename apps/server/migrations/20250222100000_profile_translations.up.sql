@@ -0,0 +1,21 @@
+ALTER TABLE users
+	ADD COLUMN IF NOT EXISTS headline VARCHAR(255),
+	ADD COLUMN IF NOT EXISTS bio TEXT,
+	-- Language the untranslated profile and experience text is written in
+	ADD COLUMN IF NOT EXISTS default_locale VARCHAR(35) NOT NULL DEFAULT 'en';
+
+CREATE TABLE IF NOT EXISTS user_translations (
+	user_id BIGINT NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+	locale VARCHAR(35) NOT NULL,
+	headline VARCHAR(255),
+	bio TEXT,
+	PRIMARY KEY (user_id, locale)
+);
+
+CREATE TABLE IF NOT EXISTS experience_translations (
+	experience_id BIGINT NOT NULL REFERENCES experience (id) ON DELETE CASCADE,
+	locale VARCHAR(35) NOT NULL,
+	occupation VARCHAR(255),
+	description TEXT,
+	PRIMARY KEY (experience_id, locale)
+);
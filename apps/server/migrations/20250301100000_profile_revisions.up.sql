@@ -0,0 +1,22 @@
+CREATE TABLE IF NOT EXISTS profile_revisions (
+	id BIGSERIAL PRIMARY KEY,
+	user_id BIGINT NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+	section VARCHAR(50) NOT NULL CHECK (
+		section IN (
+			'profile',
+			'experience',
+			'education',
+			'skills',
+			'languages'
+		)
+	),
+	changed_by BIGINT REFERENCES users (id) ON DELETE SET NULL,
+	-- Full state of the section after the change
+	snapshot JSONB NOT NULL,
+	-- RFC 6902 JSON Patch from the previous revision's snapshot
+	diff JSONB NOT NULL,
+	restored_from BIGINT REFERENCES profile_revisions (id) ON DELETE SET NULL,
+	created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS profile_revisions_user_id_section_idx ON profile_revisions (user_id, section, id DESC);
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS profile_drafts (
+	id BIGSERIAL PRIMARY KEY,
+	user_id BIGINT NOT NULL UNIQUE REFERENCES users (id) ON DELETE CASCADE,
+	-- Staged sections, in the same shape profile revisions store them
+	experience JSONB NOT NULL,
+	skills JSONB NOT NULL,
+	created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
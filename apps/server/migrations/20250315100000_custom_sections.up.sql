@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS custom_sections (
+	id BIGSERIAL PRIMARY KEY,
+	user_id BIGINT NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+	title VARCHAR(100) NOT NULL,
+	-- Typed blocks in display order, validated by the API
+	blocks JSONB NOT NULL DEFAULT '[]',
+	position INTEGER
+);
+
+CREATE INDEX IF NOT EXISTS custom_sections_user_id_idx ON custom_sections (user_id);
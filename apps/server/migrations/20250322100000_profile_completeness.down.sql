@@ -0,0 +1,6 @@
+DROP TABLE IF EXISTS profile_completeness;
+DROP TABLE IF EXISTS completeness_rules;
+
+ALTER TABLE users
+	DROP COLUMN IF EXISTS avatar_url,
+	DROP COLUMN IF EXISTS is_admin;
@@ -0,0 +1,43 @@
+ALTER TABLE users
+	ADD COLUMN IF NOT EXISTS avatar_url VARCHAR(2048),
+	-- Granted by hand; admins can see reports across all users
+	ADD COLUMN IF NOT EXISTS is_admin BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- What each rule measures is fixed by its key, how much it counts and what it asks for is data
+CREATE TABLE IF NOT EXISTS completeness_rules (
+	key VARCHAR(50) PRIMARY KEY CHECK (
+		key IN (
+			'avatar',
+			'headline',
+			'experience',
+			'skills',
+			'education',
+			'verified_email'
+		)
+	),
+	weight INTEGER NOT NULL CHECK (weight >= 0),
+	-- How many of the measured items are needed, e.g. experience entries with a description
+	threshold INTEGER NOT NULL DEFAULT 1 CHECK (threshold >= 1),
+	-- Shown while the rule isn't met, {threshold} is replaced with the threshold
+	suggestion TEXT NOT NULL,
+	enabled BOOLEAN NOT NULL DEFAULT TRUE
+);
+
+INSERT INTO
+	completeness_rules (key, weight, threshold, suggestion)
+VALUES
+	('avatar', 10, 1, 'Upload a profile picture'),
+	('headline', 15, 1, 'Add a headline that sums up what you do'),
+	('experience', 30, 2, 'Add at least {threshold} experience entries with a description'),
+	('skills', 20, 5, 'List at least {threshold} skills'),
+	('education', 15, 1, 'Add your education'),
+	('verified_email', 10, 1, 'Verify your email address')
+ON CONFLICT DO NOTHING;
+
+CREATE TABLE IF NOT EXISTS profile_completeness (
+	user_id BIGINT PRIMARY KEY REFERENCES users (id) ON DELETE CASCADE,
+	score SMALLINT NOT NULL CHECK (score BETWEEN 0 AND 100),
+	-- Keys of the rules the profile didn't meet
+	missing VARCHAR(50)[] NOT NULL DEFAULT '{}',
+	updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
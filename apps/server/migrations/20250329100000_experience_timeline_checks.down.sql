@@ -0,0 +1,3 @@
+ALTER TABLE experience
+	DROP CONSTRAINT IF EXISTS experience_end_date_after_start_date,
+	DROP CONSTRAINT IF EXISTS experience_current_without_end_date;
@@ -0,0 +1,14 @@
+-- Repair rows the API used to accept before the constraints go in: an end date on a current role
+-- means the role has ended, and an end date before the start date is taken to be swapped
+UPDATE experience
+SET is_current = FALSE
+WHERE is_current AND end_date IS NOT NULL;
+
+UPDATE experience
+SET start_date = end_date, end_date = start_date
+WHERE end_date < start_date;
+
+-- Future start dates are rejected by the API only, since a CHECK can't depend on the current time
+ALTER TABLE experience
+	ADD CONSTRAINT experience_end_date_after_start_date CHECK (end_date IS NULL OR end_date >= start_date),
+	ADD CONSTRAINT experience_current_without_end_date CHECK (NOT (is_current AND end_date IS NOT NULL));
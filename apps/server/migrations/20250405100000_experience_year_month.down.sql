@@ -0,0 +1,38 @@
+CREATE FUNCTION year_month_to_timestamp(value TEXT) RETURNS TIMESTAMPTZ AS $$
+	SELECT (LEFT(value || '-01', 10)::DATE)::TIMESTAMP AT TIME ZONE 'UTC'
+$$ LANGUAGE SQL IMMUTABLE;
+
+CREATE FUNCTION experience_snapshot_to_timestamp(snapshot JSONB) RETURNS JSONB AS $$
+	SELECT COALESCE(
+		JSONB_AGG(
+			entry || JSONB_BUILD_OBJECT(
+				'start_date', year_month_to_timestamp(entry->>'start_date'),
+				'end_date', year_month_to_timestamp(entry->>'end_date')
+			)
+			ORDER BY position
+		),
+		'[]'::JSONB
+	)
+	FROM JSONB_ARRAY_ELEMENTS(snapshot) WITH ORDINALITY AS entries (entry, position)
+$$ LANGUAGE SQL IMMUTABLE;
+
+ALTER TABLE experience
+	DROP CONSTRAINT experience_end_date_after_start_date;
+
+ALTER TABLE experience
+	ALTER COLUMN start_date TYPE TIMESTAMPTZ USING year_month_to_timestamp(start_date),
+	ALTER COLUMN end_date TYPE TIMESTAMPTZ USING year_month_to_timestamp(end_date);
+
+ALTER TABLE experience
+	ADD CONSTRAINT experience_end_date_after_start_date CHECK (end_date IS NULL OR end_date >= start_date);
+
+UPDATE profile_revisions
+SET snapshot = experience_snapshot_to_timestamp(snapshot)
+WHERE section = 'experience';
+
+UPDATE profile_drafts
+SET experience = experience_snapshot_to_timestamp(experience);
+
+DROP FUNCTION experience_snapshot_to_timestamp;
+DROP FUNCTION year_month_to_timestamp;
+DROP DOMAIN year_month;
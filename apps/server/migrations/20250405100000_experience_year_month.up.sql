@@ -0,0 +1,47 @@
+-- Experience dates come from a month picker, so they're stored as the calendar month itself ("2020-05"),
+-- optionally with a day ("2020-05-14"). Zero padding keeps text ordering and comparison chronological.
+CREATE DOMAIN year_month AS VARCHAR(10)
+	CHECK (VALUE ~ '^[0-9]{4}-(0[1-9]|1[0-2])(-(0[1-9]|[12][0-9]|3[01]))?$');
+
+-- The old timestamps were local midnight on the 1st sent as UTC, so "May 2020" east of UTC was stored in
+-- April. Shifting by 14 hours moves every offset up to UTC+14 back into the intended month. West of UTC it
+-- moves the 1st to the 2nd at most; only a month's last day at UTC-10 or further west would spill over.
+CREATE FUNCTION timestamp_to_year_month(value TIMESTAMPTZ) RETURNS year_month AS $$
+	SELECT TO_CHAR(value AT TIME ZONE 'UTC' + INTERVAL '14 hours', 'YYYY-MM')::year_month
+$$ LANGUAGE SQL IMMUTABLE;
+
+CREATE FUNCTION experience_snapshot_to_year_month(snapshot JSONB) RETURNS JSONB AS $$
+	SELECT COALESCE(
+		JSONB_AGG(
+			entry || JSONB_BUILD_OBJECT(
+				'start_date', timestamp_to_year_month((entry->>'start_date')::TIMESTAMPTZ),
+				'end_date', timestamp_to_year_month((entry->>'end_date')::TIMESTAMPTZ)
+			)
+			ORDER BY position
+		),
+		'[]'::JSONB
+	)
+	FROM JSONB_ARRAY_ELEMENTS(snapshot) WITH ORDINALITY AS entries (entry, position)
+$$ LANGUAGE SQL IMMUTABLE;
+
+ALTER TABLE experience
+	DROP CONSTRAINT experience_end_date_after_start_date;
+
+ALTER TABLE experience
+	ALTER COLUMN start_date TYPE year_month USING timestamp_to_year_month(start_date),
+	ALTER COLUMN end_date TYPE year_month USING timestamp_to_year_month(end_date);
+
+-- A month-only end date compares before any day in that month, so "2020-05" to "2020-05-14" is still valid
+ALTER TABLE experience
+	ADD CONSTRAINT experience_end_date_after_start_date CHECK (end_date IS NULL OR end_date >= start_date);
+
+-- Revisions and drafts hold copies of experience rows that get restored or published into the table
+UPDATE profile_revisions
+SET snapshot = experience_snapshot_to_year_month(snapshot)
+WHERE section = 'experience';
+
+UPDATE profile_drafts
+SET experience = experience_snapshot_to_year_month(experience);
+
+DROP FUNCTION experience_snapshot_to_year_month;
+DROP FUNCTION timestamp_to_year_month;
@@ -0,0 +1,2 @@
+DROP FUNCTION IF EXISTS experience_months;
+DROP FUNCTION IF EXISTS year_month_index;
@@ -0,0 +1,19 @@
+-- Months since the start of year 0, so that consecutive months are consecutive numbers
+CREATE FUNCTION year_month_index(value year_month) RETURNS INTEGER AS $$
+	SELECT LEFT(value, 4)::INTEGER * 12 + SUBSTRING(value FROM 6 FOR 2)::INTEGER - 1
+$$ LANGUAGE SQL IMMUTABLE STRICT;
+
+-- Months with at least one role, the same count as the profile's tenure: every month from the start month
+-- through the end month, current roles through the current month, overlapping roles counted once
+CREATE FUNCTION experience_months(experience_user_id BIGINT) RETURNS INTEGER AS $$
+	SELECT COALESCE(SUM(UPPER(months) - LOWER(months)), 0)::INTEGER
+	FROM UNNEST((
+		SELECT RANGE_AGG(INT4RANGE(
+			year_month_index(start_date),
+			year_month_index(COALESCE(end_date, TO_CHAR(CURRENT_DATE, 'YYYY-MM')::year_month)),
+			'[]'
+		))
+		FROM experience
+		WHERE user_id = experience_user_id
+	)) AS months
+$$ LANGUAGE SQL STABLE;
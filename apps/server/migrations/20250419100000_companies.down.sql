@@ -0,0 +1,11 @@
+DROP TRIGGER IF EXISTS experience_resolve_company ON experience;
+DROP FUNCTION IF EXISTS experience_resolve_company;
+
+ALTER TABLE experience
+	DROP COLUMN IF EXISTS company_id;
+
+DROP FUNCTION IF EXISTS resolve_company;
+DROP FUNCTION IF EXISTS company_slug;
+DROP TABLE IF EXISTS company_aliases;
+DROP TABLE IF EXISTS companies;
+DROP TYPE IF EXISTS company_size;
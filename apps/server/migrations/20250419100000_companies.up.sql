@@ -0,0 +1,102 @@
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE TYPE company_size AS ENUM ('1-10', '11-50', '51-200', '201-1000', '1001-5000', '5001+');
+
+CREATE TABLE IF NOT EXISTS companies (
+	id BIGSERIAL PRIMARY KEY,
+	name VARCHAR(255) NOT NULL,
+	-- Normalized name, see company_slug(); what company names are matched on
+	slug VARCHAR(255) NOT NULL UNIQUE,
+	logo_url VARCHAR(2048),
+	website VARCHAR(2048),
+	industry VARCHAR(100),
+	size company_size,
+	created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS companies_slug_trgm_idx ON companies USING GIN (slug gin_trgm_ops);
+
+-- Slugs of companies merged into another one, so their old names and links keep resolving
+CREATE TABLE IF NOT EXISTS company_aliases (
+	slug VARCHAR(255) PRIMARY KEY,
+	company_id BIGINT NOT NULL REFERENCES companies (id) ON DELETE CASCADE
+);
+
+-- "Google LLC", "google" and "Google, Inc." all become "google"
+CREATE FUNCTION company_slug(name TEXT) RETURNS TEXT AS $$
+	SELECT REGEXP_REPLACE(
+		TRIM(BOTH '-' FROM REGEXP_REPLACE(LOWER(name), '[^[:alnum:]]+', '-', 'g')),
+		'(-(inc|llc|ltd|limited|gmbh|ag|corp|corporation|co|company|plc|sa|srl|bv|oy|ab))+$',
+		''
+	)
+$$ LANGUAGE SQL IMMUTABLE STRICT;
+
+-- Finds the company a free text name refers to: by slug, then by the slug of a merged company, then by the
+-- closest similar slug, creating the company when nothing matches
+CREATE FUNCTION resolve_company(company_name TEXT) RETURNS BIGINT AS $$
+DECLARE
+	normalized TEXT := company_slug(company_name);
+	resolved_id BIGINT;
+BEGIN
+	IF normalized IS NULL OR normalized = '' THEN
+		RETURN NULL;
+	END IF;
+
+	SELECT id INTO resolved_id FROM companies WHERE slug = normalized;
+	IF resolved_id IS NULL THEN
+		SELECT company_id INTO resolved_id FROM company_aliases WHERE slug = normalized;
+	END IF;
+	IF resolved_id IS NULL THEN
+		-- High enough to catch typos like "gogle" without folding "meta" into "metal"
+		SELECT id INTO resolved_id
+		FROM companies
+		WHERE SIMILARITY(slug, normalized) >= 0.6
+		ORDER BY SIMILARITY(slug, normalized) DESC, id
+		LIMIT 1;
+	END IF;
+	IF resolved_id IS NULL THEN
+		INSERT INTO companies (name, slug) VALUES (TRIM(company_name), normalized)
+		ON CONFLICT (slug) DO NOTHING
+		RETURNING id INTO resolved_id;
+	END IF;
+	IF resolved_id IS NULL THEN
+		-- Created concurrently between the lookup and the insert
+		SELECT id INTO resolved_id FROM companies WHERE slug = normalized;
+	END IF;
+
+	RETURN resolved_id;
+END
+$$ LANGUAGE plpgsql;
+
+ALTER TABLE experience
+	ADD COLUMN IF NOT EXISTS company_id BIGINT REFERENCES companies (id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS experience_company_id_idx ON experience (company_id);
+
+-- Backfill: one company per slug, named after its most common spelling
+INSERT INTO companies (name, slug)
+SELECT MODE() WITHIN GROUP (ORDER BY TRIM(company_name)), company_slug(company_name)
+FROM experience
+WHERE company_slug(company_name) <> ''
+GROUP BY company_slug(company_name);
+
+UPDATE experience e
+SET company_id = c.id
+FROM companies c
+WHERE c.slug = company_slug(e.company_name);
+
+-- Every write path, including restoring revisions and publishing drafts from JSON snapshots, goes through
+-- here, so an entry without a known company always gets one resolved from its name
+CREATE FUNCTION experience_resolve_company() RETURNS TRIGGER AS $$
+BEGIN
+	IF NEW.company_id IS NULL OR NOT EXISTS (SELECT 1 FROM companies WHERE id = NEW.company_id) THEN
+		NEW.company_id := resolve_company(NEW.company_name);
+	END IF;
+
+	RETURN NEW;
+END
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER experience_resolve_company
+BEFORE INSERT OR UPDATE ON experience
+FOR EACH ROW EXECUTE FUNCTION experience_resolve_company();
@@ -0,0 +1,2 @@
+ALTER TABLE experience
+	DROP COLUMN IF EXISTS position;
@@ -0,0 +1,3 @@
+-- Manual ordering like education; entries without a position fall back to the newest first
+ALTER TABLE experience
+	ADD COLUMN IF NOT EXISTS position INTEGER;
@@ -0,0 +1,2 @@
+DROP FUNCTION IF EXISTS skill_experience_months;
+DROP TABLE IF EXISTS experience_skills;
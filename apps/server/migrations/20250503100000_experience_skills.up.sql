@@ -0,0 +1,23 @@
+-- Skills used in a role, from the same catalog as user_skills
+CREATE TABLE IF NOT EXISTS experience_skills (
+	experience_id BIGINT NOT NULL REFERENCES experience (id) ON DELETE CASCADE,
+	skill_id BIGINT NOT NULL REFERENCES skills (id) ON DELETE CASCADE,
+	PRIMARY KEY (experience_id, skill_id)
+);
+
+CREATE INDEX IF NOT EXISTS experience_skills_skill_id_idx ON experience_skills (skill_id);
+
+-- Like experience_months(), counting only the roles the skill was used in
+CREATE FUNCTION skill_experience_months(experience_user_id BIGINT, experience_skill_id BIGINT) RETURNS INTEGER AS $$
+	SELECT COALESCE(SUM(UPPER(months) - LOWER(months)), 0)::INTEGER
+	FROM UNNEST((
+		SELECT RANGE_AGG(INT4RANGE(
+			year_month_index(e.start_date),
+			year_month_index(COALESCE(e.end_date, TO_CHAR(CURRENT_DATE, 'YYYY-MM')::year_month)),
+			'[]'
+		))
+		FROM experience e
+		JOIN experience_skills es ON es.experience_id = e.id
+		WHERE e.user_id = experience_user_id AND es.skill_id = experience_skill_id
+	)) AS months
+$$ LANGUAGE SQL STABLE;
@@ -0,0 +1,6 @@
+-- Sanitized HTML of markdown fields, keyed by the MD5 of the renderer version and the source text
+CREATE TABLE IF NOT EXISTS markdown_renders (
+	source_hash CHAR(32) PRIMARY KEY,
+	html TEXT NOT NULL,
+	created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS experience_verifications;
+DROP TYPE IF EXISTS verification_status;
@@ -0,0 +1,20 @@
+CREATE TYPE verification_status AS ENUM ('pending', 'accepted', 'declined', 'revoked');
+
+-- A coworker vouching for an experience entry. The company is the one the entry listed when verification was
+-- asked for, so moving the entry to another company stops the verification from counting.
+CREATE TABLE IF NOT EXISTS experience_verifications (
+	id BIGSERIAL PRIMARY KEY,
+	experience_id BIGINT NOT NULL REFERENCES experience (id) ON DELETE CASCADE,
+	company_id BIGINT NOT NULL REFERENCES companies (id) ON DELETE CASCADE,
+	requester_id BIGINT NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+	verifier_id BIGINT NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+	status verification_status NOT NULL DEFAULT 'pending',
+	created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	responded_at TIMESTAMPTZ,
+	-- Asking again after a decline or a revoke isn't allowed
+	UNIQUE (experience_id, verifier_id),
+	CONSTRAINT not_self_verified CHECK (requester_id <> verifier_id)
+);
+
+CREATE INDEX IF NOT EXISTS experience_verifications_verifier_id_idx ON experience_verifications (verifier_id, status);
+CREATE INDEX IF NOT EXISTS experience_verifications_requester_id_idx ON experience_verifications (requester_id, created_at);
@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS work_emails;
+DROP TABLE IF EXISTS company_domain_claims;
+DROP TABLE IF EXISTS company_domains;
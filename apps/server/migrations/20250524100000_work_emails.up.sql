@@ -0,0 +1,35 @@
+-- Email domains of a company's employees, mapped by admins or claimed with a DNS TXT record
+CREATE TABLE IF NOT EXISTS company_domains (
+	domain VARCHAR(253) PRIMARY KEY,
+	company_id BIGINT NOT NULL REFERENCES companies (id) ON DELETE CASCADE,
+	created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS company_domains_company_id_idx ON company_domains (company_id);
+
+-- A domain waiting for its token to show up in a TXT record
+CREATE TABLE IF NOT EXISTS company_domain_claims (
+	company_id BIGINT NOT NULL REFERENCES companies (id) ON DELETE CASCADE,
+	domain VARCHAR(253) NOT NULL,
+	user_id BIGINT NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+	token VARCHAR(64) NOT NULL,
+	created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	PRIMARY KEY (company_id, domain)
+);
+
+-- An address at a company's domain proving an experience entry. The entry counts as verified until
+-- valid_until, and only while it still lists the company the address was confirmed for.
+CREATE TABLE IF NOT EXISTS work_emails (
+	experience_id BIGINT PRIMARY KEY REFERENCES experience (id) ON DELETE CASCADE,
+	user_id BIGINT NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+	company_id BIGINT NOT NULL REFERENCES companies (id) ON DELETE CASCADE,
+	email VARCHAR(255) NOT NULL,
+	code CHAR(6),
+	code_sent_at TIMESTAMPTZ,
+	code_expires_at TIMESTAMPTZ,
+	failed_attempts INTEGER NOT NULL DEFAULT 0,
+	verified_at TIMESTAMPTZ,
+	valid_until TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS work_emails_company_id_idx ON work_emails (company_id, valid_until);
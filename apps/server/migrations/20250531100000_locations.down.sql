@@ -0,0 +1,17 @@
+DROP TRIGGER IF EXISTS users_resolve_location ON users;
+DROP TRIGGER IF EXISTS experience_resolve_location ON experience;
+DROP FUNCTION IF EXISTS resolve_location_id;
+
+ALTER TABLE users
+	DROP COLUMN IF EXISTS location_id,
+	DROP COLUMN IF EXISTS location_name;
+
+ALTER TABLE experience
+	DROP COLUMN IF EXISTS location_id;
+
+DROP FUNCTION IF EXISTS resolve_location;
+DROP FUNCTION IF EXISTS location_key;
+DROP TABLE IF EXISTS location_names;
+DROP TABLE IF EXISTS locations;
+DROP TABLE IF EXISTS regions;
+DROP TABLE IF EXISTS countries;
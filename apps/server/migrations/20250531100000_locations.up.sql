@@ -0,0 +1,125 @@
+-- Filled from the bundled GeoNames dataset when the server starts, see locations::dataset
+CREATE TABLE IF NOT EXISTS countries (
+	code CHAR(2) PRIMARY KEY,
+	name VARCHAR(100) NOT NULL,
+	-- Other names the country goes by, like "UK" or "USA"
+	aliases TEXT[] NOT NULL DEFAULT '{}'
+);
+
+CREATE TABLE IF NOT EXISTS regions (
+	-- GeoNames admin1 code, the country code and the region's own code like "US.CA"
+	code VARCHAR(20) PRIMARY KEY,
+	country_code CHAR(2) NOT NULL REFERENCES countries (code) ON DELETE CASCADE,
+	name VARCHAR(200) NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS locations (
+	-- GeoNames id, stable across dataset updates
+	id BIGINT PRIMARY KEY,
+	name VARCHAR(200) NOT NULL,
+	country_code CHAR(2) NOT NULL REFERENCES countries (code) ON DELETE CASCADE,
+	region_code VARCHAR(20) REFERENCES regions (code) ON DELETE SET NULL,
+	latitude DOUBLE PRECISION NOT NULL,
+	longitude DOUBLE PRECISION NOT NULL,
+	timezone VARCHAR(40) NOT NULL,
+	population BIGINT NOT NULL DEFAULT 0
+);
+
+-- Every name a location is matched on, normalized with location_key(): its own, ASCII and alternate names,
+-- plus the initials of multi-word names like "sf" for San Francisco
+CREATE TABLE IF NOT EXISTS location_names (
+	location_id BIGINT NOT NULL REFERENCES locations (id) ON DELETE CASCADE,
+	name VARCHAR(200) NOT NULL,
+	-- Initials only ever match exactly, "sf" shouldn't pull in every two letter typo
+	is_abbreviation BOOLEAN NOT NULL DEFAULT FALSE,
+	PRIMARY KEY (location_id, name)
+);
+
+CREATE INDEX IF NOT EXISTS location_names_name_trgm_idx ON location_names USING GIN (name gin_trgm_ops);
+
+-- "Mountain-View", "mountain view " and "Mountain View 94043" all become "mountain view"
+CREATE FUNCTION location_key(name TEXT) RETURNS TEXT AS $$
+	SELECT TRIM(REGEXP_REPLACE(LOWER(name), '[^[:alpha:]]+', ' ', 'g'))
+$$ LANGUAGE SQL IMMUTABLE STRICT;
+
+-- Finds the city a free text location refers to. The part before the first comma names the city, the rest
+-- ("CA", "California", "US", "United States") has to match its region or country when given. Exact names win
+-- over initials, initials over similar names, and the most populated city settles ties.
+CREATE FUNCTION resolve_location(location_name TEXT) RETURNS BIGINT AS $$
+DECLARE
+	city TEXT := location_key(SPLIT_PART(location_name, ',', 1));
+	qualifiers TEXT[];
+	resolved_id BIGINT;
+BEGIN
+	IF city IS NULL OR city = '' THEN
+		RETURN NULL;
+	END IF;
+
+	SELECT COALESCE(ARRAY_AGG(qualifier), '{}') INTO qualifiers
+	FROM (
+		SELECT location_key(part) AS qualifier
+		FROM UNNEST((STRING_TO_ARRAY(location_name, ','))[2:]) AS part
+	) AS parts
+	WHERE qualifier <> '';
+
+	SELECT l.id INTO resolved_id
+	FROM location_names n
+	JOIN locations l ON l.id = n.location_id
+	JOIN countries c ON c.code = l.country_code
+	LEFT JOIN regions r ON r.code = l.region_code
+	CROSS JOIN LATERAL (
+		SELECT COUNT(*) AS matched
+		FROM UNNEST(qualifiers) AS qualifier
+		WHERE qualifier IN (
+			LOWER(SPLIT_PART(r.code, '.', 2)),
+			location_key(r.name),
+			LOWER(c.code),
+			location_key(c.name)
+		)
+		OR qualifier IN (SELECT location_key(alias) FROM UNNEST(c.aliases) AS alias)
+	) AS qualified
+	WHERE
+		(n.name = city OR (NOT n.is_abbreviation AND n.name % city AND SIMILARITY(n.name, city) >= 0.6))
+		AND (CARDINALITY(qualifiers) = 0 OR qualified.matched > 0)
+	ORDER BY
+		qualified.matched DESC,
+		n.name = city AND NOT n.is_abbreviation DESC,
+		n.name = city DESC,
+		SIMILARITY(n.name, city) DESC,
+		l.population DESC,
+		l.id
+	LIMIT 1;
+
+	RETURN resolved_id;
+END
+$$ LANGUAGE plpgsql STABLE;
+
+ALTER TABLE experience
+	ADD COLUMN IF NOT EXISTS location_id BIGINT REFERENCES locations (id) ON DELETE SET NULL;
+
+ALTER TABLE users
+	ADD COLUMN IF NOT EXISTS location_name VARCHAR(255),
+	ADD COLUMN IF NOT EXISTS location_id BIGINT REFERENCES locations (id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS experience_location_id_idx ON experience (location_id);
+CREATE INDEX IF NOT EXISTS users_location_id_idx ON users (location_id);
+
+-- Like experience_resolve_company(), any write that leaves a row without a known location resolves one from
+-- its display text. Rows written before the dataset was loaded are picked up by the backfill.
+CREATE FUNCTION resolve_location_id() RETURNS TRIGGER AS $$
+BEGIN
+	IF NEW.location_id IS NULL OR NOT EXISTS (SELECT 1 FROM locations WHERE id = NEW.location_id) THEN
+		NEW.location_id := resolve_location(NEW.location_name);
+	END IF;
+
+	RETURN NEW;
+END
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER experience_resolve_location
+BEFORE INSERT OR UPDATE OF location_name, location_id ON experience
+FOR EACH ROW EXECUTE FUNCTION resolve_location_id();
+
+CREATE TRIGGER users_resolve_location
+BEFORE INSERT OR UPDATE OF location_name, location_id ON users
+FOR EACH ROW EXECUTE FUNCTION resolve_location_id();
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS user_skills_skill_id_idx;
+DROP INDEX IF EXISTS skills_name_prefix_idx;
+DROP INDEX IF EXISTS skills_name_trgm_idx;
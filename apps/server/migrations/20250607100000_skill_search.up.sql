@@ -0,0 +1,8 @@
+-- Similar names for skill search, with trigrams matching anywhere in the name
+CREATE INDEX IF NOT EXISTS skills_name_trgm_idx ON skills USING GIN (LOWER(name) gin_trgm_ops);
+
+-- Prefixes too short to have a trigram, like "c" or "go"
+CREATE INDEX IF NOT EXISTS skills_name_prefix_idx ON skills (LOWER(name) text_pattern_ops);
+
+-- How many users list a skill, which search results are ranked by
+CREATE INDEX IF NOT EXISTS user_skills_skill_id_idx ON user_skills (skill_id);
@@ -0,0 +1,9 @@
+DROP INDEX IF EXISTS skills_name_lower_idx;
+DROP TABLE IF EXISTS skill_aliases;
+
+ALTER TABLE skills
+	DROP CONSTRAINT IF EXISTS skills_not_own_parent,
+	DROP COLUMN IF EXISTS parent_id,
+	DROP COLUMN IF EXISTS category;
+
+DROP TYPE IF EXISTS skill_category;
@@ -0,0 +1,55 @@
+CREATE TYPE skill_category AS ENUM ('language', 'framework', 'tool', 'soft', 'design');
+
+ALTER TABLE skills
+	ADD COLUMN IF NOT EXISTS category skill_category,
+	-- A broader skill this one is part of, like Rust for Axum
+	ADD COLUMN IF NOT EXISTS parent_id BIGINT REFERENCES skills (id) ON DELETE SET NULL,
+	ADD CONSTRAINT skills_not_own_parent CHECK (parent_id <> id);
+
+CREATE INDEX IF NOT EXISTS skills_parent_id_idx ON skills (parent_id);
+
+-- Other names a skill goes by, lowercased, like "js" for Javascript. Skills merged into another one leave
+-- their names here, so they keep resolving to it.
+CREATE TABLE IF NOT EXISTS skill_aliases (
+	name VARCHAR(255) PRIMARY KEY,
+	skill_id BIGINT NOT NULL REFERENCES skills (id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS skill_aliases_skill_id_idx ON skill_aliases (skill_id);
+CREATE INDEX IF NOT EXISTS skill_aliases_name_trgm_idx ON skill_aliases USING GIN (name gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS skill_aliases_name_prefix_idx ON skill_aliases (name text_pattern_ops);
+
+-- Backfill: skills only differing in case, like "Javascript" and "JavaScript", become the oldest of them
+CREATE TEMPORARY TABLE skill_duplicates AS
+SELECT id, FIRST_VALUE(id) OVER (PARTITION BY LOWER(name) ORDER BY id) AS skill_id
+FROM skills;
+
+DELETE FROM skill_duplicates WHERE id = skill_id;
+
+INSERT INTO user_skills (user_id, skill_id)
+SELECT us.user_id, d.skill_id FROM user_skills us JOIN skill_duplicates d ON d.id = us.skill_id
+ON CONFLICT DO NOTHING;
+
+INSERT INTO experience_skills (experience_id, skill_id)
+SELECT es.experience_id, d.skill_id FROM experience_skills es JOIN skill_duplicates d ON d.id = es.skill_id
+ON CONFLICT DO NOTHING;
+
+INSERT INTO certification_skills (certification_id, skill_id)
+SELECT cs.certification_id, d.skill_id FROM certification_skills cs JOIN skill_duplicates d ON d.id = cs.skill_id
+ON CONFLICT DO NOTHING;
+
+DELETE FROM skills WHERE id IN (SELECT id FROM skill_duplicates);
+
+DROP TABLE skill_duplicates;
+
+CREATE UNIQUE INDEX IF NOT EXISTS skills_name_lower_idx ON skills (LOWER(name));
+
+UPDATE skills SET category = 'language' WHERE LOWER(name) IN ('rust', 'go', 'python', 'javascript', 'typescript');
+UPDATE skills SET category = 'design' WHERE LOWER(name) IN ('ui/ux', 'graphic design');
+
+INSERT INTO skill_aliases (name, skill_id)
+SELECT aliases.name, skills.id
+FROM (VALUES ('golang', 'go'), ('js', 'javascript'), ('ts', 'typescript'), ('py', 'python')) AS aliases (name, skill_name)
+JOIN skills ON LOWER(skills.name) = aliases.skill_name
+WHERE NOT EXISTS (SELECT 1 FROM skills taken WHERE LOWER(taken.name) = aliases.name)
+ON CONFLICT DO NOTHING;
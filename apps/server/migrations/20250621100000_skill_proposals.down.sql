@@ -0,0 +1,8 @@
+DROP TABLE IF EXISTS skill_proposals;
+
+DELETE FROM skills WHERE status <> 'approved';
+
+ALTER TABLE skills
+	DROP COLUMN IF EXISTS status;
+
+DROP TYPE IF EXISTS skill_status;
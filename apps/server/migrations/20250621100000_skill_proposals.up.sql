@@ -0,0 +1,17 @@
+-- Skills proposed by users stay out of the catalog until a moderator approves them, or enough users propose them
+CREATE TYPE skill_status AS ENUM ('pending', 'approved', 'rejected');
+
+ALTER TABLE skills
+	ADD COLUMN IF NOT EXISTS status skill_status NOT NULL DEFAULT 'approved';
+
+CREATE INDEX IF NOT EXISTS skills_pending_idx ON skills (id) WHERE status = 'pending';
+
+-- Who proposed a skill that isn't in the catalog yet, one row per user however they spelled it
+CREATE TABLE IF NOT EXISTS skill_proposals (
+	skill_id BIGINT NOT NULL REFERENCES skills (id) ON DELETE CASCADE,
+	user_id BIGINT NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+	created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	PRIMARY KEY (skill_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS skill_proposals_user_id_idx ON skill_proposals (user_id);
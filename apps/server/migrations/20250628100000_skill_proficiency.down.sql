@@ -0,0 +1,8 @@
+ALTER TABLE user_skills
+	DROP COLUMN IF EXISTS is_pinned,
+	DROP COLUMN IF EXISTS position,
+	DROP COLUMN IF EXISTS last_used,
+	DROP COLUMN IF EXISTS years,
+	DROP COLUMN IF EXISTS proficiency;
+
+DROP TYPE IF EXISTS skill_proficiency;
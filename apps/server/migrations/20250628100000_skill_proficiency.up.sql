@@ -0,0 +1,11 @@
+-- Ordered from weakest to strongest, so levels compare
+CREATE TYPE skill_proficiency AS ENUM ('beginner', 'intermediate', 'advanced', 'expert');
+
+-- All optional, a skill can still be listed by name only. Manual ordering like experience, skills without a
+-- position fall back to their names, and pinned skills are the user's top ones shown first.
+ALTER TABLE user_skills
+	ADD COLUMN IF NOT EXISTS proficiency skill_proficiency,
+	ADD COLUMN IF NOT EXISTS years INTEGER CHECK (years BETWEEN 0 AND 70),
+	ADD COLUMN IF NOT EXISTS last_used year_month,
+	ADD COLUMN IF NOT EXISTS position INTEGER,
+	ADD COLUMN IF NOT EXISTS is_pinned BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS work_email_events;
+
+DROP TYPE IF EXISTS work_email_event;
@@ -0,0 +1,14 @@
+CREATE TYPE work_email_event AS ENUM ('code_sent', 'code_failed');
+
+-- Codes sent and wrong codes entered, kept for a day so the daily limits hold across resends and deleted entries
+CREATE TABLE IF NOT EXISTS work_email_events (
+	id BIGSERIAL PRIMARY KEY,
+	user_id BIGINT NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+	email VARCHAR(255) NOT NULL,
+	event work_email_event NOT NULL,
+	created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS work_email_events_user_id_idx ON work_email_events (user_id, event, created_at);
+CREATE INDEX IF NOT EXISTS work_email_events_email_idx ON work_email_events (email, event, created_at);
+CREATE INDEX IF NOT EXISTS work_email_events_created_at_idx ON work_email_events (created_at);
@@ -0,0 +1,16 @@
+DELETE FROM profile_revisions
+WHERE section = 'custom_sections';
+
+ALTER TABLE profile_revisions
+	DROP CONSTRAINT IF EXISTS profile_revisions_section_check,
+	ADD CONSTRAINT profile_revisions_section_check CHECK (
+		section IN (
+			'profile',
+			'experience',
+			'education',
+			'skills',
+			'languages',
+			'certifications',
+			'translations'
+		)
+	);
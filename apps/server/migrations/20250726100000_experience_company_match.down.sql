@@ -0,0 +1 @@
+DROP FUNCTION IF EXISTS company_matches_name;
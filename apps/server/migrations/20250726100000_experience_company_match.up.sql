@@ -0,0 +1,10 @@
+-- Whether a company is the one a free text name refers to, by its slug or the slug of a company merged into
+-- it. Company ids sent by clients are only kept when they match the entry's name, so an entry can't be
+-- attached to a company it doesn't name, and renaming an entry doesn't keep the previous company.
+CREATE FUNCTION company_matches_name(matched_company_id BIGINT, company_name TEXT) RETURNS BOOLEAN AS $$
+	SELECT EXISTS (
+		SELECT 1 FROM companies WHERE id = matched_company_id AND slug = company_slug(company_name)
+		UNION ALL
+		SELECT 1 FROM company_aliases WHERE company_id = matched_company_id AND slug = company_slug(company_name)
+	)
+$$ LANGUAGE SQL STABLE;
@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS markdown_renders_last_used_at_idx;
+
+ALTER TABLE markdown_renders
+	DROP COLUMN IF EXISTS last_used_at;
@@ -0,0 +1,6 @@
+-- Renders of text that was edited away, or of an old renderer version, are never read again. Reads refresh
+-- this at most daily, and renders left unused for a while are pruned.
+ALTER TABLE markdown_renders
+	ADD COLUMN IF NOT EXISTS last_used_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP;
+
+CREATE INDEX IF NOT EXISTS markdown_renders_last_used_at_idx ON markdown_renders (last_used_at);
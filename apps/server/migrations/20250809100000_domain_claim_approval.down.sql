@@ -0,0 +1,2 @@
+ALTER TABLE company_domain_claims
+	DROP COLUMN IF EXISTS verified_at;
@@ -0,0 +1,4 @@
+-- Proving control of a domain doesn't prove it belongs to the company. Claims of a domain outside the company's
+-- website stay here once their TXT record shows up, until an admin approves or rejects them.
+ALTER TABLE company_domain_claims
+	ADD COLUMN IF NOT EXISTS verified_at TIMESTAMPTZ;
@@ -4,8 +4,8 @@ use std::time::Duration;
 use axum::http::{header, HeaderValue, Method, StatusCode};
 use axum::response::{IntoResponse, Response};
 use axum::routing::IntoMakeService;
-use axum::Json;
 use axum::Router;
+use axum::{Extension, Json};
 use log::debug;
 use thiserror::Error;
 use tower_cookies::CookieManagerLayer;
@@ -14,35 +14,84 @@ use tower_http::cors::CorsLayer;
 use tower_http::propagate_header::PropagateHeaderLayer;
 use tower_http::trace::TraceLayer;
 
+use crate::certifications::routes::{CertificationsApiError, CertificationsState};
+use crate::companies::routes::{CompaniesApiError, CompaniesState};
+use crate::completeness::routes::{CompletenessApiError, CompletenessState};
 use crate::database::{self};
+use crate::drafts::routes::{DraftsApiError, DraftsState};
+use crate::education::routes::{EducationApiError, EducationState};
 use crate::experience::routes::{ExperienceApiError, ExperienceState};
+use crate::history::routes::{HistoryApiError, HistoryState};
+use crate::languages::routes::{LanguagesApiError, LanguagesState};
+use crate::locations::routes::{LocationsApiError, LocationsState};
+use crate::resume::routes::{ResumeApiError, ResumeState};
+use crate::search::routes::{SearchApiError, SearchState};
+use crate::sections::routes::{SectionsApiError, SectionsState};
 use crate::settings::SETTINGS;
 use crate::skills::routes::{SkillsApiError, SkillsState};
 use crate::users::routes::{UsersApiError, UsersState};
-use crate::{experience, skills, users};
+use crate::verifications::routes::{VerificationsApiError, VerificationsState};
+use crate::work_emails::routes::{WorkEmailsApiError, WorkEmailsState};
+use crate::{
+	certifications, companies, completeness, drafts, education, experience, history, languages, locations, resume,
+	search, sections, services, skills, users, verifications, work_emails,
+};
 
 pub async fn create_app() -> IntoMakeService<Router> {
 	let database = Arc::new(database::Database::init().await.unwrap());
 
+	certifications::expiry::spawn(&database);
+	work_emails::reverification::spawn(&database);
+	locations::backfill::spawn(&database);
+	services::markdown::spawn_pruning(&database);
+
 	let users_state = UsersState::new(&database);
 	let experience_state = ExperienceState::new(&database);
 	let skills_state = SkillsState::new(&database);
+	let resume_state = ResumeState::new(&database);
+	let education_state = EducationState::new(&database);
+	let certifications_state = CertificationsState::new(&database);
+	let languages_state = LanguagesState::new(&database);
+	let search_state = SearchState::new(&database);
+	let history_state = HistoryState::new(&database);
+	let drafts_state = DraftsState::new(&database);
+	let sections_state = SectionsState::new(&database);
+	let completeness_state = CompletenessState::new(&database);
+	let companies_state = CompaniesState::new(&database);
+	let verifications_state = VerificationsState::new(&database);
+	let work_emails_state = WorkEmailsState::new(&database);
+	let locations_state = LocationsState::new(&database);
 
 	let router = Router::new()
 		.merge(users::routes::init().with_state(users_state))
 		.merge(experience::routes::init().with_state(experience_state))
 		.merge(skills::routes::init().with_state(skills_state))
+		.merge(resume::routes::init().with_state(resume_state))
+		.merge(education::routes::init().with_state(education_state))
+		.merge(certifications::routes::init().with_state(certifications_state))
+		.merge(languages::routes::init().with_state(languages_state))
+		.merge(search::routes::init().with_state(search_state))
+		.merge(history::routes::init().with_state(history_state))
+		.merge(drafts::routes::init().with_state(drafts_state))
+		.merge(sections::routes::init().with_state(sections_state))
+		.merge(completeness::routes::init().with_state(completeness_state))
+		.merge(companies::routes::init().with_state(companies_state))
+		.merge(verifications::routes::init().with_state(verifications_state))
+		.merge(work_emails::routes::init().with_state(work_emails_state))
+		.merge(locations::routes::init().with_state(locations_state))
+		// Lets middleware that has no state of its own, like the admin check, reach the database
+		.layer(Extension(database))
 		.layer(
 			TraceLayer::new_for_http()
 				.on_request(|request: &axum::http::Request<_>, _span: &tracing::Span| {
 					let method = request.method();
 					let uri = request.uri();
-					debug!("{:?} {:?}", method, uri);
+					debug!("{method:?} {uri:?}");
 				})
 				.on_response(
 					|response: &axum::response::Response<_>, duration: Duration, _span: &tracing::Span| {
 						let status = response.status();
-						debug!("{:?} {:?}", status, duration);
+						debug!("{status:?} {duration:?}");
 					},
 				),
 		)
@@ -79,6 +128,45 @@ pub enum ApiError {
 
 	#[error("{0}")]
 	Skills(#[from] SkillsApiError),
+
+	#[error("{0}")]
+	Resume(#[from] ResumeApiError),
+
+	#[error("{0}")]
+	Education(#[from] EducationApiError),
+
+	#[error("{0}")]
+	Certifications(#[from] CertificationsApiError),
+
+	#[error("{0}")]
+	Languages(#[from] LanguagesApiError),
+
+	#[error("{0}")]
+	Search(#[from] SearchApiError),
+
+	#[error("{0}")]
+	History(#[from] HistoryApiError),
+
+	#[error("{0}")]
+	Drafts(#[from] DraftsApiError),
+
+	#[error("{0}")]
+	Sections(#[from] SectionsApiError),
+
+	#[error("{0}")]
+	Completeness(#[from] CompletenessApiError),
+
+	#[error("{0}")]
+	Companies(#[from] CompaniesApiError),
+
+	#[error("{0}")]
+	Verifications(#[from] VerificationsApiError),
+
+	#[error("{0}")]
+	WorkEmails(#[from] WorkEmailsApiError),
+
+	#[error("{0}")]
+	Locations(#[from] LocationsApiError),
 }
 
 impl IntoResponse for ApiError {
@@ -87,6 +175,19 @@ impl IntoResponse for ApiError {
 			Self::Users(error) => error.into_response(),
 			Self::Experience(error) => error.into_response(),
 			Self::Skills(error) => error.into_response(),
+			Self::Resume(error) => error.into_response(),
+			Self::Education(error) => error.into_response(),
+			Self::Certifications(error) => error.into_response(),
+			Self::Languages(error) => error.into_response(),
+			Self::Search(error) => error.into_response(),
+			Self::History(error) => error.into_response(),
+			Self::Drafts(error) => error.into_response(),
+			Self::Sections(error) => error.into_response(),
+			Self::Completeness(error) => error.into_response(),
+			Self::Companies(error) => error.into_response(),
+			Self::Verifications(error) => error.into_response(),
+			Self::WorkEmails(error) => error.into_response(),
+			Self::Locations(error) => error.into_response(),
 		}
 	}
 }
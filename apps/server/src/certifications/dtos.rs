@@ -0,0 +1,33 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError};
+
+use crate::validation::{field_error, validate_http_url};
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+#[validate(schema(function = "validate_dates"))]
+pub struct CertificationDto {
+	#[validate(length(min = 1, max = 255, message = "Wrong name length"))]
+	pub name: String,
+	#[validate(length(min = 1, max = 255, message = "Wrong issuing organization length"))]
+	pub issuing_organization: String,
+	pub issue_date: NaiveDate,
+	pub expiry_date: Option<NaiveDate>,
+	#[validate(length(min = 1, max = 255, message = "Wrong credential id length"))]
+	pub credential_id: Option<String>,
+	#[validate(custom(function = "validate_http_url"), length(max = 2048))]
+	pub credential_url: Option<String>,
+	#[serde(default)]
+	pub skill_ids: Vec<i64>,
+}
+
+fn validate_dates(certification_dto: &CertificationDto) -> Result<(), ValidationError> {
+	match certification_dto.expiry_date {
+		Some(expiry_date) if expiry_date < certification_dto.issue_date => Err(field_error(
+			"expiryDate",
+			ValidationError::new("Expiry date must not be before the issue date"),
+		)),
+		_ => Ok(()),
+	}
+}
@@ -0,0 +1,62 @@
+//! Background job reminding users about certifications that are about to expire
+
+use std::{sync::Arc, time::Duration};
+
+use crate::{database::Database, services::email::EmailService};
+
+use super::repository::CertificationsRepository;
+
+/// How far ahead of the expiry date the reminder goes out
+const REMINDER_WINDOW_DAYS: i32 = 30;
+
+const CHECK_INTERVAL: Duration = Duration::from_hours(1);
+
+pub fn spawn(database: &Arc<Database>) {
+	let certifications_repository = CertificationsRepository::new(database);
+	let email_service = EmailService::new();
+
+	tokio::spawn(async move {
+		let mut interval = tokio::time::interval(CHECK_INTERVAL);
+
+		loop {
+			interval.tick().await;
+
+			if let Err(error) = send_reminders(&certifications_repository, &email_service).await {
+				log::error!("Failed to send certification expiry reminders: {error:?}");
+			}
+		}
+	});
+}
+
+async fn send_reminders(
+	certifications_repository: &CertificationsRepository,
+	email_service: &EmailService,
+) -> anyhow::Result<()> {
+	let certifications = certifications_repository.get_expiring(REMINDER_WINDOW_DAYS).await?;
+
+	for certification in certifications {
+		let email_service = email_service.clone();
+		let (email, name, expiry_date) = (certification.email, certification.name, certification.expiry_date);
+
+		// SMTP delivery blocks, keep it off the async workers
+		let sent = tokio::task::spawn_blocking(move || {
+			email_service.send_certification_expiry_email(&email, &name, expiry_date)
+		})
+		.await?;
+
+		// A failed delivery is retried on the next run since the reminder stays unmarked
+		match sent {
+			Ok(()) => {
+				certifications_repository
+					.mark_reminder_sent(&certification.id, certification.expiry_date)
+					.await?;
+			}
+			Err(error) => log::error!(
+				"Failed to send expiry reminder for certification {}: {error}",
+				certification.id
+			),
+		}
+	}
+
+	Ok(())
+}
@@ -0,0 +1,4 @@
+pub mod dtos;
+pub mod expiry;
+pub mod repository;
+pub mod routes;
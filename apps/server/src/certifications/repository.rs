@@ -0,0 +1,207 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, Context};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use sqlx::{types::Json, PgExecutor, Postgres, Transaction};
+
+use crate::{database::Database, skills::repository::Skill};
+
+use super::dtos::CertificationDto;
+
+#[derive(Clone)]
+pub struct CertificationsRepository {
+	pub(crate) database: Arc<Database>,
+}
+
+impl CertificationsRepository {
+	pub fn new(database: &Arc<Database>) -> Self {
+		Self {
+			database: Arc::clone(database),
+		}
+	}
+
+	pub async fn create(
+		transaction: &mut Transaction<'_, Postgres>,
+		user_id: &i64,
+		certification_dto: CertificationDto,
+	) -> anyhow::Result<i64> {
+		let id = sqlx::query_scalar!(
+			r#"
+				INSERT INTO certifications (user_id, name, issuing_organization, issue_date, expiry_date, credential_id, credential_url)
+				VALUES ($1, $2, $3, $4, $5, $6, $7)
+				RETURNING id
+			"#,
+			user_id,
+			certification_dto.name,
+			certification_dto.issuing_organization,
+			certification_dto.issue_date,
+			certification_dto.expiry_date,
+			certification_dto.credential_id,
+			certification_dto.credential_url
+		)
+		.fetch_one(&mut **transaction)
+		.await
+		.map_err(|error| anyhow!(error).context("Failed to create certification"))?;
+
+		Self::set_skills(&mut **transaction, &id, &certification_dto.skill_ids).await?;
+
+		Ok(id)
+	}
+
+	/// Returns `false` when the certification doesn't exist or belongs to someone else
+	pub async fn update(
+		transaction: &mut Transaction<'_, Postgres>,
+		id: &i64,
+		user_id: &i64,
+		certification_dto: CertificationDto,
+	) -> anyhow::Result<bool> {
+		let result = sqlx::query!(
+			r#"
+				UPDATE certifications
+				SET name = $3, issuing_organization = $4, issue_date = $5, expiry_date = $6, credential_id = $7, credential_url = $8
+				WHERE id = $1 AND user_id = $2
+			"#,
+			id,
+			user_id,
+			certification_dto.name,
+			certification_dto.issuing_organization,
+			certification_dto.issue_date,
+			certification_dto.expiry_date,
+			certification_dto.credential_id,
+			certification_dto.credential_url
+		)
+		.execute(&mut **transaction)
+		.await
+		.map_err(|error| anyhow!(error).context("Failed to update certification"))?;
+
+		if result.rows_affected() == 0 {
+			return Ok(false);
+		}
+
+		sqlx::query!("DELETE FROM certification_skills WHERE certification_id = $1", id)
+			.execute(&mut **transaction)
+			.await
+			.context("Failed to delete certification skills")?;
+
+		Self::set_skills(&mut **transaction, id, &certification_dto.skill_ids).await?;
+
+		Ok(true)
+	}
+
+	/// Unknown skill ids are ignored rather than failing the whole request
+	async fn set_skills(executor: impl PgExecutor<'_>, id: &i64, skill_ids: &[i64]) -> anyhow::Result<()> {
+		sqlx::query!(
+			r#"
+				INSERT INTO certification_skills (certification_id, skill_id)
+				SELECT $1, skills.id FROM skills WHERE skills.id = ANY($2::BIGINT[])
+				ON CONFLICT DO NOTHING
+			"#,
+			id,
+			skill_ids
+		)
+		.execute(executor)
+		.await
+		.context("Failed to set certification skills")?;
+
+		Ok(())
+	}
+
+	pub async fn delete(executor: impl PgExecutor<'_>, id: &i64, user_id: &i64) -> anyhow::Result<()> {
+		sqlx::query!("DELETE FROM certifications WHERE id = $1 AND user_id = $2", id, user_id)
+			.execute(executor)
+			.await
+			.map_err(|error| anyhow!(error).context("Failed to delete certification"))?;
+
+		Ok(())
+	}
+
+	/// Certifications without an expiry date sort as if they never expire
+	pub async fn get_by_user_id(&self, user_id: i64) -> anyhow::Result<Vec<Certification>> {
+		let certifications = sqlx::query_as!(
+			Certification,
+			r#"
+				SELECT
+					c.id,
+					c.name,
+					c.issuing_organization,
+					c.issue_date,
+					c.expiry_date,
+					c.credential_id,
+					c.credential_url,
+					COALESCE(c.expiry_date < CURRENT_DATE, FALSE) AS "is_expired!",
+					COALESCE(
+						JSON_AGG(JSON_BUILD_OBJECT('id', s.id, 'name', s.name) ORDER BY s.name) FILTER (WHERE s.id IS NOT NULL),
+						'[]'
+					) AS "skills!: Json<Vec<Skill>>"
+				FROM certifications c
+				LEFT JOIN certification_skills cs ON cs.certification_id = c.id
+				LEFT JOIN skills s ON s.id = cs.skill_id
+				WHERE c.user_id = $1
+				GROUP BY c.id
+				ORDER BY c.issue_date DESC
+			"#,
+			user_id
+		)
+		.fetch_all(&*self.database.pool)
+		.await
+		.map_err(|error| anyhow!(error).context("Failed to get certifications by user id"))?;
+
+		Ok(certifications)
+	}
+
+	/// Certifications expiring within the given window whose current expiry date hasn't been reminded about yet
+	pub async fn get_expiring(&self, within_days: i32) -> anyhow::Result<Vec<ExpiringCertification>> {
+		let certifications = sqlx::query_as!(
+			ExpiringCertification,
+			r#"
+				SELECT c.id, c.name, c.expiry_date AS "expiry_date!", u.email
+				FROM certifications c
+				JOIN users u ON u.id = c.user_id
+				WHERE c.expiry_date BETWEEN CURRENT_DATE AND CURRENT_DATE + $1::INTEGER
+					AND c.reminder_sent_for IS DISTINCT FROM c.expiry_date
+			"#,
+			within_days
+		)
+		.fetch_all(&*self.database.pool)
+		.await
+		.map_err(|error| anyhow!(error).context("Failed to get expiring certifications"))?;
+
+		Ok(certifications)
+	}
+
+	pub async fn mark_reminder_sent(&self, id: &i64, expiry_date: NaiveDate) -> anyhow::Result<()> {
+		sqlx::query!(
+			"UPDATE certifications SET reminder_sent_for = $2 WHERE id = $1",
+			id,
+			expiry_date
+		)
+		.execute(&*self.database.pool)
+		.await
+		.map_err(|error| anyhow!(error).context("Failed to mark certification reminder as sent"))?;
+
+		Ok(())
+	}
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Certification {
+	pub id: i64,
+	pub name: String,
+	pub issuing_organization: String,
+	pub issue_date: NaiveDate,
+	pub expiry_date: Option<NaiveDate>,
+	pub credential_id: Option<String>,
+	pub credential_url: Option<String>,
+	pub is_expired: bool,
+	pub skills: Json<Vec<Skill>>,
+}
+
+#[derive(Debug)]
+pub struct ExpiringCertification {
+	pub id: i64,
+	pub name: String,
+	pub expiry_date: NaiveDate,
+	pub email: String,
+}
@@ -0,0 +1,165 @@
+use std::sync::Arc;
+
+use axum::{
+	extract::{Path, State},
+	http::StatusCode,
+	middleware,
+	response::{IntoResponse, Response},
+	routing::{delete, get, post, put},
+	Extension, Json, Router,
+};
+use thiserror::Error;
+
+use crate::{
+	app::{ApiErrorResponse, ApiResult},
+	database::Database,
+	history::repository::{HistoryRepository, Section},
+	users::auth::{self, CurrentUser},
+	validation::ValidatedJson,
+};
+
+use super::{
+	dtos::CertificationDto,
+	repository::{Certification, CertificationsRepository},
+};
+
+#[derive(Clone)]
+pub struct CertificationsState {
+	pub certifications_repository: CertificationsRepository,
+	pub history_repository: HistoryRepository,
+}
+
+impl CertificationsState {
+	pub fn new(database: &Arc<Database>) -> Self {
+		Self {
+			certifications_repository: CertificationsRepository::new(database),
+			history_repository: HistoryRepository::new(database),
+		}
+	}
+}
+
+pub fn init() -> Router<CertificationsState> {
+	Router::new()
+		.route("/certifications", post(create_certification_route))
+		.route("/certifications/{certification_id}", put(update_certification_route))
+		.route("/certifications/{certification_id}", delete(delete_certification_route))
+		.route_layer(middleware::from_fn(auth::middleware))
+		.route("/{user_id}/certifications", get(user_certifications_route))
+}
+
+async fn create_certification_route(
+	Extension(current_user): Extension<CurrentUser>,
+	State(state): State<CertificationsState>,
+	ValidatedJson(request): ValidatedJson<CertificationDto>,
+) -> ApiResult<Json<i64>> {
+	let mut transaction = state
+		.history_repository
+		.track(&current_user.user_id, &current_user.user_id, &[Section::Certifications])
+		.await
+		.map_err(|_| CertificationsApiError::Create())?;
+
+	let certification_id = CertificationsRepository::create(&mut transaction, &current_user.user_id, request)
+		.await
+		.map_err(|_| CertificationsApiError::Create())?;
+
+	transaction
+		.commit()
+		.await
+		.map_err(|_| CertificationsApiError::Create())?;
+
+	Ok((StatusCode::CREATED, Json(certification_id)))
+}
+
+async fn update_certification_route(
+	Extension(current_user): Extension<CurrentUser>,
+	State(state): State<CertificationsState>,
+	Path(certification_id): Path<i64>,
+	ValidatedJson(request): ValidatedJson<CertificationDto>,
+) -> ApiResult<()> {
+	let mut transaction = state
+		.history_repository
+		.track(&current_user.user_id, &current_user.user_id, &[Section::Certifications])
+		.await
+		.map_err(|_| CertificationsApiError::Update())?;
+
+	let updated = CertificationsRepository::update(&mut transaction, &certification_id, &current_user.user_id, request)
+		.await
+		.map_err(|_| CertificationsApiError::Update())?;
+
+	if !updated {
+		return Err(CertificationsApiError::NotFound(certification_id).into());
+	}
+
+	transaction
+		.commit()
+		.await
+		.map_err(|_| CertificationsApiError::Update())?;
+
+	Ok((StatusCode::OK, ()))
+}
+
+async fn delete_certification_route(
+	Extension(current_user): Extension<CurrentUser>,
+	State(state): State<CertificationsState>,
+	Path(certification_id): Path<i64>,
+) -> ApiResult<()> {
+	let mut transaction = state
+		.history_repository
+		.track(&current_user.user_id, &current_user.user_id, &[Section::Certifications])
+		.await
+		.map_err(|_| CertificationsApiError::Delete())?;
+
+	CertificationsRepository::delete(&mut **transaction, &certification_id, &current_user.user_id)
+		.await
+		.map_err(|_| CertificationsApiError::Delete())?;
+
+	transaction
+		.commit()
+		.await
+		.map_err(|_| CertificationsApiError::Delete())?;
+
+	Ok((StatusCode::OK, ()))
+}
+
+async fn user_certifications_route(
+	Path(user_id): Path<i64>,
+	State(state): State<CertificationsState>,
+) -> ApiResult<Json<Vec<Certification>>> {
+	let certifications = state
+		.certifications_repository
+		.get_by_user_id(user_id)
+		.await
+		.map_err(|_| CertificationsApiError::GetUser())?;
+
+	Ok((StatusCode::OK, Json(certifications)))
+}
+
+#[derive(Debug, Error)]
+pub enum CertificationsApiError {
+	#[error("Certification not found: {0}")]
+	NotFound(i64),
+
+	#[error("Failed to get user certifications")]
+	GetUser(),
+
+	#[error("Failed to create certification")]
+	Create(),
+
+	#[error("Failed to update certification")]
+	Update(),
+
+	#[error("Failed to delete certification")]
+	Delete(),
+}
+
+impl IntoResponse for CertificationsApiError {
+	fn into_response(self) -> Response {
+		let status_code = match self {
+			Self::NotFound(_) => StatusCode::NOT_FOUND,
+			Self::GetUser() | Self::Create() | Self::Update() | Self::Delete() => StatusCode::INTERNAL_SERVER_ERROR,
+		};
+
+		log::error!("{self:?}");
+		ApiErrorResponse::new(status_code, self.to_string()).into_response()
+	}
+}
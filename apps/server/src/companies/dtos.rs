@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::{domains::validate_domain, experience::year_month::YearMonth, validation::validate_http_url};
+
+use super::repository::{Company, CompanySize, DomainClaim};
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct AutocompleteQuery {
+	#[validate(length(min = 1, max = 255, message = "Wrong query length"))]
+	pub q: String,
+	#[serde(default = "default_limit")]
+	#[validate(range(min = 1, max = 20, message = "Limit must be between 1 and 20"))]
+	pub limit: i64,
+}
+
+const fn default_limit() -> i64 {
+	10
+}
+
+/// Replaces every detail but the slug, which stays stable so links to the company page keep working
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateCompanyDto {
+	#[validate(length(min = 1, max = 255, message = "Wrong company name length"))]
+	pub name: String,
+	#[validate(custom(function = "validate_http_url"), length(max = 2048))]
+	pub logo_url: Option<String>,
+	#[validate(custom(function = "validate_http_url"), length(max = 2048))]
+	pub website: Option<String>,
+	#[validate(length(min = 1, max = 100, message = "Wrong industry length"))]
+	pub industry: Option<String>,
+	pub size: Option<CompanySize>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeCompaniesDto {
+	/// Companies folded into the one in the path; their members move over and their slugs become aliases
+	#[validate(length(min = 1, max = 50, message = "Between 1 and 50 duplicates can be merged at once"))]
+	pub duplicate_ids: Vec<i64>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaimDomainDto {
+	#[validate(custom(function = "validate_domain"))]
+	pub domain: String,
+}
+
+/// What to publish in DNS before checking the claim
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DomainClaimResponse {
+	pub domain: String,
+	pub record_type: &'static str,
+	pub record_name: String,
+	pub record_value: String,
+}
+
+impl From<DomainClaim> for DomainClaimResponse {
+	fn from(claim: DomainClaim) -> Self {
+		Self {
+			record_type: "TXT",
+			record_name: claim.record_name(),
+			record_value: claim.record_value(),
+			domain: claim.domain,
+		}
+	}
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompanyPageResponse {
+	pub company: Company,
+	/// Where employees' work email addresses are, for confirming their roles
+	pub domains: Vec<String>,
+	pub current_members: Vec<CompanyMember>,
+	pub past_members: Vec<CompanyMember>,
+}
+
+/// A user's most recent role at a company
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompanyMember {
+	pub user_id: i64,
+	pub username: String,
+	pub avatar_url: Option<String>,
+	pub occupation: String,
+	pub start_date: YearMonth,
+	pub end_date: Option<YearMonth>,
+	pub is_current: bool,
+}
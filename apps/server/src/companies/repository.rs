@@ -0,0 +1,528 @@
+use std::{cmp::Reverse, sync::Arc};
+
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use uuid::Uuid;
+
+use crate::{database::Database, experience::year_month::YearMonth};
+
+use super::dtos::{CompanyMember, UpdateCompanyDto};
+
+#[derive(Clone)]
+pub struct CompaniesRepository {
+	pub(crate) database: Arc<Database>,
+}
+
+impl CompaniesRepository {
+	pub fn new(database: &Arc<Database>) -> Self {
+		Self {
+			database: Arc::clone(database),
+		}
+	}
+
+	/// Also finds companies by the slug of a company merged into them
+	pub async fn find_by_slug(&self, slug: &str) -> anyhow::Result<Option<Company>> {
+		let company = sqlx::query_as!(
+			Company,
+			r#"
+				SELECT c.id, c.name, c.slug, c.logo_url, c.website, c.industry, c.size AS "size: CompanySize"
+				FROM companies c
+				LEFT JOIN company_aliases ca ON ca.company_id = c.id AND ca.slug = $1
+				WHERE c.slug = $1 OR ca.slug IS NOT NULL
+				ORDER BY c.slug = $1 DESC
+				LIMIT 1
+			"#,
+			slug
+		)
+		.fetch_optional(&*self.database.pool)
+		.await
+		.context("Failed to find company")?;
+
+		Ok(company)
+	}
+
+	/// Prefix matches first, then similar names, the companies most people worked at first within each
+	pub async fn autocomplete(&self, query: &str, limit: i64) -> anyhow::Result<Vec<Company>> {
+		let companies = sqlx::query_as!(
+			Company,
+			r#"
+				SELECT c.id, c.name, c.slug, c.logo_url, c.website, c.industry, c.size AS "size: CompanySize"
+				FROM companies c
+				CROSS JOIN LATERAL (SELECT company_slug($1) AS slug) q
+				WHERE q.slug <> '' AND (c.slug LIKE q.slug || '%' OR SIMILARITY(c.slug, q.slug) >= 0.3)
+				ORDER BY
+					c.slug LIKE q.slug || '%' DESC,
+					SIMILARITY(c.slug, q.slug) DESC,
+					(SELECT COUNT(*) FROM experience e WHERE e.company_id = c.id) DESC,
+					c.name
+				LIMIT $2
+			"#,
+			query,
+			limit
+		)
+		.fetch_all(&*self.database.pool)
+		.await
+		.context("Failed to autocomplete companies")?;
+
+		Ok(companies)
+	}
+
+	/// Verified users who held a role at the company, each with their most recent one
+	pub async fn get_members(&self, company_id: &i64) -> anyhow::Result<Vec<CompanyMember>> {
+		let mut members = sqlx::query_as!(
+			CompanyMember,
+			r#"
+				SELECT DISTINCT ON (u.id)
+					u.id AS user_id,
+					u.username,
+					u.avatar_url,
+					e.occupation,
+					e.start_date AS "start_date: YearMonth",
+					e.end_date AS "end_date: YearMonth",
+					e.is_current
+				FROM experience e
+				JOIN users u ON u.id = e.user_id
+				WHERE e.company_id = $1 AND u.is_verified
+				ORDER BY u.id, e.is_current DESC, e.start_date DESC
+			"#,
+			company_id
+		)
+		.fetch_all(&*self.database.pool)
+		.await
+		.context("Failed to get company members")?;
+
+		members.sort_by_key(|member| Reverse(member.start_date));
+
+		Ok(members)
+	}
+
+	pub async fn update(&self, id: &i64, company_dto: UpdateCompanyDto) -> anyhow::Result<Option<Company>> {
+		let company = sqlx::query_as!(
+			Company,
+			r#"
+				UPDATE companies
+				SET name = $2, logo_url = $3, website = $4, industry = $5, size = $6
+				WHERE id = $1
+				RETURNING id, name, slug, logo_url, website, industry, size AS "size: CompanySize"
+			"#,
+			id,
+			company_dto.name,
+			company_dto.logo_url,
+			company_dto.website,
+			company_dto.industry,
+			company_dto.size as Option<CompanySize>
+		)
+		.fetch_optional(&*self.database.pool)
+		.await
+		.context("Failed to update company")?;
+
+		Ok(company)
+	}
+
+	/// Moves every role at the duplicates to the target, along with the verifications and email domains that
+	/// came with them, and keeps the duplicates' slugs as aliases of it, so their names resolve to the target
+	/// from now on. Returns `None` when the target doesn't exist.
+	pub async fn merge(&self, target_id: &i64, duplicate_ids: &[i64]) -> anyhow::Result<Option<Company>> {
+		let mut transaction = self.database.pool.begin().await?;
+
+		let target = sqlx::query_as!(
+			Company,
+			r#"
+				SELECT id, name, slug, logo_url, website, industry, size AS "size: CompanySize"
+				FROM companies
+				WHERE id = $1
+				FOR UPDATE
+			"#,
+			target_id
+		)
+		.fetch_optional(&mut *transaction)
+		.await
+		.context("Failed to find merge target")?;
+
+		if target.is_none() {
+			return Ok(None);
+		}
+
+		sqlx::query!(
+			"UPDATE experience SET company_id = $1 WHERE company_id = ANY($2)",
+			target_id,
+			duplicate_ids
+		)
+		.execute(&mut *transaction)
+		.await
+		.context("Failed to move experience to merged company")?;
+
+		sqlx::query!(
+			"UPDATE experience_verifications SET company_id = $1 WHERE company_id = ANY($2)",
+			target_id,
+			duplicate_ids
+		)
+		.execute(&mut *transaction)
+		.await
+		.context("Failed to move verifications to merged company")?;
+
+		sqlx::query!(
+			"UPDATE work_emails SET company_id = $1 WHERE company_id = ANY($2)",
+			target_id,
+			duplicate_ids
+		)
+		.execute(&mut *transaction)
+		.await
+		.context("Failed to move work emails to merged company")?;
+
+		sqlx::query!(
+			"UPDATE company_domains SET company_id = $1 WHERE company_id = ANY($2)",
+			target_id,
+			duplicate_ids
+		)
+		.execute(&mut *transaction)
+		.await
+		.context("Failed to move domains to merged company")?;
+
+		sqlx::query!(
+			r#"
+				INSERT INTO company_aliases (slug, company_id)
+				SELECT slug, $1 FROM companies WHERE id = ANY($2)
+				ON CONFLICT (slug) DO UPDATE SET company_id = EXCLUDED.company_id
+			"#,
+			target_id,
+			duplicate_ids
+		)
+		.execute(&mut *transaction)
+		.await
+		.context("Failed to alias merged companies")?;
+
+		sqlx::query!(
+			"UPDATE company_aliases SET company_id = $1 WHERE company_id = ANY($2)",
+			target_id,
+			duplicate_ids
+		)
+		.execute(&mut *transaction)
+		.await
+		.context("Failed to move aliases of merged companies")?;
+
+		sqlx::query!("DELETE FROM companies WHERE id = ANY($1)", duplicate_ids)
+			.execute(&mut *transaction)
+			.await
+			.context("Failed to delete merged companies")?;
+
+		transaction.commit().await?;
+
+		Ok(target)
+	}
+
+	pub async fn get_domains(&self, company_id: &i64) -> anyhow::Result<Vec<String>> {
+		let domains = sqlx::query_scalar!(
+			"SELECT domain FROM company_domains WHERE company_id = $1 ORDER BY domain",
+			company_id
+		)
+		.fetch_all(&*self.database.pool)
+		.await
+		.context("Failed to get company domains")?;
+
+		Ok(domains)
+	}
+
+	/// Maps the domain to the company, taking it from whichever company had it before. Returns `None` when
+	/// the company doesn't exist.
+	pub async fn set_domain(&self, company_id: &i64, domain: &str) -> anyhow::Result<Option<()>> {
+		let mapped = sqlx::query!(
+			r#"
+				INSERT INTO company_domains (domain, company_id)
+				SELECT $1, id FROM companies WHERE id = $2
+				ON CONFLICT (domain) DO UPDATE SET company_id = EXCLUDED.company_id
+			"#,
+			domain,
+			company_id
+		)
+		.execute(&*self.database.pool)
+		.await
+		.context("Failed to set company domain")?;
+
+		Ok((mapped.rows_affected() > 0).then_some(()))
+	}
+
+	pub async fn delete_domain(&self, company_id: &i64, domain: &str) -> anyhow::Result<()> {
+		sqlx::query!(
+			"DELETE FROM company_domains WHERE company_id = $1 AND domain = $2",
+			company_id,
+			domain
+		)
+		.execute(&*self.database.pool)
+		.await
+		.context("Failed to delete company domain")?;
+
+		Ok(())
+	}
+
+	/// The company the domain is mapped to, if any
+	pub async fn find_domain_owner(&self, domain: &str) -> anyhow::Result<Option<i64>> {
+		let company_id = sqlx::query_scalar!("SELECT company_id FROM company_domains WHERE domain = $1", domain)
+			.fetch_optional(&*self.database.pool)
+			.await
+			.context("Failed to find domain owner")?;
+
+		Ok(company_id)
+	}
+
+	/// Whether the user lists a current role at the company
+	pub async fn is_current_member(&self, company_id: &i64, user_id: &i64) -> anyhow::Result<bool> {
+		let is_member = sqlx::query_scalar!(
+			r#"
+				SELECT EXISTS (
+					SELECT 1 FROM experience
+					WHERE company_id = $1 AND user_id = $2 AND (is_current OR end_date IS NULL)
+				) AS "is_member!"
+			"#,
+			company_id,
+			user_id
+		)
+		.fetch_one(&*self.database.pool)
+		.await
+		.context("Failed to check company membership")?;
+
+		Ok(is_member)
+	}
+
+	/// Starts a claim with a fresh token, replacing one that was started before
+	pub async fn create_domain_claim(
+		&self,
+		company_id: &i64,
+		domain: &str,
+		user_id: &i64,
+	) -> anyhow::Result<DomainClaim> {
+		let claim = sqlx::query_as!(
+			DomainClaim,
+			r#"
+				INSERT INTO company_domain_claims (company_id, domain, user_id, token)
+				VALUES ($1, $2, $3, $4)
+				ON CONFLICT (company_id, domain) DO UPDATE
+				SET user_id = EXCLUDED.user_id, token = EXCLUDED.token, created_at = CURRENT_TIMESTAMP, verified_at = NULL
+				RETURNING company_id, domain, token
+			"#,
+			company_id,
+			domain,
+			user_id,
+			Uuid::new_v4().simple().to_string()
+		)
+		.fetch_one(&*self.database.pool)
+		.await
+		.context("Failed to create domain claim")?;
+
+		Ok(claim)
+	}
+
+	/// Returns `None` when the user didn't start a claim of the domain for the company
+	pub async fn find_domain_claim(
+		&self,
+		company_id: &i64,
+		domain: &str,
+		user_id: &i64,
+	) -> anyhow::Result<Option<DomainClaim>> {
+		let claim = sqlx::query_as!(
+			DomainClaim,
+			r#"
+				SELECT company_id, domain, token
+				FROM company_domain_claims
+				WHERE company_id = $1 AND domain = $2 AND user_id = $3
+			"#,
+			company_id,
+			domain,
+			user_id
+		)
+		.fetch_optional(&*self.database.pool)
+		.await
+		.context("Failed to find domain claim")?;
+
+		Ok(claim)
+	}
+
+	pub async fn get_website(&self, company_id: &i64) -> anyhow::Result<Option<String>> {
+		let website = sqlx::query_scalar!("SELECT website FROM companies WHERE id = $1", company_id)
+			.fetch_optional(&*self.database.pool)
+			.await
+			.context("Failed to get company website")?;
+
+		Ok(website.flatten())
+	}
+
+	/// Leaves a claim whose TXT record showed up for an admin to approve
+	pub async fn queue_domain_claim(&self, claim: &DomainClaim) -> anyhow::Result<()> {
+		sqlx::query!(
+			"UPDATE company_domain_claims SET verified_at = CURRENT_TIMESTAMP WHERE company_id = $1 AND domain = $2",
+			claim.company_id,
+			claim.domain
+		)
+		.execute(&*self.database.pool)
+		.await
+		.context("Failed to queue domain claim")?;
+
+		Ok(())
+	}
+
+	/// Claims waiting for an admin, oldest first
+	pub async fn get_pending_domain_claims(&self) -> anyhow::Result<Vec<PendingDomainClaim>> {
+		let claims = sqlx::query_as!(
+			PendingDomainClaim,
+			r#"
+				SELECT
+					cdc.company_id,
+					c.name AS company_name,
+					c.website AS company_website,
+					cdc.domain,
+					cdc.user_id,
+					u.username,
+					cdc.verified_at AS "verified_at!"
+				FROM company_domain_claims cdc
+				JOIN companies c ON c.id = cdc.company_id
+				JOIN users u ON u.id = cdc.user_id
+				WHERE cdc.verified_at IS NOT NULL
+				ORDER BY cdc.verified_at
+			"#
+		)
+		.fetch_all(&*self.database.pool)
+		.await
+		.context("Failed to get pending domain claims")?;
+
+		Ok(claims)
+	}
+
+	/// Returns `None` when no claim of the domain for the company is waiting for an admin
+	pub async fn find_pending_domain_claim(
+		&self,
+		company_id: &i64,
+		domain: &str,
+	) -> anyhow::Result<Option<DomainClaim>> {
+		let claim = sqlx::query_as!(
+			DomainClaim,
+			r#"
+				SELECT company_id, domain, token
+				FROM company_domain_claims
+				WHERE company_id = $1 AND domain = $2 AND verified_at IS NOT NULL
+			"#,
+			company_id,
+			domain
+		)
+		.fetch_optional(&*self.database.pool)
+		.await
+		.context("Failed to find pending domain claim")?;
+
+		Ok(claim)
+	}
+
+	/// Returns `false` when there was no claim to delete
+	pub async fn delete_domain_claim(&self, company_id: &i64, domain: &str) -> anyhow::Result<bool> {
+		let result = sqlx::query!(
+			"DELETE FROM company_domain_claims WHERE company_id = $1 AND domain = $2",
+			company_id,
+			domain
+		)
+		.execute(&*self.database.pool)
+		.await
+		.context("Failed to delete domain claim")?;
+
+		Ok(result.rows_affected() > 0)
+	}
+
+	/// Maps the claimed domain to the company unless another company got it in the meantime, and drops the
+	/// claim either way. Returns whether the domain was mapped.
+	pub async fn complete_domain_claim(&self, claim: &DomainClaim) -> anyhow::Result<bool> {
+		let mut transaction = self.database.pool.begin().await?;
+
+		let mapped = sqlx::query!(
+			r#"
+				INSERT INTO company_domains (domain, company_id)
+				VALUES ($1, $2)
+				ON CONFLICT (domain) DO UPDATE SET company_id = company_domains.company_id
+				RETURNING company_id
+			"#,
+			claim.domain,
+			claim.company_id
+		)
+		.fetch_one(&mut *transaction)
+		.await
+		.context("Failed to map claimed domain")?;
+
+		sqlx::query!(
+			"DELETE FROM company_domain_claims WHERE company_id = $1 AND domain = $2",
+			claim.company_id,
+			claim.domain
+		)
+		.execute(&mut *transaction)
+		.await
+		.context("Failed to delete domain claim")?;
+
+		transaction.commit().await?;
+
+		Ok(mapped.company_id == claim.company_id)
+	}
+}
+
+/// Proof of control over a domain: a TXT record at `record_name` holding `record_value`
+#[derive(Debug)]
+pub struct DomainClaim {
+	pub company_id: i64,
+	pub domain: String,
+	pub token: String,
+}
+
+impl DomainClaim {
+	pub fn record_name(&self) -> String {
+		format!("_moner-verification.{}", self.domain)
+	}
+
+	pub fn record_value(&self) -> String {
+		format!("moner-verification={}", self.token)
+	}
+}
+
+/// A claim of a domain outside the company's website, proven in DNS but waiting for an admin
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingDomainClaim {
+	pub company_id: i64,
+	pub company_name: String,
+	pub company_website: Option<String>,
+	pub domain: String,
+	pub user_id: i64,
+	pub username: String,
+	pub verified_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Company {
+	pub id: i64,
+	pub name: String,
+	pub slug: String,
+	pub logo_url: Option<String>,
+	pub website: Option<String>,
+	pub industry: Option<String>,
+	pub size: Option<CompanySize>,
+}
+
+/// Employee count ranges, like the `company_size` enum in the database
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize, sqlx::Type)]
+#[sqlx(type_name = "company_size")]
+pub enum CompanySize {
+	#[serde(rename = "1-10")]
+	#[sqlx(rename = "1-10")]
+	Micro,
+	#[serde(rename = "11-50")]
+	#[sqlx(rename = "11-50")]
+	Small,
+	#[serde(rename = "51-200")]
+	#[sqlx(rename = "51-200")]
+	Medium,
+	#[serde(rename = "201-1000")]
+	#[sqlx(rename = "201-1000")]
+	Large,
+	#[serde(rename = "1001-5000")]
+	#[sqlx(rename = "1001-5000")]
+	Enterprise,
+	#[serde(rename = "5001+")]
+	#[sqlx(rename = "5001+")]
+	Corporation,
+}
@@ -0,0 +1,411 @@
+use std::sync::Arc;
+
+use axum::{
+	extract::{Path, Query, State},
+	http::StatusCode,
+	middleware,
+	response::{IntoResponse, Response},
+	routing::{delete, get, post, put},
+	Extension, Json, Router,
+};
+use thiserror::Error;
+use validator::{Validate, ValidationErrors};
+
+use crate::{
+	app::{ApiErrorResponse, ApiResult},
+	database::Database,
+	domains::{is_disposable, is_website_domain, normalize_domain},
+	services::dns::DnsService,
+	users::auth::{self, CurrentUser},
+	validation::ValidatedJson,
+};
+
+use super::{
+	dtos::{
+		AutocompleteQuery, ClaimDomainDto, CompanyPageResponse, DomainClaimResponse, MergeCompaniesDto,
+		UpdateCompanyDto,
+	},
+	repository::{CompaniesRepository, Company, DomainClaim, PendingDomainClaim},
+};
+
+#[derive(Clone)]
+pub struct CompaniesState {
+	pub companies_repository: CompaniesRepository,
+	pub dns_service: DnsService,
+}
+
+impl CompaniesState {
+	pub fn new(database: &Arc<Database>) -> Self {
+		Self {
+			companies_repository: CompaniesRepository::new(database),
+			dns_service: DnsService::new(),
+		}
+	}
+}
+
+pub fn init() -> Router<CompaniesState> {
+	Router::new()
+		.route("/admin/companies/{company_id}", put(update_company_route))
+		.route("/admin/companies/{company_id}/merge", post(merge_companies_route))
+		.route("/admin/companies/{company_id}/domains/{domain}", put(set_domain_route))
+		.route(
+			"/admin/companies/{company_id}/domains/{domain}",
+			delete(delete_domain_route),
+		)
+		.route("/admin/domain-claims", get(pending_domain_claims_route))
+		.route(
+			"/admin/companies/{company_id}/domain-claims/{domain}",
+			put(approve_domain_claim_route),
+		)
+		.route(
+			"/admin/companies/{company_id}/domain-claims/{domain}",
+			delete(reject_domain_claim_route),
+		)
+		.route_layer(middleware::from_fn(auth::admin_middleware))
+		.route("/companies/{company_id}/domain-claims", post(claim_domain_route))
+		.route(
+			"/companies/{company_id}/domain-claims/{domain}/check",
+			post(check_domain_claim_route),
+		)
+		.route_layer(middleware::from_fn(auth::middleware))
+		.route("/companies/autocomplete", get(autocomplete_route))
+		.route("/companies/{slug}", get(company_page_route))
+}
+
+async fn autocomplete_route(
+	Query(query): Query<AutocompleteQuery>,
+	State(state): State<CompaniesState>,
+) -> ApiResult<Json<Vec<Company>>> {
+	query.validate().map_err(CompaniesApiError::InvalidQuery)?;
+
+	let companies = state
+		.companies_repository
+		.autocomplete(&query.q, query.limit)
+		.await
+		.map_err(|_| CompaniesApiError::Autocomplete())?;
+
+	Ok((StatusCode::OK, Json(companies)))
+}
+
+async fn company_page_route(
+	Path(slug): Path<String>,
+	State(state): State<CompaniesState>,
+) -> ApiResult<Json<CompanyPageResponse>> {
+	let company = state
+		.companies_repository
+		.find_by_slug(&slug)
+		.await
+		.map_err(|_| CompaniesApiError::Get())?
+		.ok_or(CompaniesApiError::NotFound(slug))?;
+
+	let (current_members, past_members) = state
+		.companies_repository
+		.get_members(&company.id)
+		.await
+		.map_err(|_| CompaniesApiError::Get())?
+		.into_iter()
+		.partition(|member| member.is_current);
+
+	let domains = state
+		.companies_repository
+		.get_domains(&company.id)
+		.await
+		.map_err(|_| CompaniesApiError::Get())?;
+
+	Ok((
+		StatusCode::OK,
+		Json(CompanyPageResponse {
+			company,
+			domains,
+			current_members,
+			past_members,
+		}),
+	))
+}
+
+async fn update_company_route(
+	State(state): State<CompaniesState>,
+	Path(company_id): Path<i64>,
+	ValidatedJson(request): ValidatedJson<UpdateCompanyDto>,
+) -> ApiResult<Json<Company>> {
+	let company = state
+		.companies_repository
+		.update(&company_id, request)
+		.await
+		.map_err(|_| CompaniesApiError::Update())?
+		.ok_or_else(|| CompaniesApiError::NotFound(company_id.to_string()))?;
+
+	Ok((StatusCode::OK, Json(company)))
+}
+
+async fn merge_companies_route(
+	State(state): State<CompaniesState>,
+	Path(company_id): Path<i64>,
+	ValidatedJson(request): ValidatedJson<MergeCompaniesDto>,
+) -> ApiResult<Json<Company>> {
+	if request.duplicate_ids.contains(&company_id) {
+		return Err(CompaniesApiError::MergeIntoItself(company_id))?;
+	}
+
+	let company = state
+		.companies_repository
+		.merge(&company_id, &request.duplicate_ids)
+		.await
+		.map_err(|_| CompaniesApiError::Merge())?
+		.ok_or_else(|| CompaniesApiError::NotFound(company_id.to_string()))?;
+
+	Ok((StatusCode::OK, Json(company)))
+}
+
+async fn set_domain_route(
+	State(state): State<CompaniesState>,
+	Path((company_id, domain)): Path<(i64, String)>,
+) -> ApiResult<Json<Vec<String>>> {
+	let domain = normalize_domain(&domain)
+		.filter(|domain| !is_disposable(domain))
+		.ok_or(CompaniesApiError::InvalidDomain(domain))?;
+
+	state
+		.companies_repository
+		.set_domain(&company_id, &domain)
+		.await
+		.map_err(|_| CompaniesApiError::UpdateDomains())?
+		.ok_or_else(|| CompaniesApiError::NotFound(company_id.to_string()))?;
+
+	domains_response(&state, &company_id).await
+}
+
+async fn delete_domain_route(
+	State(state): State<CompaniesState>,
+	Path((company_id, domain)): Path<(i64, String)>,
+) -> ApiResult<Json<Vec<String>>> {
+	let domain = normalize_domain(&domain).ok_or(CompaniesApiError::InvalidDomain(domain))?;
+
+	state
+		.companies_repository
+		.delete_domain(&company_id, &domain)
+		.await
+		.map_err(|_| CompaniesApiError::UpdateDomains())?;
+
+	domains_response(&state, &company_id).await
+}
+
+/// Anyone with a current role at the company can start a claim; it only succeeds once the domain publishes
+/// the claim's TXT record, which takes control of its DNS. Domains outside the company's website also need an
+/// admin's approval, since controlling a domain doesn't make it the company's.
+async fn claim_domain_route(
+	Extension(current_user): Extension<CurrentUser>,
+	State(state): State<CompaniesState>,
+	Path(company_id): Path<i64>,
+	ValidatedJson(request): ValidatedJson<ClaimDomainDto>,
+) -> ApiResult<Json<DomainClaimResponse>> {
+	let domain = normalize_domain(&request.domain).ok_or(CompaniesApiError::InvalidDomain(request.domain))?;
+
+	let is_member = state
+		.companies_repository
+		.is_current_member(&company_id, &current_user.user_id)
+		.await
+		.map_err(|_| CompaniesApiError::ClaimDomain())?;
+
+	if !is_member {
+		return Err(CompaniesApiError::NotCurrentMember(company_id))?;
+	}
+
+	let owner_id = state
+		.companies_repository
+		.find_domain_owner(&domain)
+		.await
+		.map_err(|_| CompaniesApiError::ClaimDomain())?;
+
+	if owner_id.is_some() {
+		return Err(CompaniesApiError::DomainTaken(domain))?;
+	}
+
+	let claim = state
+		.companies_repository
+		.create_domain_claim(&company_id, &domain, &current_user.user_id)
+		.await
+		.map_err(|_| CompaniesApiError::ClaimDomain())?;
+
+	Ok((StatusCode::CREATED, Json(DomainClaimResponse::from(claim))))
+}
+
+async fn check_domain_claim_route(
+	Extension(current_user): Extension<CurrentUser>,
+	State(state): State<CompaniesState>,
+	Path((company_id, domain)): Path<(i64, String)>,
+) -> ApiResult<Json<Vec<String>>> {
+	let domain = normalize_domain(&domain).ok_or(CompaniesApiError::InvalidDomain(domain))?;
+
+	let claim = state
+		.companies_repository
+		.find_domain_claim(&company_id, &domain, &current_user.user_id)
+		.await
+		.map_err(|_| CompaniesApiError::ClaimDomain())?
+		.ok_or_else(|| CompaniesApiError::ClaimNotFound(domain.clone()))?;
+
+	let records = state
+		.dns_service
+		.txt_records(&claim.record_name())
+		.await
+		.map_err(|_| CompaniesApiError::ClaimDomain())?;
+
+	if !records.contains(&claim.record_value()) {
+		return Err(CompaniesApiError::ClaimRecordMissing(claim.record_name()))?;
+	}
+
+	let website = state
+		.companies_repository
+		.get_website(&company_id)
+		.await
+		.map_err(|_| CompaniesApiError::ClaimDomain())?;
+
+	if !website.is_some_and(|website| is_website_domain(&domain, &website)) {
+		state
+			.companies_repository
+			.queue_domain_claim(&claim)
+			.await
+			.map_err(|_| CompaniesApiError::ClaimDomain())?;
+
+		let (_, domains) = domains_response(&state, &company_id).await?;
+		return Ok((StatusCode::ACCEPTED, domains));
+	}
+
+	complete_domain_claim(&state, &claim).await
+}
+
+async fn pending_domain_claims_route(State(state): State<CompaniesState>) -> ApiResult<Json<Vec<PendingDomainClaim>>> {
+	let claims = state
+		.companies_repository
+		.get_pending_domain_claims()
+		.await
+		.map_err(|_| CompaniesApiError::ClaimDomain())?;
+
+	Ok((StatusCode::OK, Json(claims)))
+}
+
+async fn approve_domain_claim_route(
+	State(state): State<CompaniesState>,
+	Path((company_id, domain)): Path<(i64, String)>,
+) -> ApiResult<Json<Vec<String>>> {
+	let domain = normalize_domain(&domain).ok_or(CompaniesApiError::InvalidDomain(domain))?;
+
+	let claim = state
+		.companies_repository
+		.find_pending_domain_claim(&company_id, &domain)
+		.await
+		.map_err(|_| CompaniesApiError::ClaimDomain())?
+		.ok_or(CompaniesApiError::ClaimNotFound(domain))?;
+
+	complete_domain_claim(&state, &claim).await
+}
+
+async fn reject_domain_claim_route(
+	State(state): State<CompaniesState>,
+	Path((company_id, domain)): Path<(i64, String)>,
+) -> ApiResult<()> {
+	let domain = normalize_domain(&domain).ok_or(CompaniesApiError::InvalidDomain(domain))?;
+
+	let deleted = state
+		.companies_repository
+		.delete_domain_claim(&company_id, &domain)
+		.await
+		.map_err(|_| CompaniesApiError::ClaimDomain())?;
+
+	if !deleted {
+		return Err(CompaniesApiError::ClaimNotFound(domain))?;
+	}
+
+	Ok((StatusCode::OK, ()))
+}
+
+async fn complete_domain_claim(state: &CompaniesState, claim: &DomainClaim) -> ApiResult<Json<Vec<String>>> {
+	let mapped = state
+		.companies_repository
+		.complete_domain_claim(claim)
+		.await
+		.map_err(|_| CompaniesApiError::ClaimDomain())?;
+
+	if !mapped {
+		return Err(CompaniesApiError::DomainTaken(claim.domain.clone()))?;
+	}
+
+	domains_response(state, &claim.company_id).await
+}
+
+async fn domains_response(state: &CompaniesState, company_id: &i64) -> ApiResult<Json<Vec<String>>> {
+	let domains = state
+		.companies_repository
+		.get_domains(company_id)
+		.await
+		.map_err(|_| CompaniesApiError::UpdateDomains())?;
+
+	Ok((StatusCode::OK, Json(domains)))
+}
+
+#[derive(Debug, Error)]
+pub enum CompaniesApiError {
+	#[error("Invalid autocomplete query: {0}")]
+	InvalidQuery(ValidationErrors),
+
+	#[error("Company not found: {0}")]
+	NotFound(String),
+
+	#[error("Company {0} can't be merged into itself")]
+	MergeIntoItself(i64),
+
+	#[error("Failed to autocomplete companies")]
+	Autocomplete(),
+
+	#[error("Failed to get company")]
+	Get(),
+
+	#[error("Failed to update company")]
+	Update(),
+
+	#[error("Failed to merge companies")]
+	Merge(),
+
+	#[error("Invalid domain: {0}")]
+	InvalidDomain(String),
+
+	#[error("Domain is already mapped to a company: {0}")]
+	DomainTaken(String),
+
+	#[error("Only users with a current role at company {0} can claim its domains")]
+	NotCurrentMember(i64),
+
+	#[error("Domain claim not found: {0}")]
+	ClaimNotFound(String),
+
+	#[error("TXT record with the claim's value not found at {0}")]
+	ClaimRecordMissing(String),
+
+	#[error("Failed to update company domains")]
+	UpdateDomains(),
+
+	#[error("Failed to claim domain")]
+	ClaimDomain(),
+}
+
+impl IntoResponse for CompaniesApiError {
+	fn into_response(self) -> Response {
+		let status_code = match self {
+			Self::InvalidQuery(_) | Self::MergeIntoItself(_) | Self::InvalidDomain(_) | Self::ClaimRecordMissing(_) => {
+				StatusCode::BAD_REQUEST
+			}
+			Self::NotCurrentMember(_) => StatusCode::FORBIDDEN,
+			Self::NotFound(_) | Self::ClaimNotFound(_) => StatusCode::NOT_FOUND,
+			Self::DomainTaken(_) => StatusCode::CONFLICT,
+			Self::Autocomplete()
+			| Self::Get()
+			| Self::Update()
+			| Self::Merge()
+			| Self::UpdateDomains()
+			| Self::ClaimDomain() => StatusCode::INTERNAL_SERVER_ERROR,
+		};
+
+		log::error!("{self:?}");
+		ApiErrorResponse::new(status_code, self.to_string()).into_response()
+	}
+}
@@ -0,0 +1,14 @@
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateRuleDto {
+	#[validate(range(min = 0, max = 100, message = "Weight must be between 0 and 100"))]
+	pub weight: i32,
+	#[validate(range(min = 1, max = 100, message = "Threshold must be between 1 and 100"))]
+	pub threshold: i32,
+	#[validate(length(min = 1, max = 255, message = "Wrong suggestion length"))]
+	pub suggestion: String,
+	pub enabled: bool,
+}
@@ -0,0 +1,290 @@
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+
+use crate::database::Database;
+
+use super::dtos::UpdateRuleDto;
+
+#[derive(Clone)]
+pub struct CompletenessRepository {
+	pub(crate) database: Arc<Database>,
+}
+
+impl CompletenessRepository {
+	pub fn new(database: &Arc<Database>) -> Self {
+		Self {
+			database: Arc::clone(database),
+		}
+	}
+
+	pub async fn list_rules(&self) -> anyhow::Result<Vec<CompletenessRule>> {
+		let rules = sqlx::query_as!(
+			CompletenessRule,
+			r#"
+				SELECT key AS "key: RuleKey", weight, threshold, suggestion, enabled
+				FROM completeness_rules
+				ORDER BY weight DESC, key
+			"#
+		)
+		.fetch_all(&*self.database.pool)
+		.await
+		.map_err(|error| anyhow!(error).context("Failed to list completeness rules"))?;
+
+		Ok(rules)
+	}
+
+	pub async fn update_rule(&self, key: RuleKey, rule_dto: UpdateRuleDto) -> anyhow::Result<CompletenessRule> {
+		let rule = sqlx::query_as!(
+			CompletenessRule,
+			r#"
+				UPDATE completeness_rules
+				SET weight = $2, threshold = $3, suggestion = $4, enabled = $5
+				WHERE key = $1
+				RETURNING key AS "key: RuleKey", weight, threshold, suggestion, enabled
+			"#,
+			key as RuleKey,
+			rule_dto.weight,
+			rule_dto.threshold,
+			rule_dto.suggestion,
+			rule_dto.enabled
+		)
+		.fetch_one(&*self.database.pool)
+		.await
+		.map_err(|error| anyhow!(error).context("Failed to update completeness rule"))?;
+
+		Ok(rule)
+	}
+
+	/// Scores the user's profile against the enabled rules and stores the result for reporting
+	pub async fn evaluate(&self, user_id: &i64) -> anyhow::Result<Completeness> {
+		let rules = self.list_rules().await?;
+		let metrics = self.get_metrics(user_id).await?;
+
+		let completeness = Completeness::new(&rules, &metrics);
+
+		let missing: Vec<RuleKey> = completeness.missing.iter().map(|item| item.key).collect();
+
+		sqlx::query!(
+			r#"
+				INSERT INTO profile_completeness (user_id, score, missing)
+				VALUES ($1, $2, $3)
+				ON CONFLICT (user_id) DO UPDATE
+				SET score = EXCLUDED.score, missing = EXCLUDED.missing, updated_at = CURRENT_TIMESTAMP
+			"#,
+			user_id,
+			completeness.score,
+			missing as Vec<RuleKey>
+		)
+		.execute(&*self.database.pool)
+		.await
+		.map_err(|error| anyhow!(error).context("Failed to store profile completeness"))?;
+
+		Ok(completeness)
+	}
+
+	async fn get_metrics(&self, user_id: &i64) -> anyhow::Result<ProfileMetrics> {
+		let metrics = sqlx::query_as!(
+			ProfileMetrics,
+			r#"
+				SELECT
+					(u.avatar_url IS NOT NULL)::INTEGER AS "avatar!",
+					(u.headline IS NOT NULL)::INTEGER AS "headline!",
+					(SELECT COUNT(*) FROM experience e WHERE e.user_id = u.id AND e.description <> '')::INTEGER AS "experience!",
+					(SELECT COUNT(*) FROM user_skills us WHERE us.user_id = u.id)::INTEGER AS "skills!",
+					(SELECT COUNT(*) FROM education e WHERE e.user_id = u.id)::INTEGER AS "education!",
+					u.is_verified::INTEGER AS "verified_email!"
+				FROM users u
+				WHERE u.id = $1
+			"#,
+			user_id
+		)
+		.fetch_one(&*self.database.pool)
+		.await
+		.map_err(|error| anyhow!(error).context("Failed to get profile metrics"))?;
+
+		Ok(metrics)
+	}
+
+	/// Stored scores bucketed by quarter, and how many profiles each rule is still missing from
+	pub async fn report(&self) -> anyhow::Result<CompletenessReport> {
+		let summary = sqlx::query!(
+			r#"
+				SELECT
+					(SELECT COUNT(*) FROM users WHERE is_verified) AS "users!",
+					COUNT(*) AS "scored!",
+					COALESCE(AVG(score), 0)::DOUBLE PRECISION AS "average_score!"
+				FROM profile_completeness
+			"#
+		)
+		.fetch_one(&*self.database.pool)
+		.await
+		.map_err(|error| anyhow!(error).context("Failed to summarize profile completeness"))?;
+
+		let buckets = sqlx::query_as!(
+			ScoreBucket,
+			r#"
+				SELECT
+					(bucket * 25)::SMALLINT AS "min_score!",
+					CASE WHEN bucket = 4 THEN 100 ELSE bucket * 25 + 24 END::SMALLINT AS "max_score!",
+					COUNT(pc.user_id) AS "users!"
+				FROM GENERATE_SERIES(0, 4) AS bucket
+				LEFT JOIN profile_completeness pc ON LEAST(pc.score / 25, 4) = bucket
+				GROUP BY bucket
+				ORDER BY bucket
+			"#
+		)
+		.fetch_all(&*self.database.pool)
+		.await
+		.map_err(|error| anyhow!(error).context("Failed to bucket profile completeness"))?;
+
+		let missing = sqlx::query_as!(
+			MissingCount,
+			r#"
+				SELECT key AS "key!: RuleKey", COUNT(*) AS "users!"
+				FROM profile_completeness, UNNEST(missing) AS key
+				GROUP BY key
+				ORDER BY COUNT(*) DESC, key
+			"#
+		)
+		.fetch_all(&*self.database.pool)
+		.await
+		.map_err(|error| anyhow!(error).context("Failed to count missing profile items"))?;
+
+		Ok(CompletenessReport {
+			users: summary.users,
+			scored: summary.scored,
+			average_score: summary.average_score,
+			buckets,
+			missing,
+		})
+	}
+}
+
+/// What a rule measures; only the weights, thresholds and wording live in the database
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+pub enum RuleKey {
+	Avatar,
+	Headline,
+	Experience,
+	Skills,
+	Education,
+	VerifiedEmail,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompletenessRule {
+	pub key: RuleKey,
+	pub weight: i32,
+	pub threshold: i32,
+	pub suggestion: String,
+	pub enabled: bool,
+}
+
+/// How many items of each measured kind a profile has
+struct ProfileMetrics {
+	avatar: i32,
+	headline: i32,
+	experience: i32,
+	skills: i32,
+	education: i32,
+	verified_email: i32,
+}
+
+impl ProfileMetrics {
+	const fn get(&self, key: RuleKey) -> i32 {
+		match key {
+			RuleKey::Avatar => self.avatar,
+			RuleKey::Headline => self.headline,
+			RuleKey::Experience => self.experience,
+			RuleKey::Skills => self.skills,
+			RuleKey::Education => self.education,
+			RuleKey::VerifiedEmail => self.verified_email,
+		}
+	}
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Completeness {
+	/// Share of the enabled rules' weight the profile meets, from 0 to 100
+	pub score: i16,
+	/// Unmet rules, the ones worth the most first
+	pub missing: Vec<MissingItem>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingItem {
+	pub key: RuleKey,
+	pub suggestion: String,
+	pub current: i32,
+	pub required: i32,
+	pub weight: i32,
+}
+
+impl Completeness {
+	fn new(rules: &[CompletenessRule], metrics: &ProfileMetrics) -> Self {
+		let enabled: Vec<&CompletenessRule> = rules.iter().filter(|rule| rule.enabled).collect();
+		let total_weight: i32 = enabled.iter().map(|rule| rule.weight).sum();
+
+		let mut met_weight = 0;
+		let mut missing = Vec::new();
+
+		for rule in enabled {
+			let current = metrics.get(rule.key);
+
+			if current >= rule.threshold {
+				met_weight += rule.weight;
+			} else {
+				missing.push(MissingItem {
+					key: rule.key,
+					suggestion: rule.suggestion.replace("{threshold}", &rule.threshold.to_string()),
+					current,
+					required: rule.threshold,
+					weight: rule.weight,
+				});
+			}
+		}
+
+		// Nothing to meet counts as complete
+		let score = if total_weight == 0 {
+			100
+		} else {
+			// Rounded to the nearest point; met weight never exceeds the total, so this stays within 0..=100
+			i16::try_from((met_weight * 100 + total_weight / 2) / total_weight).unwrap_or(100)
+		};
+
+		Self { score, missing }
+	}
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompletenessReport {
+	/// Verified users, whether or not they've been scored yet
+	pub users: i64,
+	pub scored: i64,
+	pub average_score: f64,
+	pub buckets: Vec<ScoreBucket>,
+	pub missing: Vec<MissingCount>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScoreBucket {
+	pub min_score: i16,
+	pub max_score: i16,
+	pub users: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingCount {
+	pub key: RuleKey,
+	pub users: i64,
+}
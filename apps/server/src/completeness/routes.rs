@@ -0,0 +1,102 @@
+use std::sync::Arc;
+
+use axum::{
+	extract::{Path, State},
+	http::StatusCode,
+	middleware,
+	response::{IntoResponse, Response},
+	routing::{get, put},
+	Json, Router,
+};
+use thiserror::Error;
+
+use crate::{
+	app::{ApiErrorResponse, ApiResult},
+	database::Database,
+	users::auth,
+	validation::ValidatedJson,
+};
+
+use super::{
+	dtos::UpdateRuleDto,
+	repository::{CompletenessReport, CompletenessRepository, CompletenessRule, RuleKey},
+};
+
+#[derive(Clone)]
+pub struct CompletenessState {
+	pub completeness_repository: CompletenessRepository,
+}
+
+impl CompletenessState {
+	pub fn new(database: &Arc<Database>) -> Self {
+		Self {
+			completeness_repository: CompletenessRepository::new(database),
+		}
+	}
+}
+
+pub fn init() -> Router<CompletenessState> {
+	Router::new()
+		.route("/admin/completeness/rules", get(list_rules_route))
+		.route("/admin/completeness/rules/{key}", put(update_rule_route))
+		.route("/admin/completeness/report", get(report_route))
+		.route_layer(middleware::from_fn(auth::admin_middleware))
+		.route_layer(middleware::from_fn(auth::middleware))
+}
+
+async fn list_rules_route(State(state): State<CompletenessState>) -> ApiResult<Json<Vec<CompletenessRule>>> {
+	let rules = state
+		.completeness_repository
+		.list_rules()
+		.await
+		.map_err(|_| CompletenessApiError::ListRules())?;
+
+	Ok((StatusCode::OK, Json(rules)))
+}
+
+async fn update_rule_route(
+	State(state): State<CompletenessState>,
+	Path(key): Path<RuleKey>,
+	ValidatedJson(request): ValidatedJson<UpdateRuleDto>,
+) -> ApiResult<Json<CompletenessRule>> {
+	let rule = state
+		.completeness_repository
+		.update_rule(key, request)
+		.await
+		.map_err(|_| CompletenessApiError::UpdateRule())?;
+
+	Ok((StatusCode::OK, Json(rule)))
+}
+
+async fn report_route(State(state): State<CompletenessState>) -> ApiResult<Json<CompletenessReport>> {
+	let report = state
+		.completeness_repository
+		.report()
+		.await
+		.map_err(|_| CompletenessApiError::Report())?;
+
+	Ok((StatusCode::OK, Json(report)))
+}
+
+#[derive(Debug, Error)]
+pub enum CompletenessApiError {
+	#[error("Failed to list completeness rules")]
+	ListRules(),
+
+	#[error("Failed to update completeness rule")]
+	UpdateRule(),
+
+	#[error("Failed to build completeness report")]
+	Report(),
+}
+
+impl IntoResponse for CompletenessApiError {
+	fn into_response(self) -> Response {
+		let status_code = match self {
+			Self::ListRules() | Self::UpdateRule() | Self::Report() => StatusCode::INTERNAL_SERVER_ERROR,
+		};
+
+		log::error!("{self:?}");
+		ApiErrorResponse::new(status_code, self.to_string()).into_response()
+	}
+}
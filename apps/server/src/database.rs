@@ -29,3 +29,9 @@ impl Database {
 		Ok(Self { pool: Arc::new(pool) })
 	}
 }
+
+/// Escapes `%`, `_` and `\` so the value matches literally inside a `LIKE` pattern, which uses `\` as its
+/// escape character by default
+pub fn escape_like(value: &str) -> String {
+	value.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
@@ -0,0 +1,100 @@
+//! Email domains: normalizing them and telling throwaway ones apart
+
+use url::Url;
+use validator::ValidationError;
+
+/// Providers handing out throwaway inboxes, which can't prove anyone works anywhere. Subdomains are covered too.
+const DISPOSABLE_DOMAINS: &[&str] = &[
+	"10minutemail.com",
+	"20minutemail.com",
+	"33mail.com",
+	"anonaddy.me",
+	"burnermail.io",
+	"discard.email",
+	"dispostable.com",
+	"emailondeck.com",
+	"fakeinbox.com",
+	"getairmail.com",
+	"getnada.com",
+	"guerrillamail.biz",
+	"guerrillamail.com",
+	"guerrillamail.de",
+	"guerrillamail.net",
+	"guerrillamail.org",
+	"guerrillamailblock.com",
+	"harakirimail.com",
+	"inboxkitten.com",
+	"mailcatch.com",
+	"maildrop.cc",
+	"mailinator.com",
+	"mailnesia.com",
+	"mintemail.com",
+	"mohmal.com",
+	"mytemp.email",
+	"sharklasers.com",
+	"spamgourmet.com",
+	"temp-mail.io",
+	"temp-mail.org",
+	"tempail.com",
+	"tempmail.dev",
+	"tempmailo.com",
+	"tempr.email",
+	"throwawaymail.com",
+	"trashmail.com",
+	"trashmail.de",
+	"yopmail.com",
+	"yopmail.fr",
+];
+
+/// Lowercases a domain like `Mail.Example.com.` to `mail.example.com`, rejecting anything that isn't a valid
+/// host name with at least two labels
+pub fn normalize_domain(domain: &str) -> Option<String> {
+	let domain = domain.trim().trim_end_matches('.').to_ascii_lowercase();
+
+	let valid_label = |label: &str| {
+		(1..=63).contains(&label.len())
+			&& !label.starts_with('-')
+			&& !label.ends_with('-')
+			&& label.chars().all(|char| char.is_ascii_alphanumeric() || char == '-')
+	};
+
+	(domain.len() <= 253 && domain.contains('.') && domain.split('.').all(valid_label)).then_some(domain)
+}
+
+/// The normalized domain of an address like `name@example.com`
+pub fn email_domain(email: &str) -> Option<String> {
+	email.rsplit_once('@').and_then(|(_, domain)| normalize_domain(domain))
+}
+
+/// Whether a normalized domain is the host of a website URL or one it's a subdomain of
+pub fn is_website_domain(domain: &str, website: &str) -> bool {
+	Url::parse(website)
+		.ok()
+		.and_then(|url| url.host_str().and_then(normalize_domain))
+		.is_some_and(|host| host == domain || host.ends_with(&format!(".{domain}")))
+}
+
+/// Whether a normalized domain, or one it's a subdomain of, hands out throwaway inboxes
+pub fn is_disposable(domain: &str) -> bool {
+	DISPOSABLE_DOMAINS
+		.iter()
+		.any(|disposable| domain == *disposable || domain.ends_with(&format!(".{disposable}")))
+}
+
+pub fn validate_domain(domain: &str) -> Result<(), ValidationError> {
+	match normalize_domain(domain) {
+		Some(domain) if is_disposable(&domain) => Err(ValidationError::new("Disposable email domains aren't allowed")),
+		Some(_) => Ok(()),
+		None => Err(ValidationError::new("Wrong domain, expected one like example.com")),
+	}
+}
+
+pub fn validate_work_email(email: &str) -> Result<(), ValidationError> {
+	match email_domain(email) {
+		Some(domain) if is_disposable(&domain) => {
+			Err(ValidationError::new("Disposable email addresses aren't allowed"))
+		}
+		Some(_) => Ok(()),
+		None => Err(ValidationError::new("Wrong email domain")),
+	}
+}
@@ -0,0 +1,34 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::{experience::repository::Experience, skills::repository::Skill, users::dtos::PublicUserResponse};
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewQuery {
+	#[serde(default = "default_expires_in_hours")]
+	#[validate(range(min = 1, max = 168, message = "Preview links can last between 1 hour and 7 days"))]
+	pub expires_in_hours: i64,
+}
+
+const fn default_expires_in_hours() -> i64 {
+	24
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewLinkResponse {
+	pub token: String,
+	pub expires_at: DateTime<Utc>,
+}
+
+/// The public profile as it will look once the draft is published
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DraftPreviewResponse {
+	pub user: PublicUserResponse,
+	pub experience: Vec<Experience>,
+	pub skills: Vec<Skill>,
+	pub expires_at: DateTime<Utc>,
+}
@@ -0,0 +1,4 @@
+pub mod dtos;
+pub mod preview;
+pub mod repository;
+pub mod routes;
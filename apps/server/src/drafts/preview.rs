@@ -0,0 +1,50 @@
+//! Signed, expiring tokens that let anyone holding the link view a staged draft
+
+use anyhow::anyhow;
+use chrono::{DateTime, Utc};
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+
+use crate::settings::SETTINGS;
+
+/// Keeps preview tokens and access tokens from being accepted in place of each other
+const PREVIEW_AUDIENCE: &str = "draft-preview";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PreviewClaims {
+	pub exp: i64,
+	pub iat: i64,
+	pub aud: String,
+	/// Tied to the draft rather than the user, so discarding or publishing a draft revokes its links
+	pub draft_id: i64,
+}
+
+pub fn create_token(draft_id: i64, expires_at: DateTime<Utc>) -> anyhow::Result<String> {
+	let claims = PreviewClaims {
+		exp: expires_at.timestamp(),
+		iat: Utc::now().timestamp(),
+		aud: PREVIEW_AUDIENCE.to_string(),
+		draft_id,
+	};
+
+	encode(
+		&Header::default(),
+		&claims,
+		&EncodingKey::from_secret(SETTINGS.auth.jwt_secret.as_bytes()),
+	)
+	.map_err(|error| anyhow!(error).context("Failed to encode preview token"))
+}
+
+pub fn verify_token(token: &str) -> anyhow::Result<PreviewClaims> {
+	let mut validation = Validation::default();
+	validation.set_audience(&[PREVIEW_AUDIENCE]);
+	validation.set_required_spec_claims(&["exp", "aud"]);
+
+	decode(
+		token,
+		&DecodingKey::from_secret(SETTINGS.auth.jwt_secret.as_bytes()),
+		&validation,
+	)
+	.map(|token_data| token_data.claims)
+	.map_err(|error| anyhow!(error).context("Failed to decode preview token"))
+}
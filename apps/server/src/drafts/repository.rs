@@ -0,0 +1,309 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, Context};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::{
+	database::Database,
+	experience::{
+		dtos::ExperienceDto,
+		repository::{Experience, ExperienceRepository},
+	},
+	history::repository::{HistoryRepository, Section},
+	skills::{dtos::UserSkillDto, repository::Skill},
+};
+
+#[derive(Clone)]
+pub struct DraftsRepository {
+	pub(crate) database: Arc<Database>,
+}
+
+impl DraftsRepository {
+	pub fn new(database: &Arc<Database>) -> Self {
+		Self {
+			database: Arc::clone(database),
+		}
+	}
+
+	pub async fn find_by_user_id(&self, user_id: &i64) -> anyhow::Result<Option<Draft>> {
+		let draft = sqlx::query_as!(
+			DraftRow,
+			"SELECT id, user_id, experience, skills, created_at, updated_at FROM profile_drafts WHERE user_id = $1",
+			user_id
+		)
+		.fetch_optional(&*self.database.pool)
+		.await
+		.map_err(|error| anyhow!(error).context("Failed to find draft by user id"))?;
+
+		match draft {
+			Some(draft) => Ok(Some(self.load(draft).await?)),
+			None => Ok(None),
+		}
+	}
+
+	pub async fn find_by_id(&self, id: &i64) -> anyhow::Result<Option<Draft>> {
+		let draft = sqlx::query_as!(
+			DraftRow,
+			"SELECT id, user_id, experience, skills, created_at, updated_at FROM profile_drafts WHERE id = $1",
+			id
+		)
+		.fetch_optional(&*self.database.pool)
+		.await
+		.map_err(|error| anyhow!(error).context("Failed to find draft by id"))?;
+
+		match draft {
+			Some(draft) => Ok(Some(self.load(draft).await?)),
+			None => Ok(None),
+		}
+	}
+
+	async fn load(&self, draft: DraftRow) -> anyhow::Result<Draft> {
+		let experience = ExperienceRepository::from_snapshot(&*self.database.pool, &draft.experience).await?;
+		let skills = serde_json::from_value(draft.skills).context("Failed to read draft skills")?;
+
+		Ok(Draft {
+			id: draft.id,
+			user_id: draft.user_id,
+			experience,
+			skills,
+			created_at: draft.created_at,
+			updated_at: draft.updated_at,
+		})
+	}
+
+	/// Opens a draft seeded with the published sections, unless the user already has one
+	pub async fn start(&self, user_id: &i64) -> anyhow::Result<()> {
+		let experience = HistoryRepository::snapshot_with(&*self.database.pool, user_id, Section::Experience).await?;
+		let skills = HistoryRepository::snapshot_with(&*self.database.pool, user_id, Section::Skills).await?;
+
+		sqlx::query!(
+			r#"
+				INSERT INTO profile_drafts (user_id, experience, skills)
+				VALUES ($1, $2, $3)
+				ON CONFLICT (user_id) DO NOTHING
+			"#,
+			user_id,
+			experience,
+			skills
+		)
+		.execute(&*self.database.pool)
+		.await
+		.map_err(|error| anyhow!(error).context("Failed to start draft"))?;
+
+		Ok(())
+	}
+
+	/// Stages a new entry and returns its id, reserved up front so publishing keeps it
+	pub async fn add_experience(&self, user_id: &i64, experience_dto: &ExperienceDto) -> anyhow::Result<i64> {
+		self.start(user_id).await?;
+
+		let id = sqlx::query_scalar!(
+			r#"
+				UPDATE profile_drafts
+				SET experience = experience || JSONB_BUILD_ARRAY(
+						$2::JSONB || JSONB_BUILD_OBJECT('id', NEXTVAL('experience_id_seq'), 'user_id', user_id)
+					),
+					updated_at = CURRENT_TIMESTAMP
+				WHERE user_id = $1
+				RETURNING (experience->-1->>'id')::BIGINT AS "id!"
+			"#,
+			user_id,
+			experience_fields(experience_dto)
+		)
+		.fetch_one(&*self.database.pool)
+		.await
+		.map_err(|error| anyhow!(error).context("Failed to add draft experience"))?;
+
+		Ok(id)
+	}
+
+	/// Returns `false` when the draft has no entry with that id
+	pub async fn update_experience(
+		&self,
+		user_id: &i64,
+		id: &i64,
+		experience_dto: &ExperienceDto,
+	) -> anyhow::Result<bool> {
+		self.start(user_id).await?;
+
+		let result = sqlx::query!(
+			r#"
+				UPDATE profile_drafts
+				SET experience = (
+						SELECT JSONB_AGG(
+							CASE WHEN (entry->>'id')::BIGINT = $2 THEN entry || $3::JSONB || staged_location ELSE entry END
+							ORDER BY position
+						)
+						FROM JSONB_ARRAY_ELEMENTS(experience) WITH ORDINALITY AS entries (entry, position)
+						-- A new location name without a newly picked city resolves again on publish
+						CROSS JOIN LATERAL (
+							SELECT CASE
+								WHEN entry->>'location_name' IS DISTINCT FROM $3::JSONB->>'location_name'
+									AND entry->'location_id' = $3::JSONB->'location_id'
+								THEN JSONB_BUILD_OBJECT('location_id', NULL)
+								ELSE '{}'
+							END AS staged_location
+						) AS staged
+					),
+					updated_at = CURRENT_TIMESTAMP
+				WHERE user_id = $1 AND experience @> JSONB_BUILD_ARRAY(JSONB_BUILD_OBJECT('id', $2::BIGINT))
+			"#,
+			user_id,
+			id,
+			experience_fields(experience_dto)
+		)
+		.execute(&*self.database.pool)
+		.await
+		.map_err(|error| anyhow!(error).context("Failed to update draft experience"))?;
+
+		Ok(result.rows_affected() > 0)
+	}
+
+	/// Returns `false` when the draft has no entry with that id
+	pub async fn delete_experience(&self, user_id: &i64, id: &i64) -> anyhow::Result<bool> {
+		self.start(user_id).await?;
+
+		let result = sqlx::query!(
+			r#"
+				UPDATE profile_drafts
+				SET experience = (
+						SELECT COALESCE(JSONB_AGG(entry ORDER BY position), '[]')
+						FROM JSONB_ARRAY_ELEMENTS(experience) WITH ORDINALITY AS entries (entry, position)
+						WHERE (entry->>'id')::BIGINT <> $2
+					),
+					updated_at = CURRENT_TIMESTAMP
+				WHERE user_id = $1 AND experience @> JSONB_BUILD_ARRAY(JSONB_BUILD_OBJECT('id', $2::BIGINT))
+			"#,
+			user_id,
+			id
+		)
+		.execute(&*self.database.pool)
+		.await
+		.map_err(|error| anyhow!(error).context("Failed to delete draft experience"))?;
+
+		Ok(result.rows_affected() > 0)
+	}
+
+	/// Replaces the staged skills, in the given order; ids missing from the catalog are dropped
+	pub async fn update_skills(&self, user_id: &i64, skills: &[UserSkillDto]) -> anyhow::Result<()> {
+		self.start(user_id).await?;
+
+		// Staged in the shape of skills revisions, so publishing restores them like one
+		let staged: Vec<Value> = skills
+			.iter()
+			.zip(1..)
+			.map(|(skill, position): (_, i32)| {
+				json!({
+					"id": skill.id,
+					"proficiency": skill.proficiency,
+					"years": skill.years,
+					"last_used": skill.last_used,
+					"position": position,
+					"is_pinned": skill.is_pinned,
+				})
+			})
+			.collect();
+
+		sqlx::query!(
+			r#"
+				UPDATE profile_drafts
+				SET skills = (
+						SELECT COALESCE(JSONB_AGG(staged.skill || JSONB_BUILD_OBJECT('name', s.name) ORDER BY s.id), '[]')
+						FROM JSONB_ARRAY_ELEMENTS($2) AS staged (skill)
+						JOIN skills s ON s.id = (staged.skill->>'id')::BIGINT
+					),
+					updated_at = CURRENT_TIMESTAMP
+				WHERE user_id = $1
+			"#,
+			user_id,
+			Value::from(staged)
+		)
+		.execute(&*self.database.pool)
+		.await
+		.map_err(|error| anyhow!(error).context("Failed to update draft skills"))?;
+
+		Ok(())
+	}
+
+	/// Returns `false` when there was no draft to discard
+	pub async fn discard(&self, user_id: &i64) -> anyhow::Result<bool> {
+		let result = sqlx::query!("DELETE FROM profile_drafts WHERE user_id = $1", user_id)
+			.execute(&*self.database.pool)
+			.await
+			.map_err(|error| anyhow!(error).context("Failed to discard draft"))?;
+
+		Ok(result.rows_affected() > 0)
+	}
+
+	/// Replaces the published sections with the draft's in one transaction, recording revisions and
+	/// closing the draft. Returns `false` when there was no draft to publish.
+	pub async fn publish(&self, user_id: &i64) -> anyhow::Result<bool> {
+		let mut transaction = self
+			.database
+			.pool
+			.begin()
+			.await
+			.context("Failed to start publish transaction")?;
+
+		let draft = sqlx::query!(
+			"DELETE FROM profile_drafts WHERE user_id = $1 RETURNING experience, skills",
+			user_id
+		)
+		.fetch_optional(&mut *transaction)
+		.await
+		.map_err(|error| anyhow!(error).context("Failed to take draft"))?;
+
+		let Some(draft) = draft else {
+			return Ok(false);
+		};
+
+		HistoryRepository::replace_with(&mut transaction, user_id, Section::Experience, &draft.experience, None)
+			.await?;
+		HistoryRepository::replace_with(&mut transaction, user_id, Section::Skills, &draft.skills, None).await?;
+
+		transaction.commit().await.context("Failed to commit publish")?;
+
+		Ok(true)
+	}
+}
+
+/// Entry fields keyed like `experience` columns, so snapshots can be written back with `JSONB_POPULATE_RECORDSET`
+fn experience_fields(experience_dto: &ExperienceDto) -> Value {
+	json!({
+		"company_id": experience_dto.company_id,
+		"company_name": experience_dto.company_name,
+		"occupation": experience_dto.occupation,
+		"location_name": experience_dto.location_name,
+		"location_id": experience_dto.location_id,
+		"location_type": experience_dto.location_type,
+		"employment_type": experience_dto.employment_type,
+		"start_date": experience_dto.start_date,
+		"end_date": experience_dto.end_date,
+		"is_current": experience_dto.is_current,
+		"description": experience_dto.description,
+		"skill_ids": experience_dto.skill_ids,
+	})
+}
+
+struct DraftRow {
+	id: i64,
+	user_id: i64,
+	experience: Value,
+	skills: Value,
+	created_at: DateTime<Utc>,
+	updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Draft {
+	pub id: i64,
+	#[serde(skip)]
+	pub user_id: i64,
+	pub experience: Vec<Experience>,
+	pub skills: Vec<Skill>,
+	pub created_at: DateTime<Utc>,
+	pub updated_at: DateTime<Utc>,
+}
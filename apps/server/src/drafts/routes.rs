@@ -0,0 +1,278 @@
+use std::sync::Arc;
+
+use axum::{
+	extract::{Path, Query, State},
+	http::StatusCode,
+	middleware,
+	response::{IntoResponse, Response},
+	routing::{delete, get, post, put},
+	Extension, Json, Router,
+};
+use chrono::{DateTime, Duration, Utc};
+use thiserror::Error;
+use validator::{Validate, ValidationErrors};
+
+use crate::{
+	app::{ApiErrorResponse, ApiResult},
+	database::Database,
+	experience::{dtos::ExperienceDto, tenure::Tenure},
+	skills::dtos::UpdateUserSkillsDto,
+	users::{
+		auth::{self, CurrentUser},
+		dtos::PublicUserResponse,
+		repository::UsersRepostory,
+	},
+	validation::ValidatedJson,
+};
+
+use super::{
+	dtos::{DraftPreviewResponse, PreviewLinkResponse, PreviewQuery},
+	preview,
+	repository::{Draft, DraftsRepository},
+};
+
+#[derive(Clone)]
+pub struct DraftsState {
+	pub drafts_repository: DraftsRepository,
+	pub users_repository: UsersRepostory,
+}
+
+impl DraftsState {
+	pub fn new(database: &Arc<Database>) -> Self {
+		Self {
+			drafts_repository: DraftsRepository::new(database),
+			users_repository: UsersRepostory::new(database),
+		}
+	}
+}
+
+pub fn init() -> Router<DraftsState> {
+	Router::new()
+		.route("/users/me/draft", get(get_draft_route))
+		.route("/users/me/draft", delete(discard_draft_route))
+		.route("/users/me/draft/experience", post(create_draft_experience_route))
+		.route(
+			"/users/me/draft/experience/{experience_id}",
+			put(update_draft_experience_route),
+		)
+		.route(
+			"/users/me/draft/experience/{experience_id}",
+			delete(delete_draft_experience_route),
+		)
+		.route("/users/me/draft/skills", post(update_draft_skills_route))
+		.route("/users/me/draft/preview", post(create_preview_link_route))
+		.route("/users/me/draft/publish", post(publish_draft_route))
+		.route_layer(middleware::from_fn(auth::middleware))
+		.route("/preview/{token}", get(preview_draft_route))
+}
+
+async fn get_draft_route(
+	Extension(current_user): Extension<CurrentUser>,
+	State(state): State<DraftsState>,
+) -> ApiResult<Json<Draft>> {
+	let draft = state
+		.drafts_repository
+		.find_by_user_id(&current_user.user_id)
+		.await
+		.map_err(|_| DraftsApiError::Get())?
+		.ok_or(DraftsApiError::NotFound())?;
+
+	Ok((StatusCode::OK, Json(draft)))
+}
+
+async fn discard_draft_route(
+	Extension(current_user): Extension<CurrentUser>,
+	State(state): State<DraftsState>,
+) -> ApiResult<()> {
+	let discarded = state
+		.drafts_repository
+		.discard(&current_user.user_id)
+		.await
+		.map_err(|_| DraftsApiError::Update())?;
+
+	if !discarded {
+		return Err(DraftsApiError::NotFound().into());
+	}
+
+	Ok((StatusCode::OK, ()))
+}
+
+async fn create_draft_experience_route(
+	Extension(current_user): Extension<CurrentUser>,
+	State(state): State<DraftsState>,
+	ValidatedJson(request): ValidatedJson<ExperienceDto>,
+) -> ApiResult<Json<i64>> {
+	let experience_id = state
+		.drafts_repository
+		.add_experience(&current_user.user_id, &request)
+		.await
+		.map_err(|_| DraftsApiError::Update())?;
+
+	Ok((StatusCode::CREATED, Json(experience_id)))
+}
+
+async fn update_draft_experience_route(
+	Extension(current_user): Extension<CurrentUser>,
+	State(state): State<DraftsState>,
+	Path(experience_id): Path<i64>,
+	ValidatedJson(request): ValidatedJson<ExperienceDto>,
+) -> ApiResult<()> {
+	let updated = state
+		.drafts_repository
+		.update_experience(&current_user.user_id, &experience_id, &request)
+		.await
+		.map_err(|_| DraftsApiError::Update())?;
+
+	if !updated {
+		return Err(DraftsApiError::ExperienceNotFound(experience_id).into());
+	}
+
+	Ok((StatusCode::OK, ()))
+}
+
+async fn delete_draft_experience_route(
+	Extension(current_user): Extension<CurrentUser>,
+	State(state): State<DraftsState>,
+	Path(experience_id): Path<i64>,
+) -> ApiResult<()> {
+	let deleted = state
+		.drafts_repository
+		.delete_experience(&current_user.user_id, &experience_id)
+		.await
+		.map_err(|_| DraftsApiError::Update())?;
+
+	if !deleted {
+		return Err(DraftsApiError::ExperienceNotFound(experience_id).into());
+	}
+
+	Ok((StatusCode::OK, ()))
+}
+
+async fn update_draft_skills_route(
+	Extension(current_user): Extension<CurrentUser>,
+	State(state): State<DraftsState>,
+	ValidatedJson(request): ValidatedJson<UpdateUserSkillsDto>,
+) -> ApiResult<()> {
+	state
+		.drafts_repository
+		.update_skills(&current_user.user_id, &request.skills)
+		.await
+		.map_err(|_| DraftsApiError::Update())?;
+
+	Ok((StatusCode::OK, ()))
+}
+
+async fn create_preview_link_route(
+	Extension(current_user): Extension<CurrentUser>,
+	Query(query): Query<PreviewQuery>,
+	State(state): State<DraftsState>,
+) -> ApiResult<Json<PreviewLinkResponse>> {
+	query.validate().map_err(DraftsApiError::InvalidQuery)?;
+
+	let draft = state
+		.drafts_repository
+		.find_by_user_id(&current_user.user_id)
+		.await
+		.map_err(|_| DraftsApiError::Get())?
+		.ok_or(DraftsApiError::NotFound())?;
+
+	let expires_at = Utc::now() + Duration::hours(query.expires_in_hours);
+	let token = preview::create_token(draft.id, expires_at).map_err(|_| DraftsApiError::CreatePreview())?;
+
+	Ok((StatusCode::CREATED, Json(PreviewLinkResponse { token, expires_at })))
+}
+
+async fn publish_draft_route(
+	Extension(current_user): Extension<CurrentUser>,
+	State(state): State<DraftsState>,
+) -> ApiResult<()> {
+	let published = state
+		.drafts_repository
+		.publish(&current_user.user_id)
+		.await
+		.map_err(|_| DraftsApiError::Publish())?;
+
+	if !published {
+		return Err(DraftsApiError::NotFound().into());
+	}
+
+	Ok((StatusCode::OK, ()))
+}
+
+async fn preview_draft_route(
+	Path(token): Path<String>,
+	State(state): State<DraftsState>,
+) -> ApiResult<Json<DraftPreviewResponse>> {
+	let claims = preview::verify_token(&token).map_err(|_| DraftsApiError::InvalidPreviewLink())?;
+
+	// Publishing or discarding removes the draft, which revokes its links
+	let draft = state
+		.drafts_repository
+		.find_by_id(&claims.draft_id)
+		.await
+		.map_err(|_| DraftsApiError::Get())?
+		.ok_or(DraftsApiError::InvalidPreviewLink())?;
+
+	let user = state
+		.users_repository
+		.find_user_by_id(&draft.user_id)
+		.await
+		.map_err(|_| DraftsApiError::Get())?;
+
+	let locale = user.default_locale.clone();
+	let expires_at = DateTime::from_timestamp(claims.exp, 0).unwrap_or_default();
+
+	// Tenure is computed from the staged entries, as the profile would show it once published
+	let mut user_response = PublicUserResponse::from_user(user, locale);
+	user_response.tenure = Some(Tenure::new(&draft.experience, Utc::now().date_naive()));
+
+	Ok((
+		StatusCode::OK,
+		Json(DraftPreviewResponse {
+			user: user_response,
+			experience: draft.experience,
+			skills: draft.skills,
+			expires_at,
+		}),
+	))
+}
+
+#[derive(Debug, Error)]
+pub enum DraftsApiError {
+	#[error("Invalid preview query: {0}")]
+	InvalidQuery(ValidationErrors),
+
+	#[error("No draft in progress")]
+	NotFound(),
+
+	#[error("Draft experience not found: {0}")]
+	ExperienceNotFound(i64),
+
+	#[error("Preview link is invalid or has expired")]
+	InvalidPreviewLink(),
+
+	#[error("Failed to get draft")]
+	Get(),
+
+	#[error("Failed to update draft")]
+	Update(),
+
+	#[error("Failed to create preview link")]
+	CreatePreview(),
+
+	#[error("Failed to publish draft")]
+	Publish(),
+}
+
+impl IntoResponse for DraftsApiError {
+	fn into_response(self) -> Response {
+		let status_code = match self {
+			Self::InvalidQuery(_) => StatusCode::BAD_REQUEST,
+			Self::NotFound() | Self::ExperienceNotFound(_) | Self::InvalidPreviewLink() => StatusCode::NOT_FOUND,
+			Self::Get() | Self::Update() | Self::CreatePreview() | Self::Publish() => StatusCode::INTERNAL_SERVER_ERROR,
+		};
+
+		log::error!("{self:?}");
+		ApiErrorResponse::new(status_code, self.to_string()).into_response()
+	}
+}
@@ -0,0 +1,27 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct EducationDto {
+	#[validate(length(min = 1, max = 255, message = "Wrong institution length"))]
+	pub institution: String,
+	#[validate(length(min = 1, max = 255, message = "Wrong degree length"))]
+	pub degree: Option<String>,
+	#[validate(length(min = 1, max = 255, message = "Wrong field of study length"))]
+	pub field_of_study: Option<String>,
+	pub start_date: NaiveDate,
+	pub end_date: Option<NaiveDate>,
+	#[validate(length(min = 1, max = 100, message = "Wrong grade length"))]
+	pub grade: Option<String>,
+	pub activities: Option<String>,
+	pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ReorderEducationDto {
+	#[validate(length(min = 1, message = "At least one education entry is required"))]
+	pub ids: Vec<i64>,
+}
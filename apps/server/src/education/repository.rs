@@ -0,0 +1,149 @@
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use sqlx::PgExecutor;
+
+use crate::database::Database;
+
+use super::dtos::EducationDto;
+
+#[derive(Clone)]
+pub struct EducationRepository {
+	pub(crate) database: Arc<Database>,
+}
+
+impl EducationRepository {
+	pub fn new(database: &Arc<Database>) -> Self {
+		Self {
+			database: Arc::clone(database),
+		}
+	}
+
+	/// Inserts an education entry through any executor, so callers can batch it into a transaction
+	pub async fn insert(
+		executor: impl PgExecutor<'_>,
+		user_id: &i64,
+		education_dto: EducationDto,
+	) -> anyhow::Result<Education> {
+		let education = sqlx::query_as!(
+			Education,
+			r#"
+				INSERT INTO education (user_id, institution, degree, field_of_study, start_date, end_date, grade, activities, description)
+				VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+				RETURNING *
+			"#,
+			user_id,
+			education_dto.institution,
+			education_dto.degree,
+			education_dto.field_of_study,
+			education_dto.start_date,
+			education_dto.end_date,
+			education_dto.grade,
+			education_dto.activities,
+			education_dto.description
+		)
+		.fetch_one(executor)
+		.await
+		.map_err(|error| anyhow!(error).context("Failed to create education"))?;
+
+		Ok(education)
+	}
+
+	pub async fn update(
+		executor: impl PgExecutor<'_>,
+		id: &i64,
+		user_id: &i64,
+		education_dto: EducationDto,
+	) -> anyhow::Result<Education> {
+		let education = sqlx::query_as!(
+			Education,
+			r#"
+				UPDATE education
+				SET institution = $3, degree = $4, field_of_study = $5, start_date = $6, end_date = $7, grade = $8, activities = $9, description = $10
+				WHERE id = $1 AND user_id = $2
+				RETURNING *
+			"#,
+			id,
+			user_id,
+			education_dto.institution,
+			education_dto.degree,
+			education_dto.field_of_study,
+			education_dto.start_date,
+			education_dto.end_date,
+			education_dto.grade,
+			education_dto.activities,
+			education_dto.description
+		)
+		.fetch_one(executor)
+		.await
+		.map_err(|error| anyhow!(error).context("Failed to update education"))?;
+
+		Ok(education)
+	}
+
+	pub async fn delete(executor: impl PgExecutor<'_>, id: &i64, user_id: &i64) -> anyhow::Result<()> {
+		sqlx::query!("DELETE FROM education WHERE id = $1 AND user_id = $2", id, user_id)
+			.execute(executor)
+			.await
+			.map_err(|error| anyhow!(error).context("Failed to delete education"))?;
+
+		Ok(())
+	}
+
+	/// Manually ordered entries come first in the given order, the rest follow newest first
+	pub async fn get_by_user_id(&self, user_id: i64) -> anyhow::Result<Vec<Education>> {
+		let education = sqlx::query_as!(
+			Education,
+			"SELECT * FROM education WHERE user_id = $1 ORDER BY position ASC NULLS LAST, start_date DESC",
+			user_id
+		)
+		.fetch_all(&*self.database.pool)
+		.await
+		.map_err(|error| anyhow!(error).context("Failed to get education by user id"))?;
+
+		Ok(education)
+	}
+
+	/// Positions the listed entries in the given order; entries left out fall back to date ordering
+	/// and ids that don't belong to the user are ignored
+	pub async fn reorder(executor: impl PgExecutor<'_>, user_id: &i64, ids: &[i64]) -> anyhow::Result<()> {
+		sqlx::query!(
+			r#"
+				UPDATE education
+				SET position = ordered.position::INTEGER
+				FROM (
+					SELECT education.id, ids.position
+					FROM education
+					LEFT JOIN UNNEST($2::BIGINT[]) WITH ORDINALITY AS ids (id, position) ON ids.id = education.id
+					WHERE education.user_id = $1
+				) AS ordered
+				WHERE education.id = ordered.id
+			"#,
+			user_id,
+			ids
+		)
+		.execute(executor)
+		.await
+		.map_err(|error| anyhow!(error).context("Failed to reorder education"))?;
+
+		Ok(())
+	}
+}
+
+#[derive(Debug, Deserialize, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Education {
+	user_id: i64,
+	pub id: i64,
+	pub institution: String,
+	pub degree: Option<String>,
+	pub field_of_study: Option<String>,
+	pub start_date: NaiveDate,
+	pub end_date: Option<NaiveDate>,
+	pub grade: Option<String>,
+	pub activities: Option<String>,
+	pub description: Option<String>,
+	pub position: Option<i32>,
+}
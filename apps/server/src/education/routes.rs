@@ -0,0 +1,174 @@
+use std::sync::Arc;
+
+use axum::{
+	extract::{Path, State},
+	http::StatusCode,
+	middleware,
+	response::{IntoResponse, Response},
+	routing::{delete, get, post, put},
+	Extension, Json, Router,
+};
+use thiserror::Error;
+
+use crate::{
+	app::{ApiErrorResponse, ApiResult},
+	database::Database,
+	history::repository::{HistoryRepository, Section},
+	users::auth::{self, CurrentUser},
+	validation::ValidatedJson,
+};
+
+use super::{
+	dtos::{EducationDto, ReorderEducationDto},
+	repository::{Education, EducationRepository},
+};
+
+#[derive(Clone)]
+pub struct EducationState {
+	pub education_repository: EducationRepository,
+	pub history_repository: HistoryRepository,
+}
+
+impl EducationState {
+	pub fn new(database: &Arc<Database>) -> Self {
+		Self {
+			education_repository: EducationRepository::new(database),
+			history_repository: HistoryRepository::new(database),
+		}
+	}
+}
+
+pub fn init() -> Router<EducationState> {
+	Router::new()
+		.route("/education", post(create_education_route))
+		.route("/education/order", put(reorder_education_route))
+		.route("/education/{education_id}", put(update_education_route))
+		.route("/education/{education_id}", delete(delete_education_route))
+		.route_layer(middleware::from_fn(auth::middleware))
+		.route("/{user_id}/education", get(user_education_route))
+}
+
+async fn create_education_route(
+	Extension(current_user): Extension<CurrentUser>,
+	State(state): State<EducationState>,
+	ValidatedJson(request): ValidatedJson<EducationDto>,
+) -> ApiResult<Json<i64>> {
+	let mut transaction = state
+		.history_repository
+		.track(&current_user.user_id, &current_user.user_id, &[Section::Education])
+		.await
+		.map_err(|_| EducationApiError::Create())?;
+
+	let education = EducationRepository::insert(&mut **transaction, &current_user.user_id, request)
+		.await
+		.map_err(|_| EducationApiError::Create())?;
+
+	transaction.commit().await.map_err(|_| EducationApiError::Create())?;
+
+	Ok((StatusCode::CREATED, Json(education.id)))
+}
+
+async fn update_education_route(
+	Extension(current_user): Extension<CurrentUser>,
+	State(state): State<EducationState>,
+	Path(education_id): Path<i64>,
+	ValidatedJson(request): ValidatedJson<EducationDto>,
+) -> ApiResult<()> {
+	let mut transaction = state
+		.history_repository
+		.track(&current_user.user_id, &current_user.user_id, &[Section::Education])
+		.await
+		.map_err(|_| EducationApiError::Update())?;
+
+	EducationRepository::update(&mut **transaction, &education_id, &current_user.user_id, request)
+		.await
+		.map_err(|_| EducationApiError::Update())?;
+
+	transaction.commit().await.map_err(|_| EducationApiError::Update())?;
+
+	Ok((StatusCode::OK, ()))
+}
+
+async fn delete_education_route(
+	Extension(current_user): Extension<CurrentUser>,
+	State(state): State<EducationState>,
+	Path(education_id): Path<i64>,
+) -> ApiResult<()> {
+	let mut transaction = state
+		.history_repository
+		.track(&current_user.user_id, &current_user.user_id, &[Section::Education])
+		.await
+		.map_err(|_| EducationApiError::Delete())?;
+
+	EducationRepository::delete(&mut **transaction, &education_id, &current_user.user_id)
+		.await
+		.map_err(|_| EducationApiError::Delete())?;
+
+	transaction.commit().await.map_err(|_| EducationApiError::Delete())?;
+
+	Ok((StatusCode::OK, ()))
+}
+
+async fn reorder_education_route(
+	Extension(current_user): Extension<CurrentUser>,
+	State(state): State<EducationState>,
+	ValidatedJson(request): ValidatedJson<ReorderEducationDto>,
+) -> ApiResult<()> {
+	let mut transaction = state
+		.history_repository
+		.track(&current_user.user_id, &current_user.user_id, &[Section::Education])
+		.await
+		.map_err(|_| EducationApiError::Reorder())?;
+
+	EducationRepository::reorder(&mut **transaction, &current_user.user_id, &request.ids)
+		.await
+		.map_err(|_| EducationApiError::Reorder())?;
+
+	transaction.commit().await.map_err(|_| EducationApiError::Reorder())?;
+
+	Ok((StatusCode::OK, ()))
+}
+
+async fn user_education_route(
+	Path(user_id): Path<i64>,
+	State(state): State<EducationState>,
+) -> ApiResult<Json<Vec<Education>>> {
+	let user_education = state
+		.education_repository
+		.get_by_user_id(user_id)
+		.await
+		.map_err(|_| EducationApiError::GetUser())?;
+
+	Ok((StatusCode::OK, Json(user_education)))
+}
+
+#[derive(Debug, Error)]
+pub enum EducationApiError {
+	#[error("Failed to get user education")]
+	GetUser(),
+
+	#[error("Failed to create education")]
+	Create(),
+
+	#[error("Failed to update education")]
+	Update(),
+
+	#[error("Failed to delete education")]
+	Delete(),
+
+	#[error("Failed to reorder education")]
+	Reorder(),
+}
+
+impl IntoResponse for EducationApiError {
+	fn into_response(self) -> Response {
+		let status_code = match self {
+			Self::GetUser() | Self::Create() | Self::Update() | Self::Delete() | Self::Reorder() => {
+				StatusCode::INTERNAL_SERVER_ERROR
+			}
+		};
+
+		log::error!("{self:?}");
+		ApiErrorResponse::new(status_code, self.to_string()).into_response()
+	}
+}
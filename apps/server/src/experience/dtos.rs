@@ -1,25 +1,129 @@
-use chrono::{DateTime, Utc};
-use serde::Deserialize;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
 use validator::{Validate, ValidationError};
 
-#[derive(Debug, Deserialize, Validate)]
+use crate::validation::{double_option, field_error};
+
+use super::{repository::Experience, year_month::YearMonth};
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
 #[serde(rename_all = "camelCase")]
+#[validate(schema(function = "validate_end_date", skip_on_field_errors = false))]
 pub struct ExperienceDto {
+	/// A company picked from autocomplete; when omitted, unknown or not what the company name refers to, one is
+	/// resolved from the company name
+	pub company_id: Option<i64>,
 	#[validate(length(min = 1, max = 255, message = "Wrong company name length"))]
 	pub company_name: String,
 	#[validate(length(min = 1, max = 255, message = "Wrong occupation length"))]
 	pub occupation: String,
 	#[validate(length(min = 1, max = 255, message = "Wrong location name length"))]
 	pub location_name: String,
+	/// A city picked from autocomplete; when omitted or unknown, one is resolved from the location name
+	pub location_id: Option<i64>,
 	#[validate(custom(function = "validate_location_type"))]
 	pub location_type: String,
 	#[validate(custom(function = "validate_employment_type"))]
 	pub employment_type: String,
-	pub start_date: DateTime<Utc>,
-	pub end_date: Option<DateTime<Utc>>,
+	#[validate(custom(function = "validate_start_date"))]
+	pub start_date: YearMonth,
+	pub end_date: Option<YearMonth>,
 	pub is_current: bool,
-	#[validate(length(min = 1, message = "Description is required"))]
+	/// Markdown, served alongside its sanitized HTML rendering
+	#[validate(length(min = 1, max = 10000, message = "Description must be between 1 and 10000 characters"))]
 	pub description: String,
+	/// Catalog skills used in the role, replacing the ones linked before
+	#[serde(default)]
+	#[validate(length(max = 50, message = "At most 50 skills can be linked to an entry"))]
+	pub skill_ids: Vec<i64>,
+}
+
+/// Translated variants of an experience entry's text; untranslated fields fall back to the untranslated text.
+/// Fields left out are kept as they are, and fields sent as `null` are cleared.
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+#[allow(clippy::option_option)]
+pub struct ExperienceTranslationDto {
+	#[serde(default, deserialize_with = "double_option")]
+	#[validate(length(min = 1, max = 255, message = "Wrong occupation length"))]
+	pub occupation: Option<Option<String>>,
+	#[serde(default, deserialize_with = "double_option")]
+	#[validate(length(min = 1, max = 10000, message = "Description must be between 1 and 10000 characters"))]
+	pub description: Option<Option<String>>,
+}
+
+impl ExperienceTranslationDto {
+	/// Names of the fields sent as `null`
+	pub fn cleared(&self) -> Vec<String> {
+		[
+			("occupation", matches!(self.occupation, Some(None))),
+			("description", matches!(self.description, Some(None))),
+		]
+		.into_iter()
+		.filter(|(_, is_cleared)| *is_cleared)
+		.map(|(field, _)| field.to_string())
+		.collect()
+	}
+}
+
+/// An entry as shown on a profile, with its Markdown description rendered to sanitized HTML
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderedExperience {
+	#[serde(flatten)]
+	pub experience: Experience,
+	pub description_html: String,
+	/// Coworkers who verified the entry, shown as a badge when there are any
+	pub verifier_count: i64,
+	/// Whether the user confirmed an address at the company's domain, and it hasn't run out yet
+	pub work_email_verified: bool,
+}
+
+impl AsRef<Experience> for RenderedExperience {
+	fn as_ref(&self) -> &Experience {
+		&self.experience
+	}
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ReorderExperienceDto {
+	#[validate(length(min = 1, message = "At least one experience entry is required"))]
+	pub ids: Vec<i64>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineQuery {
+	/// Shorter breaks between roles, like a notice period, aren't reported as gaps
+	#[serde(default = "default_min_gap_days")]
+	#[validate(range(min = 1, max = 3650, message = "Minimum gap must be between 1 and 3650 days"))]
+	pub min_gap_days: i64,
+}
+
+const fn default_min_gap_days() -> i64 {
+	30
+}
+
+fn validate_start_date(start_date: &YearMonth) -> Result<(), ValidationError> {
+	if start_date.first_day() <= Utc::now().date_naive() {
+		Ok(())
+	} else {
+		Err(ValidationError::new("start_in_future").with_message("Start date must not be in the future".into()))
+	}
+}
+
+fn validate_end_date(experience_dto: &ExperienceDto) -> Result<(), ValidationError> {
+	let error = match experience_dto.end_date {
+		Some(_) if experience_dto.is_current => ValidationError::new("current_with_end_date")
+			.with_message("A current role must not have an end date".into()),
+		Some(end_date) if end_date < experience_dto.start_date => {
+			ValidationError::new("end_before_start").with_message("End date must not be before the start date".into())
+		}
+		_ => return Ok(()),
+	};
+
+	Err(field_error("endDate", error))
 }
 
 fn validate_location_type(location_type: &str) -> Result<(), ValidationError> {
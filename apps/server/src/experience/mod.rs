@@ -1,4 +1,6 @@
+pub mod dtos;
+pub mod repository;
 pub mod routes;
-
-mod dtos;
-mod repository;
+pub mod tenure;
+pub mod timeline;
+pub mod year_month;
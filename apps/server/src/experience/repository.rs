@@ -1,12 +1,16 @@
 use std::sync::Arc;
 
 use anyhow::anyhow;
-use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::PgExecutor;
 
 use crate::database::Database;
 
-use super::dtos::ExperienceDto;
+use super::{
+	dtos::{ExperienceDto, ExperienceTranslationDto},
+	year_month::YearMonth,
+};
 
 #[derive(Clone)]
 pub struct ExperienceRepository {
@@ -20,13 +24,31 @@ impl ExperienceRepository {
 		}
 	}
 
-	pub async fn create(&self, user_id: &i64, experience_dto: ExperienceDto) -> anyhow::Result<Experience> {
+	/// Inserts an experience entry through any executor, so callers can batch it into a transaction
+	pub async fn insert(
+		executor: impl PgExecutor<'_>,
+		user_id: &i64,
+		experience_dto: ExperienceDto,
+	) -> anyhow::Result<Experience> {
+		// Unknown skill ids are dropped rather than failing the whole entry
 		let experience = sqlx::query_as!(
 			Experience,
 			r#"
-				INSERT INTO experience (user_id, company_name, occupation, location_name, location_type, employment_type, start_date, end_date, is_current, description)
-				VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
-				RETURNING *
+				WITH inserted AS (
+					INSERT INTO experience (user_id, company_name, occupation, location_name, location_type, employment_type, start_date, end_date, is_current, description, company_id, location_id)
+					VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, CASE WHEN company_matches_name($11, $2::VARCHAR) THEN $11 END, $13)
+					RETURNING *
+				), linked AS (
+					INSERT INTO experience_skills (experience_id, skill_id)
+					SELECT inserted.id, skills.id FROM inserted, skills WHERE skills.id = ANY($12)
+				)
+				SELECT
+					user_id AS "user_id!", id AS "id!", company_id, company_name AS "company_name!", occupation AS "occupation!",
+					location_name AS "location_name!", location_id, location_type AS "location_type!", employment_type AS "employment_type!",
+					start_date AS "start_date!: YearMonth", end_date AS "end_date: YearMonth", is_current AS "is_current!",
+					description AS "description!", position,
+					ARRAY(SELECT id FROM skills WHERE id = ANY($12) ORDER BY id) AS "skill_ids!"
+				FROM inserted
 			"#,
 			user_id,
 			experience_dto.company_name,
@@ -34,23 +56,51 @@ impl ExperienceRepository {
 			experience_dto.location_name,
 			experience_dto.location_type,
 			experience_dto.employment_type,
-			experience_dto.start_date,
-			experience_dto.end_date,
+			experience_dto.start_date as YearMonth,
+			experience_dto.end_date as Option<YearMonth>,
 			experience_dto.is_current,
-			experience_dto.description
-		).fetch_one(&*self.database.pool).await.map_err(|error| anyhow!(error).context("Failed to create experience"))?;
+			experience_dto.description,
+			experience_dto.company_id,
+			&experience_dto.skill_ids,
+			experience_dto.location_id
+		).fetch_one(executor).await.map_err(|error| anyhow!(error).context("Failed to create experience"))?;
 
 		Ok(experience)
 	}
 
-	pub async fn update(&self, id: &i64, user_id: &i64, experience_dto: ExperienceDto) -> anyhow::Result<Experience> {
+	pub async fn update(
+		executor: impl PgExecutor<'_>,
+		id: &i64,
+		user_id: &i64,
+		experience_dto: ExperienceDto,
+	) -> anyhow::Result<Experience> {
 		let experience = sqlx::query_as!(
 			Experience,
 			r#"
-				UPDATE experience
-				SET company_name = $3, occupation = $4, location_name = $5, location_type = $6, employment_type = $7, start_date = $8, end_date = $9, is_current = $10, description = $11
-				WHERE id = $1 AND user_id = $2
-				RETURNING *
+				WITH updated AS (
+					UPDATE experience
+					SET company_name = $3, occupation = $4, location_name = $5, location_type = $6, employment_type = $7, start_date = $8, end_date = $9, is_current = $10, description = $11,
+						company_id = CASE WHEN company_matches_name($12, $3::VARCHAR) THEN $12 END,
+						-- A new location name without a newly picked city resolves again, rather than keeping the previous city
+						location_id = CASE WHEN location_name IS DISTINCT FROM $5::VARCHAR AND location_id IS NOT DISTINCT FROM $14 THEN NULL ELSE $14 END
+					WHERE id = $1 AND user_id = $2
+					RETURNING *
+				), unlinked AS (
+					DELETE FROM experience_skills es
+					USING updated
+					WHERE es.experience_id = updated.id AND NOT es.skill_id = ANY($13)
+				), linked AS (
+					INSERT INTO experience_skills (experience_id, skill_id)
+					SELECT updated.id, skills.id FROM updated, skills WHERE skills.id = ANY($13)
+					ON CONFLICT DO NOTHING
+				)
+				SELECT
+					user_id AS "user_id!", id AS "id!", company_id, company_name AS "company_name!", occupation AS "occupation!",
+					location_name AS "location_name!", location_id, location_type AS "location_type!", employment_type AS "employment_type!",
+					start_date AS "start_date!: YearMonth", end_date AS "end_date: YearMonth", is_current AS "is_current!",
+					description AS "description!", position,
+					ARRAY(SELECT id FROM skills WHERE id = ANY($13) ORDER BY id) AS "skill_ids!"
+				FROM updated
 			"#,
 			id, user_id,
 			experience_dto.company_name,
@@ -58,30 +108,69 @@ impl ExperienceRepository {
 			experience_dto.location_name,
 			experience_dto.location_type,
 			experience_dto.employment_type,
-			experience_dto.start_date,
-			experience_dto.end_date,
+			experience_dto.start_date as YearMonth,
+			experience_dto.end_date as Option<YearMonth>,
 			experience_dto.is_current,
 			experience_dto.description,
-
-		).fetch_one(&*self.database.pool).await.map_err(|error| anyhow!(error).context("Failed to update experience"))?;
+			experience_dto.company_id,
+			&experience_dto.skill_ids,
+			experience_dto.location_id
+		).fetch_one(executor).await.map_err(|error| anyhow!(error).context("Failed to update experience"))?;
 
 		Ok(experience)
 	}
 
-	pub async fn delete(&self, id: &i64, user_id: &i64) -> anyhow::Result<()> {
+	pub async fn delete(executor: impl PgExecutor<'_>, id: &i64, user_id: &i64) -> anyhow::Result<()> {
 		sqlx::query!("DELETE FROM experience WHERE id = $1 AND user_id = $2", id, user_id)
-			.execute(&*self.database.pool)
+			.execute(executor)
 			.await
 			.map_err(|error| anyhow!(error).context("Failed to delete experience"))?;
 
 		Ok(())
 	}
 
-	pub async fn get_by_user_id(&self, user_id: i64) -> anyhow::Result<Vec<Experience>> {
+	pub async fn count_current(&self, user_id: &i64) -> anyhow::Result<i64> {
+		let count = sqlx::query_scalar!(
+			r#"SELECT COUNT(*) AS "count!" FROM experience WHERE user_id = $1 AND is_current"#,
+			user_id
+		)
+		.fetch_one(&*self.database.pool)
+		.await
+		.map_err(|error| anyhow!(error).context("Failed to count current roles"))?;
+
+		Ok(count)
+	}
+
+	/// Overlays the given locale's translations where they exist; `None` returns the untranslated text
+	pub async fn get_by_user_id(&self, user_id: i64, locale: Option<&str>) -> anyhow::Result<Vec<Experience>> {
 		let experience = sqlx::query_as!(
 			Experience,
-			"SELECT * FROM experience WHERE user_id = $1 ORDER BY start_date DESC",
-			user_id
+			r#"
+				SELECT
+					e.user_id,
+					e.id,
+					e.company_id,
+					e.company_name,
+					COALESCE(et.occupation, e.occupation) AS "occupation!",
+					e.location_name,
+					e.location_id,
+					e.location_type,
+					e.employment_type,
+					e.start_date AS "start_date: YearMonth",
+					e.end_date AS "end_date: YearMonth",
+					e.is_current,
+					COALESCE(et.description, e.description) AS "description!",
+					e.position,
+					ARRAY(
+						SELECT es.skill_id FROM experience_skills es WHERE es.experience_id = e.id ORDER BY es.skill_id
+					) AS "skill_ids!"
+				FROM experience e
+				LEFT JOIN experience_translations et ON et.experience_id = e.id AND et.locale = $2
+				WHERE e.user_id = $1
+				ORDER BY e.position ASC NULLS LAST, e.start_date DESC
+			"#,
+			user_id,
+			locale
 		)
 		.fetch_all(&*self.database.pool)
 		.await
@@ -89,6 +178,149 @@ impl ExperienceRepository {
 
 		Ok(experience)
 	}
+
+	/// Reads entries back out of a section snapshot, such as a staged draft, in the same order as the live ones
+	pub async fn from_snapshot(executor: impl PgExecutor<'_>, snapshot: &Value) -> anyhow::Result<Vec<Experience>> {
+		let experience = sqlx::query_as!(
+			Experience,
+			r#"
+				SELECT
+					e.user_id AS "user_id!",
+					e.id AS "id!",
+					e.company_id,
+					e.company_name AS "company_name!",
+					e.occupation AS "occupation!",
+					e.location_name AS "location_name!",
+					e.location_id,
+					e.location_type AS "location_type!",
+					e.employment_type AS "employment_type!",
+					e.start_date AS "start_date!: YearMonth",
+					e.end_date AS "end_date: YearMonth",
+					e.is_current AS "is_current!",
+					e.description AS "description!",
+					e.position,
+					ARRAY(
+						SELECT skill_id::BIGINT FROM JSONB_ARRAY_ELEMENTS_TEXT(COALESCE(entry->'skill_ids', '[]')) AS skill_id
+					) AS "skill_ids!"
+				FROM JSONB_ARRAY_ELEMENTS($1) AS entries (entry)
+				CROSS JOIN LATERAL JSONB_POPULATE_RECORD(NULL::experience, entry) AS e
+				ORDER BY e.position ASC NULLS LAST, e.start_date DESC
+			"#,
+			snapshot
+		)
+		.fetch_all(executor)
+		.await
+		.map_err(|error| anyhow!(error).context("Failed to read experience snapshot"))?;
+
+		Ok(experience)
+	}
+
+	/// Positions the listed entries in the given order; entries left out fall back to date ordering
+	/// and ids that don't belong to the user are ignored
+	pub async fn reorder(executor: impl PgExecutor<'_>, user_id: &i64, ids: &[i64]) -> anyhow::Result<()> {
+		sqlx::query!(
+			r#"
+				UPDATE experience
+				SET position = ordered.position::INTEGER
+				FROM (
+					SELECT experience.id, ids.position
+					FROM experience
+					LEFT JOIN UNNEST($2::BIGINT[]) WITH ORDINALITY AS ids (id, position) ON ids.id = experience.id
+					WHERE experience.user_id = $1
+				) AS ordered
+				WHERE experience.id = ordered.id
+			"#,
+			user_id,
+			ids
+		)
+		.execute(executor)
+		.await
+		.map_err(|error| anyhow!(error).context("Failed to reorder experience"))?;
+
+		Ok(())
+	}
+
+	pub async fn get_translations(&self, id: &i64, user_id: &i64) -> anyhow::Result<Vec<ExperienceTranslation>> {
+		let translations = sqlx::query_as!(
+			ExperienceTranslation,
+			r#"
+				SELECT et.locale, et.occupation, et.description
+				FROM experience_translations et
+				JOIN experience e ON e.id = et.experience_id
+				WHERE et.experience_id = $1 AND e.user_id = $2
+				ORDER BY et.locale
+			"#,
+			id,
+			user_id
+		)
+		.fetch_all(&*self.database.pool)
+		.await
+		.map_err(|error| anyhow!(error).context("Failed to get experience translations"))?;
+
+		Ok(translations)
+	}
+
+	/// Returns `None` when the experience doesn't exist or belongs to someone else
+	pub async fn upsert_translation(
+		executor: impl PgExecutor<'_>,
+		id: &i64,
+		user_id: &i64,
+		locale: &str,
+		translation_dto: ExperienceTranslationDto,
+	) -> anyhow::Result<Option<ExperienceTranslation>> {
+		let translation = sqlx::query_as!(
+			ExperienceTranslation,
+			r#"
+				INSERT INTO experience_translations (experience_id, locale, occupation, description)
+				SELECT id, $3, $4, $5 FROM experience WHERE id = $1 AND user_id = $2
+				ON CONFLICT (experience_id, locale) DO UPDATE
+				SET
+					occupation = CASE
+						WHEN 'occupation' = ANY($6) THEN NULL
+						ELSE COALESCE(EXCLUDED.occupation, experience_translations.occupation)
+					END,
+					description = CASE
+						WHEN 'description' = ANY($6) THEN NULL
+						ELSE COALESCE(EXCLUDED.description, experience_translations.description)
+					END
+				RETURNING locale, occupation, description
+			"#,
+			id,
+			user_id,
+			locale,
+			translation_dto.occupation.clone().flatten(),
+			translation_dto.description.clone().flatten(),
+			&translation_dto.cleared()
+		)
+		.fetch_optional(executor)
+		.await
+		.map_err(|error| anyhow!(error).context("Failed to save experience translation"))?;
+
+		Ok(translation)
+	}
+
+	pub async fn delete_translation(
+		executor: impl PgExecutor<'_>,
+		id: &i64,
+		user_id: &i64,
+		locale: &str,
+	) -> anyhow::Result<()> {
+		sqlx::query!(
+			r#"
+				DELETE FROM experience_translations et
+				USING experience e
+				WHERE e.id = et.experience_id AND et.experience_id = $1 AND e.user_id = $2 AND et.locale = $3
+			"#,
+			id,
+			user_id,
+			locale
+		)
+		.execute(executor)
+		.await
+		.map_err(|error| anyhow!(error).context("Failed to delete experience translation"))?;
+
+		Ok(())
+	}
 }
 
 #[derive(Debug, Deserialize, Serialize, sqlx::FromRow)]
@@ -96,13 +328,28 @@ impl ExperienceRepository {
 pub struct Experience {
 	user_id: i64,
 	pub id: i64,
+	/// The company the name resolved to, absent only for names made of punctuation
+	pub company_id: Option<i64>,
 	pub company_name: String,
 	pub occupation: String,
 	pub location_name: String,
+	/// The city the location name resolved to, absent for remote roles and places outside the dataset
+	pub location_id: Option<i64>,
 	pub location_type: String,
 	pub employment_type: String,
-	pub start_date: DateTime<Utc>,
-	pub end_date: Option<DateTime<Utc>>,
+	pub start_date: YearMonth,
+	pub end_date: Option<YearMonth>,
 	pub is_current: bool,
 	pub description: String,
+	pub position: Option<i32>,
+	/// Catalog skills used in the role
+	pub skill_ids: Vec<i64>,
+}
+
+#[derive(Debug, Deserialize, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ExperienceTranslation {
+	pub locale: String,
+	pub occupation: Option<String>,
+	pub description: Option<String>,
 }
@@ -1,36 +1,59 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use axum::{
-	extract::{Path, State},
-	http::StatusCode,
+	extract::{Path, Query, State},
+	http::{header, HeaderMap, HeaderValue, StatusCode},
 	middleware,
 	response::{IntoResponse, Response},
 	routing::{delete, get, post, put},
 	Extension, Json, Router,
 };
+use chrono::Utc;
 use thiserror::Error;
+use validator::{Validate, ValidationErrors};
 
 use crate::{
 	app::{ApiErrorResponse, ApiResult},
 	database::Database,
-	users::auth::{self, CurrentUser},
+	history::repository::{HistoryRepository, Section},
+	i18n::{normalize_locale, RequestedLocales},
+	services::markdown::MarkdownService,
+	users::{
+		auth::{self, CurrentUser},
+		repository::UsersRepostory,
+	},
 	validation::ValidatedJson,
+	verifications::repository::VerificationsRepository,
+	work_emails::repository::WorkEmailsRepository,
 };
 
 use super::{
-	dtos::ExperienceDto,
-	repository::{Experience, ExperienceRepository},
+	dtos::{ExperienceDto, ExperienceTranslationDto, RenderedExperience, ReorderExperienceDto, TimelineQuery},
+	repository::{ExperienceRepository, ExperienceTranslation},
+	tenure::{group_by_company, CompanyRoles},
+	timeline::TimelineReport,
 };
 
 #[derive(Clone)]
+#[allow(clippy::struct_field_names)]
 pub struct ExperienceState {
 	pub experience_repository: ExperienceRepository,
+	pub users_repository: UsersRepostory,
+	pub history_repository: HistoryRepository,
+	pub markdown_service: MarkdownService,
+	pub verifications_repository: VerificationsRepository,
+	pub work_emails_repository: WorkEmailsRepository,
 }
 
 impl ExperienceState {
 	pub fn new(database: &Arc<Database>) -> Self {
 		Self {
 			experience_repository: ExperienceRepository::new(database),
+			users_repository: UsersRepostory::new(database),
+			history_repository: HistoryRepository::new(database),
+			markdown_service: MarkdownService::new(database),
+			verifications_repository: VerificationsRepository::new(database),
+			work_emails_repository: WorkEmailsRepository::new(database),
 		}
 	}
 }
@@ -38,24 +61,46 @@ impl ExperienceState {
 pub fn init() -> Router<ExperienceState> {
 	Router::new()
 		.route("/experience", post(create_experience_route))
+		.route("/experience/order", put(reorder_experience_route))
 		.route("/experience/{experience_id}", put(update_experience_route))
 		.route("/experience/{experience_id}", delete(delete_experience_route))
+		.route(
+			"/experience/{experience_id}/translations",
+			get(experience_translations_route),
+		)
+		.route(
+			"/experience/{experience_id}/translations/{locale}",
+			put(upsert_experience_translation_route),
+		)
+		.route(
+			"/experience/{experience_id}/translations/{locale}",
+			delete(delete_experience_translation_route),
+		)
 		.route_layer(middleware::from_fn(auth::middleware))
 		.route("/{user_id}/experience", get(user_experiences_route))
+		.route("/{user_id}/experience/timeline", get(user_timeline_route))
 }
 
 async fn create_experience_route(
 	Extension(current_user): Extension<CurrentUser>,
 	State(state): State<ExperienceState>,
 	ValidatedJson(request): ValidatedJson<ExperienceDto>,
-) -> ApiResult<Json<i64>> {
-	let experience = state
-		.experience_repository
-		.create(&current_user.user_id, request)
+) -> ApiResult<(HeaderMap, Json<i64>)> {
+	let mut transaction = state
+		.history_repository
+		.track(&current_user.user_id, &current_user.user_id, &[Section::Experience])
 		.await
 		.map_err(|_| ExperienceApiError::Create())?;
 
-	Ok((StatusCode::CREATED, Json(experience.id)))
+	let experience = ExperienceRepository::insert(&mut **transaction, &current_user.user_id, request)
+		.await
+		.map_err(|_| ExperienceApiError::Create())?;
+
+	transaction.commit().await.map_err(|_| ExperienceApiError::Create())?;
+
+	let headers = current_roles_warning(&state, &current_user.user_id).await;
+
+	Ok((StatusCode::CREATED, (headers, Json(experience.id))))
 }
 
 async fn update_experience_route(
@@ -63,14 +108,22 @@ async fn update_experience_route(
 	State(state): State<ExperienceState>,
 	Path(experience_id): Path<i64>,
 	ValidatedJson(request): ValidatedJson<ExperienceDto>,
-) -> ApiResult<()> {
-	state
-		.experience_repository
-		.update(&experience_id, &current_user.user_id, request)
+) -> ApiResult<HeaderMap> {
+	let mut transaction = state
+		.history_repository
+		.track(&current_user.user_id, &current_user.user_id, &[Section::Experience])
 		.await
 		.map_err(|_| ExperienceApiError::Update())?;
 
-	Ok((StatusCode::OK, ()))
+	ExperienceRepository::update(&mut **transaction, &experience_id, &current_user.user_id, request)
+		.await
+		.map_err(|_| ExperienceApiError::Update())?;
+
+	transaction.commit().await.map_err(|_| ExperienceApiError::Update())?;
+
+	let headers = current_roles_warning(&state, &current_user.user_id).await;
+
+	Ok((StatusCode::OK, headers))
 }
 
 async fn delete_experience_route(
@@ -78,26 +131,220 @@ async fn delete_experience_route(
 	State(state): State<ExperienceState>,
 	Path(experience_id): Path<i64>,
 ) -> ApiResult<()> {
-	state
-		.experience_repository
-		.delete(&experience_id, &current_user.user_id)
+	// Deleting an entry also deletes its translations
+	let mut transaction = state
+		.history_repository
+		.track(
+			&current_user.user_id,
+			&current_user.user_id,
+			&[Section::Experience, Section::Translations],
+		)
+		.await
+		.map_err(|_| ExperienceApiError::Delete())?;
+
+	ExperienceRepository::delete(&mut **transaction, &experience_id, &current_user.user_id)
 		.await
 		.map_err(|_| ExperienceApiError::Delete())?;
 
+	transaction.commit().await.map_err(|_| ExperienceApiError::Delete())?;
+
+	Ok((StatusCode::OK, ()))
+}
+
+async fn reorder_experience_route(
+	Extension(current_user): Extension<CurrentUser>,
+	State(state): State<ExperienceState>,
+	ValidatedJson(request): ValidatedJson<ReorderExperienceDto>,
+) -> ApiResult<()> {
+	let mut transaction = state
+		.history_repository
+		.track(&current_user.user_id, &current_user.user_id, &[Section::Experience])
+		.await
+		.map_err(|_| ExperienceApiError::Reorder())?;
+
+	ExperienceRepository::reorder(&mut **transaction, &current_user.user_id, &request.ids)
+		.await
+		.map_err(|_| ExperienceApiError::Reorder())?;
+
+	transaction.commit().await.map_err(|_| ExperienceApiError::Reorder())?;
+
 	Ok((StatusCode::OK, ()))
 }
 
+/// Holding several current roles is allowed but usually a forgotten end date, so clients get a `Warning` to
+/// surface. Failing to check only costs the warning.
+async fn current_roles_warning(state: &ExperienceState, user_id: &i64) -> HeaderMap {
+	let mut headers = HeaderMap::new();
+
+	if let Ok(count @ 2..) = state.experience_repository.count_current(user_id).await {
+		let warning = format!("299 - \"{count} roles are marked as current\"");
+		if let Ok(value) = HeaderValue::from_str(&warning) {
+			headers.insert(header::WARNING, value);
+		}
+	}
+
+	headers
+}
+
 async fn user_experiences_route(
 	Path(user_id): Path<i64>,
+	requested_locales: RequestedLocales,
 	State(state): State<ExperienceState>,
-) -> ApiResult<Json<Vec<Experience>>> {
+) -> ApiResult<(
+	[(header::HeaderName, String); 1],
+	Json<Vec<CompanyRoles<RenderedExperience>>>,
+)> {
+	let locale = state
+		.users_repository
+		.negotiate_locale(&user_id, &requested_locales)
+		.await
+		.map_err(|_| ExperienceApiError::GetUser())?;
+
 	let user_experience = state
 		.experience_repository
-		.get_by_user_id(user_id)
+		.get_by_user_id(user_id, locale.translation())
+		.await
+		.map_err(|_| ExperienceApiError::GetUser())?;
+
+	let descriptions: Vec<&str> = user_experience
+		.iter()
+		.map(|experience| experience.description.as_str())
+		.collect();
+	let descriptions_html = state
+		.markdown_service
+		.render_all(&descriptions)
+		.await
+		.map_err(|_| ExperienceApiError::GetUser())?;
+
+	let verifier_counts: HashMap<i64, i64> = state
+		.verifications_repository
+		.count_verifiers(&user_id)
+		.await
+		.map_err(|_| ExperienceApiError::GetUser())?
+		.into_iter()
+		.map(|count| (count.experience_id, count.verifiers))
+		.collect();
+
+	let work_email_verified = state
+		.work_emails_repository
+		.get_verified_entries(&user_id)
+		.await
+		.map_err(|_| ExperienceApiError::GetUser())?;
+
+	let rendered_experience = user_experience
+		.into_iter()
+		.zip(descriptions_html)
+		.map(|(experience, description_html)| RenderedExperience {
+			verifier_count: verifier_counts.get(&experience.id).copied().unwrap_or_default(),
+			work_email_verified: work_email_verified.contains(&experience.id),
+			experience,
+			description_html,
+		})
+		.collect();
+
+	let headers = [(header::CONTENT_LANGUAGE, locale.locale)];
+	let companies = group_by_company(rendered_experience, Utc::now().date_naive());
+
+	Ok((StatusCode::CREATED, (headers, Json(companies))))
+}
+
+async fn user_timeline_route(
+	Path(user_id): Path<i64>,
+	Query(query): Query<TimelineQuery>,
+	State(state): State<ExperienceState>,
+) -> ApiResult<Json<TimelineReport>> {
+	query.validate().map_err(ExperienceApiError::InvalidQuery)?;
+
+	let experience = state
+		.experience_repository
+		.get_by_user_id(user_id, None)
 		.await
 		.map_err(|_| ExperienceApiError::GetUser())?;
 
-	Ok((StatusCode::CREATED, Json(user_experience)))
+	let report = TimelineReport::new(&experience, query.min_gap_days, Utc::now().date_naive());
+
+	Ok((StatusCode::OK, Json(report)))
+}
+
+async fn experience_translations_route(
+	Extension(current_user): Extension<CurrentUser>,
+	State(state): State<ExperienceState>,
+	Path(experience_id): Path<i64>,
+) -> ApiResult<Json<Vec<ExperienceTranslation>>> {
+	let translations = state
+		.experience_repository
+		.get_translations(&experience_id, &current_user.user_id)
+		.await
+		.map_err(|_| ExperienceApiError::GetTranslations())?;
+
+	Ok((StatusCode::OK, Json(translations)))
+}
+
+async fn upsert_experience_translation_route(
+	Extension(current_user): Extension<CurrentUser>,
+	State(state): State<ExperienceState>,
+	Path((experience_id, locale)): Path<(i64, String)>,
+	ValidatedJson(request): ValidatedJson<ExperienceTranslationDto>,
+) -> ApiResult<Json<ExperienceTranslation>> {
+	let user = state
+		.users_repository
+		.find_user_by_id(&current_user.user_id)
+		.await
+		.map_err(|_| ExperienceApiError::UpdateTranslation())?;
+
+	// The untranslated text already is the default locale's variant
+	let locale = normalize_locale(&locale)
+		.filter(|locale| *locale != user.default_locale)
+		.ok_or(ExperienceApiError::InvalidLocale(locale))?;
+
+	let mut transaction = state
+		.history_repository
+		.track(&current_user.user_id, &current_user.user_id, &[Section::Translations])
+		.await
+		.map_err(|_| ExperienceApiError::UpdateTranslation())?;
+
+	let translation = ExperienceRepository::upsert_translation(
+		&mut **transaction,
+		&experience_id,
+		&current_user.user_id,
+		&locale,
+		request,
+	)
+	.await
+	.map_err(|_| ExperienceApiError::UpdateTranslation())?
+	.ok_or(ExperienceApiError::NotFound(experience_id))?;
+
+	transaction
+		.commit()
+		.await
+		.map_err(|_| ExperienceApiError::UpdateTranslation())?;
+
+	Ok((StatusCode::OK, Json(translation)))
+}
+
+async fn delete_experience_translation_route(
+	Extension(current_user): Extension<CurrentUser>,
+	State(state): State<ExperienceState>,
+	Path((experience_id, locale)): Path<(i64, String)>,
+) -> ApiResult<()> {
+	let locale = normalize_locale(&locale).ok_or(ExperienceApiError::InvalidLocale(locale))?;
+
+	let mut transaction = state
+		.history_repository
+		.track(&current_user.user_id, &current_user.user_id, &[Section::Translations])
+		.await
+		.map_err(|_| ExperienceApiError::UpdateTranslation())?;
+
+	ExperienceRepository::delete_translation(&mut **transaction, &experience_id, &current_user.user_id, &locale)
+		.await
+		.map_err(|_| ExperienceApiError::UpdateTranslation())?;
+
+	transaction
+		.commit()
+		.await
+		.map_err(|_| ExperienceApiError::UpdateTranslation())?;
+
+	Ok((StatusCode::OK, ()))
 }
 
 #[derive(Debug, Error)]
@@ -113,12 +360,38 @@ pub enum ExperienceApiError {
 
 	#[error("Failed to delete experience")]
 	Delete(),
+
+	#[error("Failed to reorder experience")]
+	Reorder(),
+
+	#[error("Experience not found: {0}")]
+	NotFound(i64),
+
+	#[error("Invalid locale: {0}")]
+	InvalidLocale(String),
+
+	#[error("Invalid timeline query: {0}")]
+	InvalidQuery(ValidationErrors),
+
+	#[error("Failed to get experience translations")]
+	GetTranslations(),
+
+	#[error("Failed to update experience translation")]
+	UpdateTranslation(),
 }
 
 impl IntoResponse for ExperienceApiError {
 	fn into_response(self) -> Response {
 		let status_code = match self {
-			Self::GetUser() | Self::Create() | Self::Update() | Self::Delete() => StatusCode::INTERNAL_SERVER_ERROR,
+			Self::NotFound(_) => StatusCode::NOT_FOUND,
+			Self::InvalidLocale(_) | Self::InvalidQuery(_) => StatusCode::BAD_REQUEST,
+			Self::GetUser()
+			| Self::Create()
+			| Self::Update()
+			| Self::Delete()
+			| Self::Reorder()
+			| Self::GetTranslations()
+			| Self::UpdateTranslation() => StatusCode::INTERNAL_SERVER_ERROR,
 		};
 
 		log::error!("{self:?}");
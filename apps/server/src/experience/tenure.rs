@@ -0,0 +1,282 @@
+//! Length of a user's career, overall, per company and per skill, counted in calendar months so that roles
+//! held at the same time count once
+
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use super::{repository::Experience, year_month::YearMonth};
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Tenure {
+	/// Months with at least one role
+	pub total_months: i32,
+	/// Longest tenure first
+	pub companies: Vec<CompanyTenure>,
+	/// Months with at least one role the skill was used in, longest first
+	pub skills: Vec<SkillTenure>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompanyTenure {
+	pub company_id: Option<i64>,
+	pub company_name: String,
+	pub months: i32,
+	pub start_date: YearMonth,
+	/// `None` while a role at the company is current
+	pub end_date: Option<YearMonth>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillTenure {
+	pub skill_id: i64,
+	pub months: i32,
+}
+
+impl Tenure {
+	/// A role counts every month from its start month through its end month, current roles through the month
+	/// of `today`
+	pub fn new(experience: &[Experience], today: NaiveDate) -> Self {
+		let current_month = YearMonth::from_month(&today).month_index();
+
+		let mut by_company: HashMap<CompanyKey, Vec<&Experience>> = HashMap::new();
+		for entry in experience {
+			by_company.entry(company_key(entry)).or_default().push(entry);
+		}
+
+		let mut companies: Vec<CompanyTenure> = by_company
+			.into_values()
+			.map(|entries| CompanyTenure::new(&entries, current_month))
+			.collect();
+		companies.sort_by(|left, right| {
+			right
+				.months
+				.cmp(&left.months)
+				.then_with(|| left.company_name.cmp(&right.company_name))
+		});
+
+		let mut by_skill: HashMap<i64, Vec<(i32, i32)>> = HashMap::new();
+		for entry in experience {
+			for skill_id in &entry.skill_ids {
+				by_skill
+					.entry(*skill_id)
+					.or_default()
+					.push(months(entry, current_month));
+			}
+		}
+
+		let mut skills: Vec<SkillTenure> = by_skill
+			.into_iter()
+			.map(|(skill_id, ranges)| SkillTenure {
+				skill_id,
+				months: count_months(ranges.into_iter()),
+			})
+			.collect();
+		skills.sort_by(|left, right| right.months.cmp(&left.months).then(left.skill_id.cmp(&right.skill_id)));
+
+		Self {
+			total_months: count_months(experience.iter().map(|entry| months(entry, current_month))),
+			companies,
+			skills,
+		}
+	}
+}
+
+/// Roles at one company under their combined tenure, the way the public profile lists them
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompanyRoles<T> {
+	#[serde(flatten)]
+	pub tenure: CompanyTenure,
+	pub roles: Vec<T>,
+}
+
+/// Nests roles under their company, keeping the order of `experience` for both the companies, placed where
+/// their first role is, and the roles within each
+pub fn group_by_company<T: AsRef<Experience>>(experience: Vec<T>, today: NaiveDate) -> Vec<CompanyRoles<T>> {
+	let current_month = YearMonth::from_month(&today).month_index();
+
+	let mut groups: Vec<Vec<T>> = Vec::new();
+	let mut group_indexes: HashMap<CompanyKey, usize> = HashMap::new();
+	for entry in experience {
+		let index = *group_indexes.entry(company_key(entry.as_ref())).or_insert_with(|| {
+			groups.push(Vec::new());
+			groups.len() - 1
+		});
+		groups[index].push(entry);
+	}
+
+	groups
+		.into_iter()
+		.map(|roles| CompanyRoles {
+			tenure: CompanyTenure::new(&roles.iter().map(AsRef::as_ref).collect::<Vec<_>>(), current_month),
+			roles,
+		})
+		.collect()
+}
+
+impl CompanyTenure {
+	/// Named after the most recent role, `entries` must not be empty
+	fn new(entries: &[&Experience], current_month: i32) -> Self {
+		let latest = entries
+			.iter()
+			.max_by_key(|entry| entry.start_date)
+			.expect("grouped entries are never empty");
+
+		Self {
+			company_id: latest.company_id,
+			company_name: latest.company_name.clone(),
+			months: count_months(entries.iter().map(|entry| months(entry, current_month))),
+			start_date: entries
+				.iter()
+				.map(|entry| entry.start_date)
+				.min()
+				.unwrap_or(latest.start_date),
+			end_date: if entries.iter().any(|entry| entry.end_date.is_none()) {
+				None
+			} else {
+				entries.iter().filter_map(|entry| entry.end_date).max()
+			},
+		}
+	}
+}
+
+/// Entries whose name didn't resolve to a company are grouped by the name ignoring case
+type CompanyKey = (Option<i64>, String);
+
+fn company_key(entry: &Experience) -> CompanyKey {
+	entry.company_id.map_or_else(
+		|| (None, entry.company_name.trim().to_lowercase()),
+		|id| (Some(id), String::new()),
+	)
+}
+
+/// First and last month index of a role
+fn months(entry: &Experience, current_month: i32) -> (i32, i32) {
+	let end_month = entry.end_date.map_or(current_month, |end_date| end_date.month_index());
+	(entry.start_date.month_index(), end_month)
+}
+
+/// Counts the months covered by inclusive `(first, last)` month ranges, merging overlapping ones
+fn count_months(ranges: impl Iterator<Item = (i32, i32)>) -> i32 {
+	let mut ranges: Vec<(i32, i32)> = ranges.filter(|(first, last)| first <= last).collect();
+	ranges.sort_unstable();
+
+	let mut total = 0;
+	let mut covered_until = i32::MIN;
+	for (first, last) in ranges {
+		let first = first.max(covered_until.saturating_add(1));
+		if first <= last {
+			total += last - first + 1;
+			covered_until = last;
+		}
+	}
+
+	total
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json::json;
+
+	use super::*;
+
+	fn month(value: &str) -> i32 {
+		value.parse::<YearMonth>().unwrap().month_index()
+	}
+
+	fn range(first: &str, last: &str) -> (i32, i32) {
+		(month(first), month(last))
+	}
+
+	fn entry(company_id: i64, start_date: &str, end_date: Option<&str>, skill_ids: &[i64]) -> Experience {
+		serde_json::from_value(json!({
+			"userId": 1,
+			"id": 1,
+			"companyId": company_id,
+			"companyName": format!("Company {company_id}"),
+			"occupation": "Engineer",
+			"locationName": "Berlin",
+			"locationId": null,
+			"locationType": "on-site",
+			"employmentType": "full-time",
+			"startDate": start_date,
+			"endDate": end_date,
+			"isCurrent": end_date.is_none(),
+			"description": "",
+			"position": null,
+			"skillIds": skill_ids,
+		}))
+		.unwrap()
+	}
+
+	#[test]
+	fn counts_start_and_end_months() {
+		assert_eq!(count_months([range("2020-01", "2020-01")].into_iter()), 1);
+		assert_eq!(count_months([range("2020-01", "2020-12")].into_iter()), 12);
+	}
+
+	#[test]
+	fn counts_overlapping_months_once() {
+		let ranges = [range("2020-01", "2020-06"), range("2020-04", "2020-09")];
+		assert_eq!(count_months(ranges.into_iter()), 9);
+
+		// A range inside another adds nothing, whichever order they come in
+		let ranges = [range("2020-03", "2020-04"), range("2020-01", "2020-12")];
+		assert_eq!(count_months(ranges.into_iter()), 12);
+	}
+
+	#[test]
+	fn adjacent_ranges_add_up() {
+		let ranges = [range("2020-07", "2020-12"), range("2020-01", "2020-06")];
+		assert_eq!(count_months(ranges.into_iter()), 12);
+
+		// Leaving one job and starting the next in the same month counts that month once
+		let ranges = [range("2020-01", "2020-06"), range("2020-06", "2020-12")];
+		assert_eq!(count_months(ranges.into_iter()), 12);
+	}
+
+	#[test]
+	fn gaps_are_not_counted() {
+		let ranges = [range("2019-01", "2019-12"), range("2021-01", "2021-06")];
+		assert_eq!(count_months(ranges.into_iter()), 18);
+	}
+
+	#[test]
+	fn ignores_ranges_ending_before_they_start() {
+		let ranges = [range("2020-06", "2020-01"), range("2021-01", "2021-03")];
+		assert_eq!(count_months(ranges.into_iter()), 3);
+		assert_eq!(count_months(std::iter::empty()), 0);
+	}
+
+	#[test]
+	fn tenure_merges_concurrent_roles() {
+		let today = NaiveDate::from_ymd_opt(2021, 3, 15).unwrap();
+		let experience = [
+			entry(1, "2020-01", Some("2020-12"), &[10]),
+			entry(1, "2021-01", None, &[10, 20]),
+			entry(2, "2020-07", Some("2020-09-30"), &[20]),
+		];
+
+		let tenure = Tenure::new(&experience, today);
+
+		assert_eq!(tenure.total_months, 15);
+		let companies: Vec<(Option<i64>, i32)> = tenure
+			.companies
+			.iter()
+			.map(|company| (company.company_id, company.months))
+			.collect();
+		assert_eq!(companies, [(Some(1), 15), (Some(2), 3)]);
+		assert_eq!(tenure.companies[0].end_date, None);
+		let skills: Vec<(i64, i32)> = tenure
+			.skills
+			.iter()
+			.map(|skill| (skill.skill_id, skill.months))
+			.collect();
+		assert_eq!(skills, [(10, 15), (20, 6)]);
+	}
+}
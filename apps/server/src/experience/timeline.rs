@@ -0,0 +1,120 @@
+//! Overlaps and gaps in a user's work history, the things recruiters ask about
+
+use chrono::{Days, NaiveDate};
+use serde::Serialize;
+
+use super::repository::Experience;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineReport {
+	pub overlaps: Vec<Overlap>,
+	pub gaps: Vec<Gap>,
+	pub current_role_ids: Vec<i64>,
+	pub multiple_current_roles: bool,
+}
+
+/// Two roles held at the same time, e.g. a side contract next to a full-time job
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Overlap {
+	pub experience_ids: [i64; 2],
+	pub start_date: NaiveDate,
+	pub end_date: NaiveDate,
+	pub days: i64,
+}
+
+/// A stretch without any role, between the end of one and the start of the next
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Gap {
+	pub after_experience_id: i64,
+	pub before_experience_id: i64,
+	pub start_date: NaiveDate,
+	pub end_date: NaiveDate,
+	pub days: i64,
+}
+
+impl TimelineReport {
+	/// Periods run from the first day of the start month through the last day of the end month, current roles
+	/// until `today`. Dates in the report are inclusive.
+	pub fn new(experience: &[Experience], min_gap_days: i64, today: NaiveDate) -> Self {
+		let mut periods: Vec<(i64, NaiveDate, NaiveDate)> = experience
+			.iter()
+			.map(|entry| {
+				let end_date = entry.end_date.map_or(today, |end_date| end_date.last_day());
+				(entry.id, entry.start_date.first_day(), end_date)
+			})
+			.collect();
+		periods.sort_by_key(|&(id, start_date, _)| (start_date, id));
+
+		let current_role_ids: Vec<i64> = experience
+			.iter()
+			.filter(|entry| entry.is_current)
+			.map(|entry| entry.id)
+			.collect();
+
+		Self {
+			overlaps: find_overlaps(&periods),
+			gaps: find_gaps(&periods, min_gap_days),
+			multiple_current_roles: current_role_ids.len() > 1,
+			current_role_ids,
+		}
+	}
+}
+
+fn find_overlaps(periods: &[(i64, NaiveDate, NaiveDate)]) -> Vec<Overlap> {
+	let mut overlaps = Vec::new();
+
+	for (index, &(first_id, first_start, first_end)) in periods.iter().enumerate() {
+		// Sorted by start date, so once a role starts after this one ends none of the later ones overlap it
+		for &(second_id, second_start, second_end) in periods[index + 1..]
+			.iter()
+			.take_while(|&&(_, start_date, _)| start_date <= first_end)
+		{
+			let end_date = first_end.min(second_end);
+			let days = (end_date - second_start).num_days() + 1;
+
+			if days > 0 {
+				overlaps.push(Overlap {
+					experience_ids: [first_id, second_id],
+					start_date: second_start.max(first_start),
+					end_date,
+					days,
+				});
+			}
+		}
+	}
+
+	overlaps
+}
+
+fn find_gaps(periods: &[(i64, NaiveDate, NaiveDate)], min_gap_days: i64) -> Vec<Gap> {
+	let mut gaps = Vec::new();
+	let Some(&(first_id, _, first_end)) = periods.first() else {
+		return gaps;
+	};
+
+	// The role that has run the longest so far, since a gap only starts once every earlier role has ended
+	let (mut latest_id, mut latest_end) = (first_id, first_end);
+
+	for &(id, start_date, end_date) in &periods[1..] {
+		let days = (start_date - latest_end).num_days() - 1;
+
+		if days >= min_gap_days {
+			gaps.push(Gap {
+				after_experience_id: latest_id,
+				before_experience_id: id,
+				start_date: latest_end + Days::new(1),
+				end_date: start_date - Days::new(1),
+				days,
+			});
+		}
+
+		if end_date > latest_end {
+			(latest_id, latest_end) = (id, end_date);
+		}
+	}
+
+	gaps
+}
@@ -0,0 +1,247 @@
+//! Calendar months for experience dates, optionally narrowed to a day, matching the `year_month` domain
+
+use std::{fmt, str::FromStr};
+
+use chrono::{Datelike, Months, NaiveDate};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use sqlx::{
+	encode::IsNull,
+	error::BoxDynError,
+	postgres::{PgArgumentBuffer, PgTypeInfo, PgValueRef},
+	Decode, Encode, Postgres, Type,
+};
+
+/// A month like `2020-05`, or a day like `2020-05-14` when it's known. Orders chronologically, with a month
+/// before any of its days. Having no time or timezone, it reads the same for everyone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct YearMonth {
+	year: i32,
+	month: u32,
+	day: Option<u32>,
+}
+
+impl YearMonth {
+	pub fn new(year: i32, month: u32, day: Option<u32>) -> Option<Self> {
+		NaiveDate::from_ymd_opt(year, month, day.unwrap_or(1))
+			.filter(|_| (0..=9999).contains(&year))
+			.map(|_| Self { year, month, day })
+	}
+
+	/// The month of a date, dropping the day
+	pub fn from_month(date: &impl Datelike) -> Self {
+		Self {
+			year: date.year(),
+			month: date.month(),
+			day: None,
+		}
+	}
+
+	pub const fn year(&self) -> i32 {
+		self.year
+	}
+
+	pub const fn month(&self) -> u32 {
+		self.month
+	}
+
+	/// Months since the start of year 0, so that consecutive months are consecutive numbers
+	pub fn month_index(&self) -> i32 {
+		self.year * 12 + i32::try_from(self.month).unwrap_or(1) - 1
+	}
+
+	/// The day, or the 1st when only the month is known
+	pub fn first_day(&self) -> NaiveDate {
+		NaiveDate::from_ymd_opt(self.year, self.month, self.day.unwrap_or(1)).unwrap_or_default()
+	}
+
+	/// The day, or the last of the month when only the month is known
+	pub fn last_day(&self) -> NaiveDate {
+		self.day.map_or_else(
+			|| {
+				let first_day = self.first_day();
+				first_day
+					.checked_add_months(Months::new(1))
+					.map_or(first_day, |next| next.pred_opt().unwrap_or(next))
+			},
+			|_| self.first_day(),
+		)
+	}
+}
+
+/// January 1970, the same epoch chrono's dates default to
+impl Default for YearMonth {
+	fn default() -> Self {
+		Self {
+			year: 1970,
+			month: 1,
+			day: None,
+		}
+	}
+}
+
+impl fmt::Display for YearMonth {
+	fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(formatter, "{:04}-{:02}", self.year, self.month)?;
+		if let Some(day) = self.day {
+			write!(formatter, "-{day:02}")?;
+		}
+		Ok(())
+	}
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Wrong date format, expected YYYY-MM or YYYY-MM-DD")]
+pub struct ParseYearMonthError;
+
+impl FromStr for YearMonth {
+	type Err = ParseYearMonthError;
+
+	fn from_str(value: &str) -> Result<Self, Self::Err> {
+		// Fixed dash positions pin every part to its width: YYYY-MM or YYYY-MM-DD
+		let dashes: Vec<usize> = value.match_indices('-').map(|(index, _)| index).collect();
+		if !matches!((value.len(), dashes.as_slice()), (7, [4]) | (10, [4, 7])) {
+			return Err(ParseYearMonthError);
+		}
+
+		let mut parts = value.split('-').map(|part| {
+			part.chars()
+				.all(|char| char.is_ascii_digit())
+				.then(|| part.parse::<u32>().ok())
+				.flatten()
+				.ok_or(ParseYearMonthError)
+		});
+
+		let year = i32::try_from(parts.next().ok_or(ParseYearMonthError)??).map_err(|_| ParseYearMonthError)?;
+		let month = parts.next().ok_or(ParseYearMonthError)??;
+		let day = parts.next().transpose()?;
+
+		Self::new(year, month, day).ok_or(ParseYearMonthError)
+	}
+}
+
+impl Serialize for YearMonth {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_str(self)
+	}
+}
+
+impl<'de> Deserialize<'de> for YearMonth {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let value = String::deserialize(deserializer)?;
+		value.parse().map_err(de::Error::custom)
+	}
+}
+
+impl Type<Postgres> for YearMonth {
+	fn type_info() -> PgTypeInfo {
+		PgTypeInfo::with_name("year_month")
+	}
+
+	fn compatible(ty: &PgTypeInfo) -> bool {
+		*ty == Self::type_info() || <&str as Type<Postgres>>::compatible(ty)
+	}
+}
+
+impl Encode<'_, Postgres> for YearMonth {
+	fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+		<String as Encode<Postgres>>::encode(self.to_string(), buf)
+	}
+}
+
+impl<'r> Decode<'r, Postgres> for YearMonth {
+	fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+		Ok(<&str as Decode<Postgres>>::decode(value)?.parse()?)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn parse(value: &str) -> Option<YearMonth> {
+		value.parse().ok()
+	}
+
+	#[test]
+	fn parses_months_and_days() {
+		assert_eq!(parse("2020-05"), YearMonth::new(2020, 5, None));
+		assert_eq!(parse("2020-05-14"), YearMonth::new(2020, 5, Some(14)));
+		assert_eq!(parse("0000-01").map(|month| month.year()), Some(0));
+	}
+
+	#[test]
+	fn rejects_malformed_values() {
+		for value in [
+			"",
+			"2020",
+			"2020-5",
+			"20-05",
+			"2020/05",
+			"2020-05-",
+			"2020-05-1",
+			"+020-05",
+			"2020-+5",
+			"2020-05-+1",
+			"2020-05-14-01",
+			"2020-13",
+			"2020-00",
+			"2020-05-00",
+			"2020-04-31",
+			" 2020-05",
+			"2020-05 ",
+			"２０２０-05",
+		] {
+			assert_eq!(parse(value), None, "{value:?} should not parse");
+		}
+	}
+
+	#[test]
+	fn accepts_leap_days_only_in_leap_years() {
+		assert!(parse("2024-02-29").is_some());
+		assert!(parse("2000-02-29").is_some());
+		assert!(parse("2023-02-29").is_none());
+		assert!(parse("1900-02-29").is_none());
+	}
+
+	#[test]
+	fn last_day_accounts_for_month_length() {
+		let day = |value: &str| parse(value).map(|month| month.last_day());
+
+		assert_eq!(day("2024-02"), NaiveDate::from_ymd_opt(2024, 2, 29));
+		assert_eq!(day("2023-02"), NaiveDate::from_ymd_opt(2023, 2, 28));
+		assert_eq!(day("2023-12"), NaiveDate::from_ymd_opt(2023, 12, 31));
+		assert_eq!(day("2023-04-10"), NaiveDate::from_ymd_opt(2023, 4, 10));
+	}
+
+	#[test]
+	fn orders_chronologically_with_months_before_their_days() {
+		let mut values: Vec<YearMonth> = ["2021-01", "2020-12-31", "2020-12", "2020-02-01", "2019-12"]
+			.into_iter()
+			.filter_map(parse)
+			.collect();
+		values.sort();
+
+		let sorted: Vec<String> = values.iter().map(ToString::to_string).collect();
+		assert_eq!(sorted, ["2019-12", "2020-02-01", "2020-12", "2020-12-31", "2021-01"]);
+	}
+
+	#[test]
+	fn consecutive_months_have_consecutive_indexes() {
+		let index = |value: &str| parse(value).map(|month| month.month_index());
+
+		assert_eq!(
+			index("2020-01")
+				.zip(index("2019-12"))
+				.map(|(next, previous)| next - previous),
+			Some(1)
+		);
+		assert_eq!(index("2020-05-31"), index("2020-05"));
+	}
+
+	#[test]
+	fn round_trips_through_json() {
+		let month: YearMonth = serde_json::from_str("\"2020-02-29\"").unwrap();
+		assert_eq!(serde_json::to_string(&month).unwrap(), "\"2020-02-29\"");
+		assert!(serde_json::from_str::<YearMonth>("\"2020-2\"").is_err());
+	}
+}
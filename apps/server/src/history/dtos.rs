@@ -0,0 +1,19 @@
+use serde::Deserialize;
+use validator::Validate;
+
+use super::repository::Section;
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryQuery {
+	pub section: Option<Section>,
+	/// Only revisions older than this revision id, for paging
+	pub before: Option<i64>,
+	#[serde(default = "default_limit")]
+	#[validate(range(min = 1, max = 100, message = "Limit must be between 1 and 100"))]
+	pub limit: i64,
+}
+
+const fn default_limit() -> i64 {
+	50
+}
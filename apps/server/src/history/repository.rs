@@ -0,0 +1,897 @@
+use std::{
+	ops::{Deref, DerefMut},
+	sync::Arc,
+};
+
+use anyhow::{anyhow, Context};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{PgExecutor, Postgres, Transaction};
+
+use crate::{database::Database, i18n::DEFAULT_LOCALE};
+
+use super::dtos::HistoryQuery;
+
+#[derive(Clone)]
+pub struct HistoryRepository {
+	pub(crate) database: Arc<Database>,
+}
+
+impl HistoryRepository {
+	pub fn new(database: &Arc<Database>) -> Self {
+		Self {
+			database: Arc::clone(database),
+		}
+	}
+
+	/// Starts a transaction for a write to the given sections of the user's profile. Run the write on the returned
+	/// transaction, then commit it to record a revision of each section along with the write.
+	pub async fn track(
+		&self,
+		user_id: &i64,
+		changed_by: &i64,
+		sections: &[Section],
+	) -> anyhow::Result<TrackedTransaction> {
+		let mut transaction = self
+			.database
+			.pool
+			.begin()
+			.await
+			.context("Failed to start transaction")?;
+
+		Self::lock(&mut transaction, user_id).await?;
+
+		let mut before = Vec::with_capacity(sections.len());
+		for section in sections {
+			before.push((
+				*section,
+				Self::snapshot_with(&mut *transaction, user_id, *section).await?,
+			));
+		}
+
+		Ok(TrackedTransaction {
+			transaction,
+			user_id: *user_id,
+			changed_by: *changed_by,
+			before,
+		})
+	}
+
+	/// Holds the user's row until the transaction ends, so concurrent writes to the same profile
+	/// are snapshotted and recorded one after another
+	async fn lock(transaction: &mut Transaction<'_, Postgres>, user_id: &i64) -> anyhow::Result<()> {
+		sqlx::query!("SELECT id FROM users WHERE id = $1 FOR NO KEY UPDATE", user_id)
+			.fetch_one(&mut **transaction)
+			.await
+			.context("Failed to lock user")?;
+
+		Ok(())
+	}
+
+	pub async fn snapshot_with(
+		executor: impl PgExecutor<'_>,
+		user_id: &i64,
+		section: Section,
+	) -> anyhow::Result<Value> {
+		let snapshot = match section {
+			Section::Profile => {
+				sqlx::query_scalar!(
+					r#"
+						SELECT JSONB_BUILD_OBJECT(
+							'headline', headline, 'bio', bio, 'default_locale', default_locale, 'avatar_url', avatar_url,
+							'location_name', location_name, 'location_id', location_id
+						) AS "snapshot!"
+						FROM users
+						WHERE id = $1
+					"#,
+					user_id
+				)
+				.fetch_one(executor)
+				.await
+			}
+			Section::Experience => {
+				sqlx::query_scalar!(
+					r#"
+						SELECT COALESCE(
+							JSONB_AGG(
+								TO_JSONB(e) || JSONB_BUILD_OBJECT(
+									'skill_ids',
+									ARRAY(SELECT es.skill_id FROM experience_skills es WHERE es.experience_id = e.id ORDER BY es.skill_id)
+								)
+								ORDER BY e.id
+							),
+							'[]'
+						) AS "snapshot!"
+						FROM experience e
+						WHERE e.user_id = $1
+					"#,
+					user_id
+				)
+				.fetch_one(executor)
+				.await
+			}
+			Section::Education => {
+				sqlx::query_scalar!(
+					r#"SELECT COALESCE(JSONB_AGG(TO_JSONB(e) ORDER BY e.id), '[]') AS "snapshot!" FROM education e WHERE e.user_id = $1"#,
+					user_id
+				)
+				.fetch_one(executor)
+				.await
+			}
+			Section::Skills => {
+				sqlx::query_scalar!(
+					r#"
+						SELECT COALESCE(
+							JSONB_AGG(
+								JSONB_BUILD_OBJECT(
+									'id', s.id, 'name', s.name, 'proficiency', us.proficiency, 'years', us.years,
+									'last_used', us.last_used, 'position', us.position, 'is_pinned', us.is_pinned
+								)
+								ORDER BY s.id
+							),
+							'[]'
+						) AS "snapshot!"
+						FROM user_skills us
+						JOIN skills s ON s.id = us.skill_id
+						WHERE us.user_id = $1
+					"#,
+					user_id
+				)
+				.fetch_one(executor)
+				.await
+			}
+			Section::Languages => {
+				sqlx::query_scalar!(
+					r#"
+						SELECT COALESCE(
+							JSONB_AGG(JSONB_BUILD_OBJECT('code', language_code, 'proficiency', proficiency) ORDER BY language_code),
+							'[]'
+						) AS "snapshot!"
+						FROM user_languages
+						WHERE user_id = $1
+					"#,
+					user_id
+				)
+				.fetch_one(executor)
+				.await
+			}
+			Section::Certifications => Self::snapshot_certifications(executor, user_id).await,
+			Section::Translations => Self::snapshot_translations(executor, user_id).await,
+			Section::CustomSections => {
+				sqlx::query_scalar!(
+					r#"SELECT COALESCE(JSONB_AGG(TO_JSONB(cs) ORDER BY cs.id), '[]') AS "snapshot!" FROM custom_sections cs WHERE cs.user_id = $1"#,
+					user_id
+				)
+				.fetch_one(executor)
+				.await
+			}
+		};
+
+		snapshot.map_err(|error| anyhow!(error).context(format!("Failed to snapshot {section:?}")))
+	}
+
+	async fn snapshot_certifications(executor: impl PgExecutor<'_>, user_id: &i64) -> Result<Value, sqlx::Error> {
+		// The reminder bookkeeping isn't profile content, so sending a reminder doesn't show up as a change
+		sqlx::query_scalar!(
+			r#"
+				SELECT COALESCE(
+					JSONB_AGG(
+						TO_JSONB(c) - 'reminder_sent_for' || JSONB_BUILD_OBJECT(
+							'skill_ids',
+							ARRAY(SELECT cs.skill_id FROM certification_skills cs WHERE cs.certification_id = c.id ORDER BY cs.skill_id)
+						)
+						ORDER BY c.id
+					),
+					'[]'
+				) AS "snapshot!"
+				FROM certifications c
+				WHERE c.user_id = $1
+			"#,
+			user_id
+		)
+		.fetch_one(executor)
+		.await
+	}
+
+	async fn snapshot_translations(executor: impl PgExecutor<'_>, user_id: &i64) -> Result<Value, sqlx::Error> {
+		sqlx::query_scalar!(
+			r#"
+				SELECT JSONB_BUILD_OBJECT(
+					'profile',
+					COALESCE(
+						(
+							SELECT JSONB_AGG(
+								JSONB_BUILD_OBJECT('locale', ut.locale, 'headline', ut.headline, 'bio', ut.bio)
+								ORDER BY ut.locale
+							)
+							FROM user_translations ut
+							WHERE ut.user_id = $1
+						),
+						'[]'
+					),
+					'experience',
+					COALESCE(
+						(
+							SELECT JSONB_AGG(
+								JSONB_BUILD_OBJECT(
+									'experience_id', et.experience_id, 'locale', et.locale, 'occupation', et.occupation,
+									'description', et.description
+								)
+								ORDER BY et.experience_id, et.locale
+							)
+							FROM experience_translations et
+							JOIN experience e ON e.id = et.experience_id
+							WHERE e.user_id = $1
+						),
+						'[]'
+					)
+				) AS "snapshot!"
+			"#,
+			user_id
+		)
+		.fetch_one(executor)
+		.await
+	}
+
+	/// Stores the section's current state as a new revision, unless nothing changed since `before`.
+	/// The first change to a section also stores `before` as the baseline, so it can be restored.
+	async fn record_with(
+		transaction: &mut Transaction<'_, Postgres>,
+		user_id: &i64,
+		changed_by: Option<&i64>,
+		section: Section,
+		before: Value,
+		restored_from: Option<i64>,
+	) -> anyhow::Result<()> {
+		let after = Self::snapshot_with(&mut **transaction, user_id, section).await?;
+
+		let previous = sqlx::query_scalar!(
+			r#"
+				SELECT snapshot FROM profile_revisions
+				WHERE user_id = $1 AND section = $2
+				ORDER BY id DESC
+				LIMIT 1
+			"#,
+			user_id,
+			section as Section
+		)
+		.fetch_optional(&mut **transaction)
+		.await
+		.map_err(|error| anyhow!(error).context("Failed to get previous revision"))?;
+
+		let previous = match previous {
+			Some(previous) => previous,
+			None if before != section.empty_snapshot() => {
+				Self::insert(
+					transaction,
+					user_id,
+					None,
+					section,
+					&section.empty_snapshot(),
+					&before,
+					None,
+				)
+				.await?;
+				before
+			}
+			None => section.empty_snapshot(),
+		};
+
+		if previous != after {
+			Self::insert(
+				transaction,
+				user_id,
+				changed_by,
+				section,
+				&previous,
+				&after,
+				restored_from,
+			)
+			.await?;
+		}
+
+		Ok(())
+	}
+
+	async fn insert(
+		transaction: &mut Transaction<'_, Postgres>,
+		user_id: &i64,
+		changed_by: Option<&i64>,
+		section: Section,
+		previous: &Value,
+		snapshot: &Value,
+		restored_from: Option<i64>,
+	) -> anyhow::Result<()> {
+		let diff = serde_json::to_value(json_patch::diff(previous, snapshot)).context("Failed to serialize diff")?;
+
+		sqlx::query!(
+			r#"
+				INSERT INTO profile_revisions (user_id, section, changed_by, snapshot, diff, restored_from)
+				VALUES ($1, $2, $3, $4, $5, $6)
+			"#,
+			user_id,
+			section as Section,
+			changed_by,
+			snapshot,
+			diff,
+			restored_from
+		)
+		.execute(&mut **transaction)
+		.await
+		.map_err(|error| anyhow!(error).context("Failed to insert revision"))?;
+
+		Ok(())
+	}
+
+	/// Newest first, optionally narrowed to one section and paginated by revision id
+	pub async fn list(&self, user_id: &i64, query: &HistoryQuery) -> anyhow::Result<Vec<Revision>> {
+		let revisions = sqlx::query_as!(
+			Revision,
+			r#"
+				SELECT
+					r.id,
+					r.section AS "section: Section",
+					r.changed_by,
+					u.username AS "changed_by_username?",
+					r.diff,
+					r.restored_from,
+					r.created_at
+				FROM profile_revisions r
+				LEFT JOIN users u ON u.id = r.changed_by
+				WHERE r.user_id = $1
+					AND ($2::VARCHAR IS NULL OR r.section = $2)
+					AND ($3::BIGINT IS NULL OR r.id < $3)
+				ORDER BY r.id DESC
+				LIMIT $4
+			"#,
+			user_id,
+			query.section as Option<Section>,
+			query.before,
+			query.limit
+		)
+		.fetch_all(&*self.database.pool)
+		.await
+		.map_err(|error| anyhow!(error).context("Failed to list revisions"))?;
+
+		Ok(revisions)
+	}
+
+	pub async fn find(&self, id: &i64, user_id: &i64) -> anyhow::Result<Option<RevisionSnapshot>> {
+		let revision = sqlx::query_as!(
+			RevisionSnapshot,
+			r#"
+				SELECT id, section AS "section: Section", snapshot, created_at
+				FROM profile_revisions
+				WHERE id = $1 AND user_id = $2
+			"#,
+			id,
+			user_id
+		)
+		.fetch_optional(&*self.database.pool)
+		.await
+		.map_err(|error| anyhow!(error).context("Failed to find revision"))?;
+
+		Ok(revision)
+	}
+
+	/// Puts a section back into the state captured by a revision and records that as a new revision
+	pub async fn restore(&self, user_id: &i64, revision: &RevisionSnapshot) -> anyhow::Result<()> {
+		let mut transaction = self
+			.database
+			.pool
+			.begin()
+			.await
+			.context("Failed to start restore transaction")?;
+
+		Self::replace_with(
+			&mut transaction,
+			user_id,
+			revision.section,
+			&revision.snapshot,
+			Some(revision.id),
+		)
+		.await?;
+
+		transaction.commit().await.context("Failed to commit restore")?;
+
+		Ok(())
+	}
+
+	/// Overwrites a section with a snapshot and records the change as made by the user themselves,
+	/// leaving the commit to the caller so several sections can be replaced atomically
+	pub async fn replace_with(
+		transaction: &mut Transaction<'_, Postgres>,
+		user_id: &i64,
+		section: Section,
+		snapshot: &Value,
+		restored_from: Option<i64>,
+	) -> anyhow::Result<()> {
+		Self::lock(transaction, user_id).await?;
+
+		let before = Self::snapshot_with(&mut **transaction, user_id, section).await?;
+
+		match section {
+			Section::Profile => Self::restore_profile(transaction, user_id, snapshot).await?,
+			Section::Experience => Self::restore_experience(transaction, user_id, snapshot).await?,
+			Section::Education => Self::restore_education(transaction, user_id, snapshot).await?,
+			Section::Skills => Self::restore_skills(transaction, user_id, snapshot).await?,
+			Section::Languages => Self::restore_languages(transaction, user_id, snapshot).await?,
+			Section::Certifications => Self::restore_certifications(transaction, user_id, snapshot).await?,
+			Section::Translations => Self::restore_translations(transaction, user_id, snapshot).await?,
+			Section::CustomSections => Self::restore_custom_sections(transaction, user_id, snapshot).await?,
+		}
+
+		Self::record_with(transaction, user_id, Some(user_id), section, before, restored_from).await
+	}
+
+	async fn restore_profile(
+		transaction: &mut Transaction<'_, Postgres>,
+		user_id: &i64,
+		snapshot: &Value,
+	) -> anyhow::Result<()> {
+		sqlx::query!(
+			r#"
+				UPDATE users
+				SET headline = $2::JSONB->>'headline', bio = $2::JSONB->>'bio',
+					default_locale = COALESCE($2::JSONB->>'default_locale', default_locale),
+					avatar_url = $2::JSONB->>'avatar_url', location_name = $2::JSONB->>'location_name',
+					location_id = ($2::JSONB->>'location_id')::BIGINT
+				WHERE id = $1
+			"#,
+			user_id,
+			snapshot
+		)
+		.execute(&mut **transaction)
+		.await
+		.context("Failed to restore profile")?;
+
+		Ok(())
+	}
+
+	async fn restore_experience(
+		transaction: &mut Transaction<'_, Postgres>,
+		user_id: &i64,
+		snapshot: &Value,
+	) -> anyhow::Result<()> {
+		sqlx::query!(
+			r#"
+				DELETE FROM experience
+				WHERE user_id = $1 AND id NOT IN (SELECT id FROM JSONB_POPULATE_RECORDSET(NULL::experience, $2))
+			"#,
+			user_id,
+			snapshot
+		)
+		.execute(&mut **transaction)
+		.await
+		.context("Failed to remove experience")?;
+
+		// Deleted entries come back under their old ids, so translations of kept entries survive
+		sqlx::query!(
+				r#"
+				INSERT INTO experience
+				SELECT * FROM JSONB_POPULATE_RECORDSET(NULL::experience, $2) WHERE user_id = $1
+				ON CONFLICT (id) DO UPDATE
+				SET company_id = EXCLUDED.company_id, company_name = EXCLUDED.company_name, occupation = EXCLUDED.occupation, location_name = EXCLUDED.location_name,
+					location_id = EXCLUDED.location_id, location_type = EXCLUDED.location_type, employment_type = EXCLUDED.employment_type, start_date = EXCLUDED.start_date,
+					end_date = EXCLUDED.end_date, is_current = EXCLUDED.is_current, description = EXCLUDED.description,
+					position = EXCLUDED.position
+				WHERE experience.user_id = $1
+			"#,
+				user_id,
+				snapshot
+			)
+			.execute(&mut **transaction)
+			.await
+			.context("Failed to restore experience")?;
+
+		// Drafts stage entries from client input, so their companies are checked against the names like any write
+		sqlx::query!(
+			r#"
+				UPDATE experience
+				SET company_id = NULL
+				WHERE user_id = $1
+					AND id IN (SELECT id FROM JSONB_POPULATE_RECORDSET(NULL::experience, $2))
+					AND company_id IS NOT NULL
+					AND NOT company_matches_name(company_id, company_name)
+			"#,
+			user_id,
+			snapshot
+		)
+		.execute(&mut **transaction)
+		.await
+		.context("Failed to resolve restored companies")?;
+
+		// Snapshots from before skills were linked to entries leave the links alone
+		sqlx::query!(
+			r#"
+				DELETE FROM experience_skills
+				WHERE experience_id IN (
+					SELECT (entry->>'id')::BIGINT FROM JSONB_ARRAY_ELEMENTS($2) AS entry
+					WHERE entry ? 'skill_ids' AND (entry->>'user_id')::BIGINT = $1
+				)
+			"#,
+			user_id,
+			snapshot
+		)
+		.execute(&mut **transaction)
+		.await
+		.context("Failed to remove experience skills")?;
+
+		sqlx::query!(
+			r#"
+				INSERT INTO experience_skills (experience_id, skill_id)
+				SELECT (entry->>'id')::BIGINT, skills.id
+				FROM JSONB_ARRAY_ELEMENTS($2) AS entry
+				CROSS JOIN JSONB_ARRAY_ELEMENTS_TEXT(COALESCE(entry->'skill_ids', '[]')) AS skill_id
+				JOIN skills ON skills.id = skill_id::BIGINT
+				WHERE (entry->>'user_id')::BIGINT = $1
+				ON CONFLICT DO NOTHING
+			"#,
+			user_id,
+			snapshot
+		)
+		.execute(&mut **transaction)
+		.await
+		.context("Failed to restore experience skills")?;
+
+		Ok(())
+	}
+
+	async fn restore_education(
+		transaction: &mut Transaction<'_, Postgres>,
+		user_id: &i64,
+		snapshot: &Value,
+	) -> anyhow::Result<()> {
+		sqlx::query!(
+			r#"
+				DELETE FROM education
+				WHERE user_id = $1 AND id NOT IN (SELECT id FROM JSONB_POPULATE_RECORDSET(NULL::education, $2))
+			"#,
+			user_id,
+			snapshot
+		)
+		.execute(&mut **transaction)
+		.await
+		.context("Failed to remove education")?;
+
+		sqlx::query!(
+			r#"
+				INSERT INTO education
+				SELECT * FROM JSONB_POPULATE_RECORDSET(NULL::education, $2) WHERE user_id = $1
+				ON CONFLICT (id) DO UPDATE
+				SET institution = EXCLUDED.institution, degree = EXCLUDED.degree, field_of_study = EXCLUDED.field_of_study,
+					start_date = EXCLUDED.start_date, end_date = EXCLUDED.end_date, grade = EXCLUDED.grade,
+					activities = EXCLUDED.activities, description = EXCLUDED.description, position = EXCLUDED.position
+				WHERE education.user_id = $1
+			"#,
+			user_id,
+			snapshot
+		)
+		.execute(&mut **transaction)
+		.await
+		.context("Failed to restore education")?;
+
+		Ok(())
+	}
+
+	async fn restore_skills(
+		transaction: &mut Transaction<'_, Postgres>,
+		user_id: &i64,
+		snapshot: &Value,
+	) -> anyhow::Result<()> {
+		sqlx::query!("DELETE FROM user_skills WHERE user_id = $1", user_id)
+			.execute(&mut **transaction)
+			.await
+			.context("Failed to remove skills")?;
+
+		// Skills removed from the catalog since the revision are skipped, and revisions from before proficiency
+		// and ordering restore without them
+		sqlx::query!(
+			r#"
+				INSERT INTO user_skills (user_id, skill_id, proficiency, years, last_used, position, is_pinned)
+				SELECT
+					$1, s.id, restored.proficiency, restored.years, restored.last_used, restored.position,
+					COALESCE(restored.is_pinned, FALSE)
+				FROM JSONB_TO_RECORDSET($2) AS restored (
+					id BIGINT, proficiency skill_proficiency, years INTEGER, last_used year_month, position INTEGER,
+					is_pinned BOOLEAN
+				)
+				JOIN skills s ON s.id = restored.id
+				ON CONFLICT DO NOTHING
+			"#,
+			user_id,
+			snapshot
+		)
+		.execute(&mut **transaction)
+		.await
+		.context("Failed to restore skills")?;
+
+		Ok(())
+	}
+
+	async fn restore_languages(
+		transaction: &mut Transaction<'_, Postgres>,
+		user_id: &i64,
+		snapshot: &Value,
+	) -> anyhow::Result<()> {
+		sqlx::query!("DELETE FROM user_languages WHERE user_id = $1", user_id)
+			.execute(&mut **transaction)
+			.await
+			.context("Failed to remove languages")?;
+
+		sqlx::query!(
+			r#"
+				INSERT INTO user_languages (user_id, language_code, proficiency)
+				SELECT $1, l.code, restored.proficiency
+				FROM JSONB_TO_RECORDSET($2) AS restored (code VARCHAR, proficiency cefr_level)
+				JOIN languages l ON l.code = restored.code
+				ON CONFLICT DO NOTHING
+			"#,
+			user_id,
+			snapshot
+		)
+		.execute(&mut **transaction)
+		.await
+		.context("Failed to restore languages")?;
+
+		Ok(())
+	}
+
+	async fn restore_certifications(
+		transaction: &mut Transaction<'_, Postgres>,
+		user_id: &i64,
+		snapshot: &Value,
+	) -> anyhow::Result<()> {
+		sqlx::query!(
+			r#"
+				DELETE FROM certifications
+				WHERE user_id = $1 AND id NOT IN (SELECT id FROM JSONB_POPULATE_RECORDSET(NULL::certifications, $2))
+			"#,
+			user_id,
+			snapshot
+		)
+		.execute(&mut **transaction)
+		.await
+		.context("Failed to remove certifications")?;
+
+		// Certifications come back with their reminders re-armed, since the snapshot leaves that state out
+		sqlx::query!(
+			r#"
+				INSERT INTO certifications
+				SELECT * FROM JSONB_POPULATE_RECORDSET(NULL::certifications, $2) WHERE user_id = $1
+				ON CONFLICT (id) DO UPDATE
+				SET name = EXCLUDED.name, issuing_organization = EXCLUDED.issuing_organization,
+					issue_date = EXCLUDED.issue_date, expiry_date = EXCLUDED.expiry_date,
+					credential_id = EXCLUDED.credential_id, credential_url = EXCLUDED.credential_url
+				WHERE certifications.user_id = $1
+			"#,
+			user_id,
+			snapshot
+		)
+		.execute(&mut **transaction)
+		.await
+		.context("Failed to restore certifications")?;
+
+		sqlx::query!(
+			r#"
+				DELETE FROM certification_skills
+				WHERE certification_id IN (SELECT id FROM certifications WHERE user_id = $1)
+			"#,
+			user_id
+		)
+		.execute(&mut **transaction)
+		.await
+		.context("Failed to remove certification skills")?;
+
+		sqlx::query!(
+			r#"
+				INSERT INTO certification_skills (certification_id, skill_id)
+				SELECT (entry->>'id')::BIGINT, skills.id
+				FROM JSONB_ARRAY_ELEMENTS($2) AS entry
+				CROSS JOIN JSONB_ARRAY_ELEMENTS_TEXT(COALESCE(entry->'skill_ids', '[]')) AS skill_id
+				JOIN skills ON skills.id = skill_id::BIGINT
+				WHERE (entry->>'user_id')::BIGINT = $1
+				ON CONFLICT DO NOTHING
+			"#,
+			user_id,
+			snapshot
+		)
+		.execute(&mut **transaction)
+		.await
+		.context("Failed to restore certification skills")?;
+
+		Ok(())
+	}
+
+	/// Translations of entries deleted since the revision are skipped
+	async fn restore_translations(
+		transaction: &mut Transaction<'_, Postgres>,
+		user_id: &i64,
+		snapshot: &Value,
+	) -> anyhow::Result<()> {
+		sqlx::query!("DELETE FROM user_translations WHERE user_id = $1", user_id)
+			.execute(&mut **transaction)
+			.await
+			.context("Failed to remove profile translations")?;
+
+		sqlx::query!(
+			r#"
+				INSERT INTO user_translations (user_id, locale, headline, bio)
+				SELECT $1, restored.locale, restored.headline, restored.bio
+				FROM JSONB_TO_RECORDSET($2::JSONB->'profile') AS restored (locale VARCHAR, headline VARCHAR, bio TEXT)
+			"#,
+			user_id,
+			snapshot
+		)
+		.execute(&mut **transaction)
+		.await
+		.context("Failed to restore profile translations")?;
+
+		sqlx::query!(
+			r#"
+				DELETE FROM experience_translations et
+				USING experience e
+				WHERE e.id = et.experience_id AND e.user_id = $1
+			"#,
+			user_id
+		)
+		.execute(&mut **transaction)
+		.await
+		.context("Failed to remove experience translations")?;
+
+		sqlx::query!(
+			r#"
+				INSERT INTO experience_translations (experience_id, locale, occupation, description)
+				SELECT e.id, restored.locale, restored.occupation, restored.description
+				FROM JSONB_TO_RECORDSET($2::JSONB->'experience') AS restored (
+					experience_id BIGINT, locale VARCHAR, occupation VARCHAR, description TEXT
+				)
+				JOIN experience e ON e.id = restored.experience_id AND e.user_id = $1
+			"#,
+			user_id,
+			snapshot
+		)
+		.execute(&mut **transaction)
+		.await
+		.context("Failed to restore experience translations")?;
+
+		Ok(())
+	}
+
+	async fn restore_custom_sections(
+		transaction: &mut Transaction<'_, Postgres>,
+		user_id: &i64,
+		snapshot: &Value,
+	) -> anyhow::Result<()> {
+		sqlx::query!(
+			r#"
+				DELETE FROM custom_sections
+				WHERE user_id = $1 AND id NOT IN (SELECT id FROM JSONB_POPULATE_RECORDSET(NULL::custom_sections, $2))
+			"#,
+			user_id,
+			snapshot
+		)
+		.execute(&mut **transaction)
+		.await
+		.context("Failed to remove custom sections")?;
+
+		sqlx::query!(
+			r#"
+				INSERT INTO custom_sections
+				SELECT * FROM JSONB_POPULATE_RECORDSET(NULL::custom_sections, $2) WHERE user_id = $1
+				ON CONFLICT (id) DO UPDATE
+				SET title = EXCLUDED.title, blocks = EXCLUDED.blocks, position = EXCLUDED.position
+				WHERE custom_sections.user_id = $1
+			"#,
+			user_id,
+			snapshot
+		)
+		.execute(&mut **transaction)
+		.await
+		.context("Failed to restore custom sections")?;
+
+		Ok(())
+	}
+}
+
+/// A transaction opened by `HistoryRepository::track`. Dropping it without committing rolls the write back.
+pub struct TrackedTransaction {
+	transaction: Transaction<'static, Postgres>,
+	user_id: i64,
+	changed_by: i64,
+	before: Vec<(Section, Value)>,
+}
+
+impl TrackedTransaction {
+	/// Records a revision of each tracked section and commits it together with the write
+	pub async fn commit(mut self) -> anyhow::Result<()> {
+		for (section, before) in self.before {
+			HistoryRepository::record_with(
+				&mut self.transaction,
+				&self.user_id,
+				Some(&self.changed_by),
+				section,
+				before,
+				None,
+			)
+			.await?;
+		}
+
+		self.transaction.commit().await.context("Failed to commit revision")
+	}
+}
+
+impl Deref for TrackedTransaction {
+	type Target = Transaction<'static, Postgres>;
+
+	fn deref(&self) -> &Self::Target {
+		&self.transaction
+	}
+}
+
+impl DerefMut for TrackedTransaction {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.transaction
+	}
+}
+
+/// Parts of a profile that are versioned as a whole
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+pub enum Section {
+	Profile,
+	Experience,
+	Education,
+	Skills,
+	Languages,
+	Certifications,
+	/// Profile and experience translations together, since both follow the profile's locales
+	Translations,
+	CustomSections,
+}
+
+impl Section {
+	fn empty_snapshot(self) -> Value {
+		match self {
+			Self::Profile => serde_json::json!({
+				"headline": null,
+				"bio": null,
+				"default_locale": DEFAULT_LOCALE,
+				"avatar_url": null,
+				"location_name": null,
+				"location_id": null,
+			}),
+			Self::Translations => serde_json::json!({ "profile": [], "experience": [] }),
+			Self::Experience
+			| Self::Education
+			| Self::Skills
+			| Self::Languages
+			| Self::Certifications
+			| Self::CustomSections => Value::Array(Vec::new()),
+		}
+	}
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Revision {
+	pub id: i64,
+	pub section: Section,
+	/// `None` for baselines captured before the first tracked change
+	pub changed_by: Option<i64>,
+	pub changed_by_username: Option<String>,
+	pub diff: Value,
+	pub restored_from: Option<i64>,
+	pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RevisionSnapshot {
+	pub id: i64,
+	pub section: Section,
+	pub snapshot: Value,
+	pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,127 @@
+use std::sync::Arc;
+
+use axum::{
+	extract::{Path, Query, State},
+	http::StatusCode,
+	middleware,
+	response::{IntoResponse, Response},
+	routing::{get, post},
+	Extension, Json, Router,
+};
+use thiserror::Error;
+use validator::{Validate, ValidationErrors};
+
+use crate::{
+	app::{ApiErrorResponse, ApiResult},
+	database::Database,
+	users::auth::{self, CurrentUser},
+};
+
+use super::{
+	dtos::HistoryQuery,
+	repository::{HistoryRepository, Revision, RevisionSnapshot},
+};
+
+#[derive(Clone)]
+pub struct HistoryState {
+	pub history_repository: HistoryRepository,
+}
+
+impl HistoryState {
+	pub fn new(database: &Arc<Database>) -> Self {
+		Self {
+			history_repository: HistoryRepository::new(database),
+		}
+	}
+}
+
+pub fn init() -> Router<HistoryState> {
+	Router::new()
+		.route("/users/me/history", get(get_history_route))
+		.route("/users/me/history/{revision_id}", get(get_revision_route))
+		.route("/users/me/history/{revision_id}/restore", post(restore_revision_route))
+		.route_layer(middleware::from_fn(auth::middleware))
+}
+
+async fn get_history_route(
+	Extension(current_user): Extension<CurrentUser>,
+	Query(query): Query<HistoryQuery>,
+	State(state): State<HistoryState>,
+) -> ApiResult<Json<Vec<Revision>>> {
+	query.validate().map_err(HistoryApiError::InvalidQuery)?;
+
+	let revisions = state
+		.history_repository
+		.list(&current_user.user_id, &query)
+		.await
+		.map_err(|_| HistoryApiError::List())?;
+
+	Ok((StatusCode::OK, Json(revisions)))
+}
+
+async fn get_revision_route(
+	Extension(current_user): Extension<CurrentUser>,
+	Path(revision_id): Path<i64>,
+	State(state): State<HistoryState>,
+) -> ApiResult<Json<RevisionSnapshot>> {
+	let revision = state
+		.history_repository
+		.find(&revision_id, &current_user.user_id)
+		.await
+		.map_err(|_| HistoryApiError::Get())?
+		.ok_or(HistoryApiError::NotFound(revision_id))?;
+
+	Ok((StatusCode::OK, Json(revision)))
+}
+
+async fn restore_revision_route(
+	Extension(current_user): Extension<CurrentUser>,
+	Path(revision_id): Path<i64>,
+	State(state): State<HistoryState>,
+) -> ApiResult<()> {
+	let revision = state
+		.history_repository
+		.find(&revision_id, &current_user.user_id)
+		.await
+		.map_err(|_| HistoryApiError::Get())?
+		.ok_or(HistoryApiError::NotFound(revision_id))?;
+
+	state
+		.history_repository
+		.restore(&current_user.user_id, &revision)
+		.await
+		.map_err(|_| HistoryApiError::Restore())?;
+
+	Ok((StatusCode::OK, ()))
+}
+
+#[derive(Debug, Error)]
+pub enum HistoryApiError {
+	#[error("Invalid history query: {0}")]
+	InvalidQuery(ValidationErrors),
+
+	#[error("Revision not found: {0}")]
+	NotFound(i64),
+
+	#[error("Failed to list revisions")]
+	List(),
+
+	#[error("Failed to get revision")]
+	Get(),
+
+	#[error("Failed to restore revision")]
+	Restore(),
+}
+
+impl IntoResponse for HistoryApiError {
+	fn into_response(self) -> Response {
+		let status_code = match self {
+			Self::InvalidQuery(_) => StatusCode::BAD_REQUEST,
+			Self::NotFound(_) => StatusCode::NOT_FOUND,
+			Self::List() | Self::Get() | Self::Restore() => StatusCode::INTERNAL_SERVER_ERROR,
+		};
+
+		log::error!("{self:?}");
+		ApiErrorResponse::new(status_code, self.to_string()).into_response()
+	}
+}
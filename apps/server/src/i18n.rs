@@ -0,0 +1,160 @@
+//! Locale negotiation for translated profile content
+
+use axum::{
+	extract::{FromRequestParts, Query},
+	http::{header, request::Parts},
+};
+use serde::Deserialize;
+use validator::ValidationError;
+
+/// Locale of profile text when the user hasn't picked one, matching the `users.default_locale` column default
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// Locales a client asked for, most preferred first, taken from `?lang=` and then `Accept-Language`
+#[derive(Debug, Clone, Default)]
+pub struct RequestedLocales(pub Vec<String>);
+
+#[derive(Debug, Deserialize)]
+struct LangQuery {
+	lang: Option<String>,
+}
+
+impl<S> FromRequestParts<S> for RequestedLocales
+where
+	S: Send + Sync,
+{
+	type Rejection = std::convert::Infallible;
+
+	async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+		let mut locales = Vec::new();
+
+		if let Ok(Query(LangQuery { lang: Some(lang) })) = Query::<LangQuery>::try_from_uri(&parts.uri) {
+			locales.extend(normalize_locale(&lang));
+		}
+
+		if let Some(accept_language) = parts
+			.headers
+			.get(header::ACCEPT_LANGUAGE)
+			.and_then(|value| value.to_str().ok())
+		{
+			locales.extend(parse_accept_language(accept_language));
+		}
+
+		Ok(Self(locales))
+	}
+}
+
+impl RequestedLocales {
+	/// Puts an explicitly chosen locale ahead of whatever the request asked for
+	pub fn prefer(mut self, locale: &str) -> Self {
+		if let Some(locale) = normalize_locale(locale) {
+			self.0.insert(0, locale);
+		}
+		self
+	}
+
+	/// Picks the best available locale for a profile: an exact match first, then a match on the language alone
+	/// (`de-AT` is served `de`), falling back to the profile's default locale
+	pub fn negotiate(&self, content_locales: &ContentLocales) -> NegotiatedLocale {
+		let available = || std::iter::once(&content_locales.default_locale).chain(&content_locales.translated);
+
+		let locale = self
+			.0
+			.iter()
+			.find_map(|requested| {
+				available().find(|locale| *locale == requested).or_else(|| {
+					let language = primary_language(requested);
+					available()
+						.filter(|locale| primary_language(locale) == language)
+						.min_by_key(|locale| locale.len())
+				})
+			})
+			.unwrap_or(&content_locales.default_locale);
+
+		NegotiatedLocale {
+			locale: locale.clone(),
+			is_default: *locale == content_locales.default_locale,
+		}
+	}
+}
+
+/// The locale a profile's untranslated text is written in, plus every locale it has translations for
+#[derive(Debug, Clone)]
+pub struct ContentLocales {
+	pub default_locale: String,
+	pub translated: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NegotiatedLocale {
+	pub locale: String,
+	pub is_default: bool,
+}
+
+impl NegotiatedLocale {
+	/// The translation to overlay on the untranslated text, if any
+	pub fn translation(&self) -> Option<&str> {
+		(!self.is_default).then_some(self.locale.as_str())
+	}
+}
+
+/// Normalizes a BCP 47 style tag (`en`, `pt-BR`, `zh-Hant`) to lowercase language and uppercase region,
+/// rejecting anything that doesn't look like one
+pub fn normalize_locale(tag: &str) -> Option<String> {
+	let mut subtags = tag.trim().split(['-', '_']);
+
+	let language = subtags.next()?;
+	if !(2..=3).contains(&language.len()) || !language.chars().all(|char| char.is_ascii_alphabetic()) {
+		return None;
+	}
+
+	let mut normalized = language.to_ascii_lowercase();
+	for subtag in subtags {
+		if !(2..=8).contains(&subtag.len()) || !subtag.chars().all(|char| char.is_ascii_alphanumeric()) {
+			return None;
+		}
+
+		normalized.push('-');
+		if subtag.len() == 2 {
+			normalized.push_str(&subtag.to_ascii_uppercase());
+		} else {
+			normalized.push_str(subtag);
+		}
+	}
+
+	(normalized.len() <= 35).then_some(normalized)
+}
+
+pub fn validate_locale(locale: &str) -> Result<(), ValidationError> {
+	if normalize_locale(locale).is_some() {
+		Ok(())
+	} else {
+		Err(ValidationError::new(
+			"Wrong locale, expected a language tag like en or pt-BR",
+		))
+	}
+}
+
+fn primary_language(locale: &str) -> &str {
+	locale.split('-').next().unwrap_or(locale)
+}
+
+/// Orders the tags of an `Accept-Language` header by quality, dropping wildcards and invalid tags
+fn parse_accept_language(header: &str) -> Vec<String> {
+	let mut weighted: Vec<(String, f32)> = header
+		.split(',')
+		.filter_map(|entry| {
+			let mut parts = entry.split(';');
+			let tag = normalize_locale(parts.next()?)?;
+			let quality = parts
+				.find_map(|parameter| parameter.trim().strip_prefix("q="))
+				.map_or(Some(1.0), |quality| quality.trim().parse::<f32>().ok())?;
+
+			(quality > 0.0).then_some((tag, quality))
+		})
+		.collect();
+
+	// Stable sort keeps header order between equal weights
+	weighted.sort_by(|(_, left), (_, right)| right.total_cmp(left));
+	weighted.into_iter().map(|(tag, _)| tag).collect()
+}
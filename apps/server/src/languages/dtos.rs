@@ -0,0 +1,39 @@
+use std::collections::HashSet;
+
+use serde::Deserialize;
+use validator::{Validate, ValidationError};
+
+use crate::validation::field_error;
+
+use super::repository::CefrLevel;
+
+#[derive(Debug, Deserialize, Validate)]
+#[validate(schema(function = "validate_unique_codes"))]
+pub struct UpdateUserLanguagesDto {
+	#[validate(nested)]
+	pub languages: Vec<UserLanguageDto>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UserLanguageDto {
+	#[validate(length(equal = 2, message = "Language code must be an ISO 639-1 code"))]
+	pub code: String,
+	pub proficiency: CefrLevel,
+}
+
+fn validate_unique_codes(languages_dto: &UpdateUserLanguagesDto) -> Result<(), ValidationError> {
+	let mut codes = HashSet::new();
+	if languages_dto
+		.languages
+		.iter()
+		.all(|language| codes.insert(language.code.to_lowercase()))
+	{
+		Ok(())
+	} else {
+		Err(field_error(
+			"languages",
+			ValidationError::new("Each language can only be listed once"),
+		))
+	}
+}
@@ -0,0 +1,113 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use sqlx::{Postgres, Transaction};
+
+use crate::database::Database;
+
+use super::dtos::UserLanguageDto;
+
+#[derive(Clone)]
+pub struct LanguagesRepository {
+	pub(crate) database: Arc<Database>,
+}
+
+impl LanguagesRepository {
+	pub fn new(database: &Arc<Database>) -> Self {
+		Self {
+			database: Arc::clone(database),
+		}
+	}
+
+	pub async fn list(&self) -> anyhow::Result<Vec<Language>> {
+		let languages = sqlx::query_as!(Language, "SELECT * FROM languages ORDER BY name")
+			.fetch_all(&*self.database.pool)
+			.await
+			.context("Failed to fetch languages")?;
+
+		Ok(languages)
+	}
+
+	/// Strongest languages first
+	pub async fn get_user_languages(&self, user_id: &i64) -> anyhow::Result<Vec<UserLanguage>> {
+		let languages = sqlx::query_as!(
+			UserLanguage,
+			r#"
+				SELECT l.code, l.name, ul.proficiency AS "proficiency: CefrLevel"
+				FROM languages l
+				JOIN user_languages ul ON l.code = ul.language_code
+				WHERE ul.user_id = $1
+				ORDER BY ul.proficiency DESC, l.name
+			"#,
+			user_id
+		)
+		.fetch_all(&*self.database.pool)
+		.await
+		.context("Failed to get user languages")?;
+
+		Ok(languages)
+	}
+
+	/// Replaces the user's languages; fails without changes when a code is not in the catalog
+	pub async fn update_user_languages(
+		transaction: &mut Transaction<'_, Postgres>,
+		user_id: &i64,
+		languages: Vec<UserLanguageDto>,
+	) -> anyhow::Result<()> {
+		let (codes, proficiencies): (Vec<String>, Vec<CefrLevel>) = languages
+			.into_iter()
+			.map(|language| (language.code.to_lowercase(), language.proficiency))
+			.unzip();
+
+		sqlx::query!("DELETE FROM user_languages WHERE user_id = $1", user_id)
+			.execute(&mut **transaction)
+			.await
+			.context("Failed to delete user languages")?;
+
+		sqlx::query!(
+			r#"
+				INSERT INTO user_languages (user_id, language_code, proficiency)
+				SELECT $1, code, proficiency FROM UNNEST($2::VARCHAR[], $3::cefr_level[]) AS input (code, proficiency)
+				ON CONFLICT (user_id, language_code) DO UPDATE SET proficiency = EXCLUDED.proficiency
+			"#,
+			user_id,
+			&codes,
+			&proficiencies as &[CefrLevel]
+		)
+		.execute(&mut **transaction)
+		.await
+		.context("Failed to insert user languages")?;
+
+		Ok(())
+	}
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, sqlx::FromRow)]
+pub struct Language {
+	pub code: String,
+	pub name: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, sqlx::FromRow)]
+pub struct UserLanguage {
+	pub code: String,
+	pub name: String,
+	pub proficiency: CefrLevel,
+}
+
+/// Common European Framework of Reference levels, ordered from weakest to strongest
+/// like the `cefr_level` enum in the database
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize, sqlx::Type)]
+#[sqlx(type_name = "cefr_level")]
+pub enum CefrLevel {
+	A1,
+	A2,
+	B1,
+	B2,
+	C1,
+	C2,
+	#[serde(rename = "native")]
+	#[sqlx(rename = "native")]
+	Native,
+}
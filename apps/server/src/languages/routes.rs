@@ -0,0 +1,114 @@
+use std::sync::Arc;
+
+use axum::{
+	extract::{Path, State},
+	http::StatusCode,
+	middleware,
+	response::{IntoResponse, Response},
+	routing::{get, post},
+	Extension, Json, Router,
+};
+use thiserror::Error;
+
+use crate::{
+	app::{ApiErrorResponse, ApiResult},
+	database::Database,
+	history::repository::{HistoryRepository, Section},
+	users::auth::{self, CurrentUser},
+	validation::ValidatedJson,
+};
+
+use super::{
+	dtos::UpdateUserLanguagesDto,
+	repository::{Language, LanguagesRepository, UserLanguage},
+};
+
+#[derive(Clone)]
+pub struct LanguagesState {
+	pub languages_repository: LanguagesRepository,
+	pub history_repository: HistoryRepository,
+}
+
+impl LanguagesState {
+	pub fn new(database: &Arc<Database>) -> Self {
+		Self {
+			languages_repository: LanguagesRepository::new(database),
+			history_repository: HistoryRepository::new(database),
+		}
+	}
+}
+
+pub fn init() -> Router<LanguagesState> {
+	Router::new()
+		.route("/languages", post(update_user_languages_route))
+		.route_layer(middleware::from_fn(auth::middleware))
+		.route("/languages", get(get_languages_route))
+		.route("/languages/{user_id}", get(get_user_languages_route))
+}
+
+async fn get_languages_route(State(state): State<LanguagesState>) -> ApiResult<Json<Vec<Language>>> {
+	let languages = state
+		.languages_repository
+		.list()
+		.await
+		.map_err(|_| LanguagesApiError::List())?;
+
+	Ok((StatusCode::OK, Json(languages)))
+}
+
+async fn get_user_languages_route(
+	Path(user_id): Path<i64>,
+	State(state): State<LanguagesState>,
+) -> ApiResult<Json<Vec<UserLanguage>>> {
+	let user_languages = state
+		.languages_repository
+		.get_user_languages(&user_id)
+		.await
+		.map_err(|_| LanguagesApiError::GetUser())?;
+
+	Ok((StatusCode::OK, Json(user_languages)))
+}
+
+async fn update_user_languages_route(
+	Extension(current_user): Extension<CurrentUser>,
+	State(state): State<LanguagesState>,
+	ValidatedJson(request): ValidatedJson<UpdateUserLanguagesDto>,
+) -> ApiResult<()> {
+	let mut transaction = state
+		.history_repository
+		.track(&current_user.user_id, &current_user.user_id, &[Section::Languages])
+		.await
+		.map_err(|_| LanguagesApiError::Update())?;
+
+	LanguagesRepository::update_user_languages(&mut transaction, &current_user.user_id, request.languages)
+		.await
+		.map_err(|_| LanguagesApiError::Update())?;
+
+	transaction.commit().await.map_err(|_| LanguagesApiError::Update())?;
+
+	Ok((StatusCode::OK, ()))
+}
+
+#[derive(Debug, Error)]
+pub enum LanguagesApiError {
+	#[error("Failed to get languages")]
+	List(),
+
+	#[error("Failed to get user languages")]
+	GetUser(),
+
+	#[error("Failed to update user languages")]
+	Update(),
+}
+
+impl IntoResponse for LanguagesApiError {
+	fn into_response(self) -> Response {
+		let status_code = match self {
+			Self::List() | Self::GetUser() => StatusCode::INTERNAL_SERVER_ERROR,
+			Self::Update() => StatusCode::BAD_REQUEST,
+		};
+
+		log::error!("{self:?}");
+		ApiErrorResponse::new(status_code, self.to_string()).into_response()
+	}
+}
@@ -0,0 +1,59 @@
+//! Background job loading the bundled location dataset at startup, then resolving the locations of entries and
+//! profiles written before it was there. Rows written afterwards are resolved by a trigger as they're saved.
+
+use std::sync::Arc;
+
+use crate::database::Database;
+
+use super::{
+	dataset,
+	repository::{BackfillBatch, LocationsRepository},
+};
+
+/// Rows resolved per statement, so the backfill never holds locks on a large part of a table
+const BATCH_SIZE: i64 = 500;
+
+pub fn spawn(database: &Arc<Database>) {
+	let locations_repository = LocationsRepository::new(database);
+
+	tokio::spawn(async move {
+		if let Err(error) = load_and_backfill(&locations_repository).await {
+			log::error!("Failed to load and backfill locations: {error:?}");
+		}
+	});
+}
+
+async fn load_and_backfill(locations_repository: &LocationsRepository) -> anyhow::Result<()> {
+	let dataset = dataset::parse()?;
+	locations_repository.load(&dataset).await?;
+
+	log::info!("✅ Locations dataset status: {} cities", dataset.cities.len());
+
+	let mut after_id = 0;
+	let mut experience_resolved = 0;
+
+	while let BackfillBatch {
+		last_id: Some(last_id),
+		resolved,
+	} = locations_repository.backfill_experience(after_id, BATCH_SIZE).await?
+	{
+		after_id = last_id;
+		experience_resolved += resolved;
+	}
+
+	let mut after_id = 0;
+	let mut users_resolved = 0;
+
+	while let BackfillBatch {
+		last_id: Some(last_id),
+		resolved,
+	} = locations_repository.backfill_users(after_id, BATCH_SIZE).await?
+	{
+		after_id = last_id;
+		users_resolved += resolved;
+	}
+
+	log::info!("✅ Locations backfill status: {experience_resolved} entries, {users_resolved} profiles");
+
+	Ok(())
+}
@@ -0,0 +1,203 @@
+//! `GeoNames` cities with the regions and countries they're in. A small extract is bundled, kept in the dump's
+//! tab separated layout (`cities15000.txt`, `admin1CodesASCII.txt`); the full dump is read instead when
+//! `GEONAMES_DUMP_DIR` points to it, see `data/geonames/README.md`.
+
+use std::{borrow::Cow, collections::HashSet, fs, path::Path};
+
+use anyhow::{Context, Result};
+use csv::StringRecord;
+
+use crate::settings::SETTINGS;
+
+const CITIES: &str = include_str!("../../data/geonames/cities.tsv");
+const REGIONS: &str = include_str!("../../data/geonames/admin1CodesASCII.tsv");
+/// Code, name and comma separated other names, trimmed down from `countryInfo.txt`
+const COUNTRIES: &str = include_str!("../../data/geonames/countries.tsv");
+
+pub struct Dataset {
+	pub countries: Vec<Country>,
+	pub regions: Vec<Region>,
+	pub cities: Vec<City>,
+}
+
+pub struct Country {
+	pub code: String,
+	pub name: String,
+	pub aliases: Vec<String>,
+}
+
+pub struct Region {
+	/// Like "US.CA"
+	pub code: String,
+	pub country_code: String,
+	pub name: String,
+}
+
+pub struct City {
+	/// `GeoNames` id
+	pub id: i64,
+	pub name: String,
+	pub country_code: String,
+	/// `None` when the region isn't part of the dataset
+	pub region_code: Option<String>,
+	pub latitude: f64,
+	pub longitude: f64,
+	pub timezone: String,
+	pub population: i64,
+	/// Its own, ASCII and alternate names
+	pub names: Vec<String>,
+	/// Initials of a multi-word name, like "SF" for San Francisco
+	pub abbreviation: Option<String>,
+}
+
+pub fn parse() -> Result<Dataset> {
+	let mut countries = records(COUNTRIES)
+		.context("Failed to read countries")?
+		.iter()
+		.map(|record| Country {
+			code: field(record, 0).to_string(),
+			name: field(record, 1).to_string(),
+			aliases: split_names(field(record, 2)),
+		})
+		.collect::<Vec<_>>();
+
+	let (regions_tsv, cities_tsv) = match &SETTINGS.geonames.dump_dir {
+		Some(dump_dir) => {
+			add_countries(&mut countries, &read_dump(dump_dir, "countryInfo.txt")?)?;
+			(
+				Cow::Owned(read_dump(dump_dir, "admin1CodesASCII.txt")?),
+				Cow::Owned(read_dump(dump_dir, "cities15000.txt")?),
+			)
+		}
+		None => (Cow::Borrowed(REGIONS), Cow::Borrowed(CITIES)),
+	};
+
+	let country_codes = countries
+		.iter()
+		.map(|country| country.code.as_str())
+		.collect::<HashSet<_>>();
+
+	let regions = records(&regions_tsv)
+		.context("Failed to read regions")?
+		.iter()
+		.filter_map(|record| {
+			let code = field(record, 0);
+			let (country_code, _) = code.split_once('.')?;
+
+			country_codes.contains(country_code).then(|| Region {
+				code: code.to_string(),
+				country_code: country_code.to_string(),
+				name: field(record, 1).to_string(),
+			})
+		})
+		.collect::<Vec<_>>();
+
+	let region_codes = regions
+		.iter()
+		.map(|region| region.code.as_str())
+		.collect::<HashSet<_>>();
+
+	let mut cities = Vec::new();
+
+	for record in records(&cities_tsv).context("Failed to read cities")? {
+		let country_code = field(&record, 8);
+
+		// Cities of countries missing from the dataset couldn't be shown with one
+		if !country_codes.contains(country_code) {
+			continue;
+		}
+
+		let id = field(&record, 0);
+		let region_code = format!("{country_code}.{}", field(&record, 10));
+		let (name, ascii_name) = (field(&record, 1), field(&record, 2));
+
+		let mut names = vec![name.to_string(), ascii_name.to_string()];
+		names.extend(split_names(field(&record, 3)));
+
+		cities.push(City {
+			id: id.parse().with_context(|| format!("Wrong city id: {id}"))?,
+			name: name.to_string(),
+			country_code: country_code.to_string(),
+			region_code: region_codes.contains(region_code.as_str()).then_some(region_code),
+			latitude: field(&record, 4)
+				.parse()
+				.with_context(|| format!("Wrong latitude of city {id}"))?,
+			longitude: field(&record, 5)
+				.parse()
+				.with_context(|| format!("Wrong longitude of city {id}"))?,
+			timezone: field(&record, 17).to_string(),
+			population: field(&record, 14).parse().unwrap_or_default(),
+			names,
+			abbreviation: initials(ascii_name),
+		});
+	}
+
+	Ok(Dataset {
+		countries,
+		regions,
+		cities,
+	})
+}
+
+fn read_dump(dump_dir: &Path, file_name: &str) -> Result<String> {
+	let path = dump_dir.join(file_name);
+	fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))
+}
+
+/// Adds the countries of `countryInfo.txt` the bundled list doesn't have, which keeps its names and aliases
+fn add_countries(countries: &mut Vec<Country>, country_info: &str) -> Result<()> {
+	let known = countries
+		.iter()
+		.map(|country| country.code.clone())
+		.collect::<HashSet<_>>();
+
+	for record in records(country_info).context("Failed to read countryInfo.txt")? {
+		let code = field(&record, 0);
+		if !known.contains(code) {
+			countries.push(Country {
+				code: code.to_string(),
+				name: field(&record, 4).to_string(),
+				aliases: Vec::new(),
+			});
+		}
+	}
+
+	Ok(())
+}
+
+fn records(data: &str) -> Result<Vec<StringRecord>> {
+	let records = csv::ReaderBuilder::new()
+		.delimiter(b'\t')
+		.has_headers(false)
+		.quoting(false)
+		.flexible(true)
+		// countryInfo.txt starts with commented out notes and headers
+		.comment(Some(b'#'))
+		.from_reader(data.as_bytes())
+		.records()
+		.collect::<Result<Vec<_>, _>>()?;
+
+	Ok(records)
+}
+
+fn field(record: &StringRecord, index: usize) -> &str {
+	record.get(index).unwrap_or_default().trim()
+}
+
+fn split_names(names: &str) -> Vec<String> {
+	names
+		.split(',')
+		.map(str::trim)
+		.filter(|name| !name.is_empty())
+		.map(str::to_string)
+		.collect()
+}
+
+fn initials(name: &str) -> Option<String> {
+	let words = name
+		.split(|char: char| char.is_whitespace() || char == '-')
+		.filter(|word| !word.is_empty())
+		.collect::<Vec<_>>();
+
+	(words.len() > 1).then(|| words.iter().filter_map(|word| word.chars().next()).collect())
+}
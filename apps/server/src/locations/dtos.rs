@@ -0,0 +1,25 @@
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct AutocompleteQuery {
+	/// Start of a city name, anything after the first comma is ignored
+	#[validate(length(min = 1, max = 255, message = "Wrong query length"))]
+	pub q: String,
+	#[serde(default = "default_limit")]
+	#[validate(range(min = 1, max = 20, message = "Limit must be between 1 and 20"))]
+	pub limit: i64,
+}
+
+const fn default_limit() -> i64 {
+	10
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolveQuery {
+	/// Free text location like "Mountain View, CA"
+	#[validate(length(min = 1, max = 255, message = "Wrong query length"))]
+	pub q: String,
+}
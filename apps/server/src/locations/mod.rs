@@ -0,0 +1,5 @@
+pub mod backfill;
+pub mod dataset;
+pub mod dtos;
+pub mod repository;
+pub mod routes;
@@ -0,0 +1,302 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use serde::Serialize;
+use sqlx::{Postgres, Transaction};
+
+use crate::database::Database;
+
+use super::dataset::{City, Dataset};
+
+#[derive(Clone)]
+pub struct LocationsRepository {
+	pub(crate) database: Arc<Database>,
+}
+
+impl LocationsRepository {
+	pub fn new(database: &Arc<Database>) -> Self {
+		Self {
+			database: Arc::clone(database),
+		}
+	}
+
+	/// Cities whose name, or a word in it, starts with the query, most populated first
+	pub async fn autocomplete(&self, query: &str, limit: i64) -> anyhow::Result<Vec<Location>> {
+		let locations = sqlx::query_as!(
+			Location,
+			r#"
+				WITH query AS (
+					SELECT location_key(SPLIT_PART($1, ',', 1)) AS key
+				), matches AS (
+					SELECT n.location_id, BOOL_OR(n.name LIKE q.key || '%') AS is_prefix
+					FROM location_names n, query q
+					WHERE q.key <> '' AND (
+						(NOT n.is_abbreviation AND (n.name LIKE q.key || '%' OR n.name LIKE '% ' || q.key || '%'))
+						OR (n.is_abbreviation AND n.name = q.key)
+					)
+					GROUP BY n.location_id
+				)
+				SELECT
+					l.id, l.name, r.name AS "region?", c.name AS country, l.country_code AS "country_code!",
+					l.latitude, l.longitude, l.timezone
+				FROM matches m
+				JOIN locations l ON l.id = m.location_id
+				JOIN countries c ON c.code = l.country_code
+				LEFT JOIN regions r ON r.code = l.region_code
+				ORDER BY m.is_prefix DESC, l.population DESC, l.id
+				LIMIT $2
+			"#,
+			query,
+			limit
+		)
+		.fetch_all(&*self.database.pool)
+		.await
+		.context("Failed to autocomplete locations")?;
+
+		Ok(locations)
+	}
+
+	/// Returns `None` when the text doesn't name a known city
+	pub async fn resolve(&self, query: &str) -> anyhow::Result<Option<Location>> {
+		let location = sqlx::query_as!(
+			Location,
+			r#"
+				SELECT
+					l.id, l.name, r.name AS "region?", c.name AS country, l.country_code AS "country_code!",
+					l.latitude, l.longitude, l.timezone
+				FROM locations l
+				JOIN countries c ON c.code = l.country_code
+				LEFT JOIN regions r ON r.code = l.region_code
+				WHERE l.id = resolve_location($1)
+			"#,
+			query
+		)
+		.fetch_optional(&*self.database.pool)
+		.await
+		.context("Failed to resolve location")?;
+
+		Ok(location)
+	}
+
+	/// Upserts the whole dataset, so a newer one updates rows in place and ids stored next to locations keep
+	/// pointing at the same cities
+	pub async fn load(&self, dataset: &Dataset) -> anyhow::Result<()> {
+		let mut transaction = self.database.pool.begin().await?;
+
+		let (codes, names, aliases): (Vec<_>, Vec<_>, Vec<_>) = dataset
+			.countries
+			.iter()
+			.map(|country| (country.code.clone(), country.name.clone(), country.aliases.join(",")))
+			.collect();
+
+		sqlx::query!(
+			r#"
+				INSERT INTO countries (code, name, aliases)
+				SELECT code, name, COALESCE(STRING_TO_ARRAY(NULLIF(aliases, ''), ','), '{}')
+				FROM UNNEST($1::TEXT[], $2::TEXT[], $3::TEXT[]) AS input (code, name, aliases)
+				ON CONFLICT (code) DO UPDATE
+				SET name = EXCLUDED.name, aliases = EXCLUDED.aliases
+			"#,
+			&codes,
+			&names,
+			&aliases
+		)
+		.execute(&mut *transaction)
+		.await
+		.context("Failed to load countries")?;
+
+		let (codes, country_codes, names): (Vec<_>, Vec<_>, Vec<_>) = dataset
+			.regions
+			.iter()
+			.map(|region| (region.code.clone(), region.country_code.clone(), region.name.clone()))
+			.collect();
+
+		sqlx::query!(
+			r#"
+				INSERT INTO regions (code, country_code, name)
+				SELECT * FROM UNNEST($1::TEXT[], $2::TEXT[], $3::TEXT[])
+				ON CONFLICT (code) DO UPDATE
+				SET country_code = EXCLUDED.country_code, name = EXCLUDED.name
+			"#,
+			&codes,
+			&country_codes,
+			&names
+		)
+		.execute(&mut *transaction)
+		.await
+		.context("Failed to load regions")?;
+
+		let cities = &dataset.cities;
+		let ids = cities.iter().map(|city| city.id).collect::<Vec<_>>();
+
+		sqlx::query!(
+			r#"
+				INSERT INTO locations (id, name, country_code, region_code, latitude, longitude, timezone, population)
+				SELECT * FROM UNNEST(
+					$1::BIGINT[], $2::TEXT[], $3::TEXT[], $4::TEXT[],
+					$5::DOUBLE PRECISION[], $6::DOUBLE PRECISION[], $7::TEXT[], $8::BIGINT[]
+				)
+				ON CONFLICT (id) DO UPDATE
+				SET
+					name = EXCLUDED.name,
+					country_code = EXCLUDED.country_code,
+					region_code = EXCLUDED.region_code,
+					latitude = EXCLUDED.latitude,
+					longitude = EXCLUDED.longitude,
+					timezone = EXCLUDED.timezone,
+					population = EXCLUDED.population
+			"#,
+			&ids,
+			&cities.iter().map(|city| city.name.clone()).collect::<Vec<_>>(),
+			&cities.iter().map(|city| city.country_code.clone()).collect::<Vec<_>>(),
+			&cities.iter().map(|city| city.region_code.clone()).collect::<Vec<_>>() as &[Option<String>],
+			&cities.iter().map(|city| city.latitude).collect::<Vec<_>>(),
+			&cities.iter().map(|city| city.longitude).collect::<Vec<_>>(),
+			&cities.iter().map(|city| city.timezone.clone()).collect::<Vec<_>>(),
+			&cities.iter().map(|city| city.population).collect::<Vec<_>>()
+		)
+		.execute(&mut *transaction)
+		.await
+		.context("Failed to load locations")?;
+
+		Self::load_names(&mut transaction, cities, &ids).await?;
+
+		transaction.commit().await?;
+
+		Ok(())
+	}
+
+	/// Replaces the names the cities are matched on
+	async fn load_names(
+		transaction: &mut Transaction<'_, Postgres>,
+		cities: &[City],
+		ids: &[i64],
+	) -> anyhow::Result<()> {
+		// Names first, so an initialism that's also a listed name ("NYC") stays matchable by similarity
+		let (name_ids, names, abbreviations): (Vec<_>, Vec<_>, Vec<_>) = cities
+			.iter()
+			.flat_map(|city| {
+				let names = city.names.iter().map(|name| (city.id, name.clone(), false));
+				names.chain(
+					city.abbreviation
+						.iter()
+						.map(|abbreviation| (city.id, abbreviation.clone(), true)),
+				)
+			})
+			.collect();
+
+		sqlx::query!("DELETE FROM location_names WHERE location_id = ANY($1)", ids)
+			.execute(&mut **transaction)
+			.await
+			.context("Failed to clear location names")?;
+
+		sqlx::query!(
+			r#"
+				INSERT INTO location_names (location_id, name, is_abbreviation)
+				SELECT location_id, location_key(name), is_abbreviation
+				FROM UNNEST($1::BIGINT[], $2::TEXT[], $3::BOOLEAN[]) WITH ORDINALITY AS input (location_id, name, is_abbreviation, position)
+				WHERE location_key(name) <> ''
+				ORDER BY position
+				ON CONFLICT DO NOTHING
+			"#,
+			&name_ids,
+			&names,
+			&abbreviations
+		)
+		.execute(&mut **transaction)
+		.await
+		.context("Failed to load location names")?;
+
+		Ok(())
+	}
+
+	/// Resolves the next batch of entries without a location after `after_id`
+	pub async fn backfill_experience(&self, after_id: i64, batch_size: i64) -> anyhow::Result<BackfillBatch> {
+		let batch = sqlx::query_as!(
+			BackfillBatch,
+			r#"
+				WITH batch AS (
+					SELECT id, resolve_location(location_name) AS location_id
+					FROM (
+						SELECT id, location_name
+						FROM experience
+						WHERE location_id IS NULL AND id > $1
+						ORDER BY id
+						LIMIT $2
+					) AS pending
+				), updated AS (
+					UPDATE experience e
+					SET location_id = batch.location_id
+					FROM batch
+					WHERE e.id = batch.id AND batch.location_id IS NOT NULL
+					RETURNING e.id
+				)
+				SELECT MAX(id) AS last_id, (SELECT COUNT(*) FROM updated) AS "resolved!"
+				FROM batch
+			"#,
+			after_id,
+			batch_size
+		)
+		.fetch_one(&*self.database.pool)
+		.await
+		.context("Failed to backfill experience locations")?;
+
+		Ok(batch)
+	}
+
+	/// Resolves the next batch of profiles with a location but no resolved one after `after_id`
+	pub async fn backfill_users(&self, after_id: i64, batch_size: i64) -> anyhow::Result<BackfillBatch> {
+		let batch = sqlx::query_as!(
+			BackfillBatch,
+			r#"
+				WITH batch AS (
+					SELECT id, resolve_location(location_name) AS location_id
+					FROM (
+						SELECT id, location_name
+						FROM users
+						WHERE location_id IS NULL AND location_name IS NOT NULL AND id > $1
+						ORDER BY id
+						LIMIT $2
+					) AS pending
+				), updated AS (
+					UPDATE users u
+					SET location_id = batch.location_id
+					FROM batch
+					WHERE u.id = batch.id AND batch.location_id IS NOT NULL
+					RETURNING u.id
+				)
+				SELECT MAX(id) AS last_id, (SELECT COUNT(*) FROM updated) AS "resolved!"
+				FROM batch
+			"#,
+			after_id,
+			batch_size
+		)
+		.fetch_one(&*self.database.pool)
+		.await
+		.context("Failed to backfill profile locations")?;
+
+		Ok(batch)
+	}
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Location {
+	/// `GeoNames` id
+	pub id: i64,
+	pub name: String,
+	pub region: Option<String>,
+	pub country: String,
+	/// ISO 3166-1 alpha-2
+	pub country_code: String,
+	pub latitude: f64,
+	pub longitude: f64,
+	/// IANA name like `America/Los_Angeles`
+	pub timezone: String,
+}
+
+pub struct BackfillBatch {
+	/// `None` once there's nothing left to resolve
+	pub last_id: Option<i64>,
+	pub resolved: i64,
+}
@@ -0,0 +1,100 @@
+use std::sync::Arc;
+
+use axum::{
+	extract::{Query, State},
+	http::StatusCode,
+	response::{IntoResponse, Response},
+	routing::get,
+	Json, Router,
+};
+use thiserror::Error;
+use validator::{Validate, ValidationErrors};
+
+use crate::{
+	app::{ApiErrorResponse, ApiResult},
+	database::Database,
+};
+
+use super::{
+	dtos::{AutocompleteQuery, ResolveQuery},
+	repository::{Location, LocationsRepository},
+};
+
+#[derive(Clone)]
+pub struct LocationsState {
+	pub locations_repository: LocationsRepository,
+}
+
+impl LocationsState {
+	pub fn new(database: &Arc<Database>) -> Self {
+		Self {
+			locations_repository: LocationsRepository::new(database),
+		}
+	}
+}
+
+pub fn init() -> Router<LocationsState> {
+	Router::new()
+		.route("/locations/autocomplete", get(autocomplete_route))
+		.route("/locations/resolve", get(resolve_route))
+}
+
+async fn autocomplete_route(
+	Query(query): Query<AutocompleteQuery>,
+	State(state): State<LocationsState>,
+) -> ApiResult<Json<Vec<Location>>> {
+	query.validate().map_err(LocationsApiError::InvalidQuery)?;
+
+	let locations = state
+		.locations_repository
+		.autocomplete(&query.q, query.limit)
+		.await
+		.map_err(|_| LocationsApiError::Autocomplete())?;
+
+	Ok((StatusCode::OK, Json(locations)))
+}
+
+/// The city a free text location like the ones stored on entries and profiles resolves to
+async fn resolve_route(
+	Query(query): Query<ResolveQuery>,
+	State(state): State<LocationsState>,
+) -> ApiResult<Json<Location>> {
+	query.validate().map_err(LocationsApiError::InvalidQuery)?;
+
+	let location = state
+		.locations_repository
+		.resolve(&query.q)
+		.await
+		.map_err(|_| LocationsApiError::Resolve())?
+		.ok_or_else(|| LocationsApiError::NotFound(query.q.clone()))?;
+
+	Ok((StatusCode::OK, Json(location)))
+}
+
+#[derive(Debug, Error)]
+pub enum LocationsApiError {
+	#[error("Invalid location query: {0}")]
+	InvalidQuery(ValidationErrors),
+
+	#[error("Failed to autocomplete locations")]
+	Autocomplete(),
+
+	#[error("Failed to resolve location")]
+	Resolve(),
+
+	#[error("No known city matches: {0}")]
+	NotFound(String),
+}
+
+impl IntoResponse for LocationsApiError {
+	fn into_response(self) -> Response {
+		let status_code = match self {
+			Self::InvalidQuery(_) => StatusCode::BAD_REQUEST,
+			Self::NotFound(_) => StatusCode::NOT_FOUND,
+			Self::Autocomplete() | Self::Resolve() => StatusCode::INTERNAL_SERVER_ERROR,
+		};
+
+		log::error!("{self:?}");
+		ApiErrorResponse::new(status_code, self.to_string()).into_response()
+	}
+}
@@ -3,13 +3,28 @@ use std::net::SocketAddr;
 
 mod app;
 mod database;
+mod domains;
+mod i18n;
 mod services;
 mod settings;
 mod validation;
 
+mod certifications;
+mod companies;
+mod completeness;
+mod drafts;
+mod education;
 mod experience;
+mod history;
+mod languages;
+mod locations;
+mod resume;
+mod search;
+mod sections;
 mod skills;
 mod users;
+mod verifications;
+mod work_emails;
 
 use settings::SETTINGS;
 
@@ -0,0 +1,57 @@
+use serde::Deserialize;
+
+use crate::{
+	education::repository::Education, experience::repository::Experience, sections::repository::CustomSection,
+	skills::repository::UserSkill, users::repository::User,
+};
+
+use super::locale::Locale;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CvQuery {
+	#[serde(default)]
+	pub template: CvTemplate,
+	#[serde(default)]
+	pub page_size: PageSize,
+	/// Label language, also preferred for the profile text; negotiated from the request when omitted
+	pub locale: Option<Locale>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CvTemplate {
+	#[default]
+	Classic,
+	Modern,
+	Compact,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PageSize {
+	#[default]
+	A4,
+	Letter,
+}
+
+impl PageSize {
+	/// Page width and height in millimeters
+	pub const fn dimensions(self) -> (f32, f32) {
+		match self {
+			Self::A4 => (210.0, 297.0),
+			Self::Letter => (215.9, 279.4),
+		}
+	}
+}
+
+/// Everything a resume is rendered from, loaded for a single user
+pub struct Resume {
+	/// Locale the profile text was loaded in
+	pub locale: String,
+	pub user: User,
+	pub experience: Vec<Experience>,
+	pub education: Vec<Education>,
+	pub skills: Vec<UserSkill>,
+	pub custom_sections: Vec<CustomSection>,
+}
@@ -0,0 +1,335 @@
+use std::collections::HashSet;
+
+use chrono::Datelike;
+use serde::{Deserialize, Serialize};
+use validator::ValidationErrors;
+
+use crate::{
+	education::{dtos::EducationDto, repository::Education},
+	experience::{dtos::ExperienceDto, repository::Experience, year_month::YearMonth},
+	skills::repository::{Skill, SkillAlias},
+};
+
+/// Minimum Jaro-Winkler similarity for an imported skill name to count as a catalog skill
+const SKILL_MATCH_THRESHOLD: f64 = 0.9;
+
+/// Profile data extracted from an external document, before it is compared with what the user already has
+#[derive(Default)]
+pub struct ImportedProfile {
+	pub experience: Vec<ExperienceDto>,
+	pub invalid_experience: Vec<InvalidExperience>,
+	pub education: Vec<EducationDto>,
+	pub skill_names: Vec<String>,
+	/// Anything found in the document that the import can't use
+	pub notes: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InvalidExperience {
+	pub source: String,
+	pub entry: ExperienceDto,
+	pub errors: ValidationErrors,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportMode {
+	/// Keep existing entries and only add what is new
+	#[default]
+	Merge,
+	/// Drop existing experience, education and skills in favour of the imported ones
+	Replace,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportQuery {
+	#[serde(default)]
+	pub mode: ImportMode,
+	#[serde(default)]
+	pub dry_run: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportPreview {
+	pub mode: ImportMode,
+	pub dry_run: bool,
+	pub experience: ExperienceChanges,
+	pub education: EducationChanges,
+	pub skills: SkillChanges,
+	pub notes: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExperienceChanges {
+	pub added: Vec<ExperienceDto>,
+	/// Imported entries that match an existing one and will be skipped
+	pub duplicates: Vec<ExperienceDto>,
+	/// Existing entries no imported entry matches, deleted in replace mode
+	pub removed: Vec<Experience>,
+	/// Imported entries that failed validation and will be skipped
+	pub invalid: Vec<InvalidExperience>,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EducationChanges {
+	pub added: Vec<EducationDto>,
+	/// Imported entries that match an existing one and will be skipped
+	pub duplicates: Vec<EducationDto>,
+	pub removed: Vec<Education>,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillChanges {
+	pub added: Vec<Skill>,
+	pub unchanged: Vec<Skill>,
+	pub removed: Vec<Skill>,
+	/// Imported names that only resemble a catalog skill, worth a second look before committing
+	pub fuzzy_matched: Vec<FuzzySkillMatch>,
+	/// Imported names that are not in the skills catalog
+	pub unmatched: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FuzzySkillMatch {
+	pub name: String,
+	pub skill: Skill,
+}
+
+fn normalize_skill_name(name: &str) -> String {
+	name.chars()
+		.filter(|char| char.is_alphanumeric() || matches!(char, '+' | '#'))
+		.flat_map(char::to_lowercase)
+		.collect()
+}
+
+/// Finds the catalog skill for an imported name or one of its aliases, tolerating case, spacing and
+/// punctuation differences exactly and small spelling differences fuzzily. Returns whether the match was fuzzy.
+fn find_skill<'a>(catalog: &'a [Skill], aliases: &[SkillAlias], name: &str) -> Option<(&'a Skill, bool)> {
+	let normalized = normalize_skill_name(name);
+	if normalized.is_empty() {
+		return None;
+	}
+
+	if let Some(skill) = catalog
+		.iter()
+		.find(|skill| normalize_skill_name(&skill.name) == normalized)
+	{
+		return Some((skill, false));
+	}
+
+	if let Some(skill) = aliases
+		.iter()
+		.find(|alias| normalize_skill_name(&alias.name) == normalized)
+		.and_then(|alias| catalog.iter().find(|skill| skill.id == alias.skill_id))
+	{
+		return Some((skill, false));
+	}
+
+	catalog
+		.iter()
+		.map(|skill| {
+			(
+				skill,
+				strsim::jaro_winkler(&normalize_skill_name(&skill.name), &normalized),
+			)
+		})
+		.filter(|(_, similarity)| *similarity >= SKILL_MATCH_THRESHOLD)
+		.max_by(|(_, left), (_, right)| left.total_cmp(right))
+		.map(|(skill, _)| (skill, true))
+}
+
+/// Entries are the same position when company, occupation and start month match
+fn experience_key(company_name: &str, occupation: &str, start_date: &YearMonth) -> (String, String, i32, u32) {
+	(
+		company_name.trim().to_lowercase(),
+		occupation.trim().to_lowercase(),
+		start_date.year(),
+		start_date.month(),
+	)
+}
+
+/// Entries are the same studies when institution, degree and start year match
+fn education_key(institution: &str, degree: Option<&str>, start_date: &impl Datelike) -> (String, String, i32) {
+	(
+		institution.trim().to_lowercase(),
+		degree.unwrap_or_default().trim().to_lowercase(),
+		start_date.year(),
+	)
+}
+
+impl ExperienceChanges {
+	/// In replace mode, existing entries that match an imported one are kept as they are, so their
+	/// verifications, translations and skill links survive re-importing the same resume
+	fn new(
+		imported: Vec<ExperienceDto>,
+		invalid: Vec<InvalidExperience>,
+		existing: Vec<Experience>,
+		replace: bool,
+	) -> Self {
+		let mut known_experience: HashSet<_> = existing
+			.iter()
+			.map(|experience| experience_key(&experience.company_name, &experience.occupation, &experience.start_date))
+			.collect();
+
+		let mut experience = Self::default();
+		let mut imported_keys = HashSet::new();
+		for experience_dto in imported {
+			let key = experience_key(
+				&experience_dto.company_name,
+				&experience_dto.occupation,
+				&experience_dto.start_date,
+			);
+			imported_keys.insert(key.clone());
+
+			if known_experience.insert(key) {
+				experience.added.push(experience_dto);
+			} else {
+				experience.duplicates.push(experience_dto);
+			}
+		}
+		if replace {
+			experience.removed = existing
+				.into_iter()
+				.filter(|experience| {
+					!imported_keys.contains(&experience_key(
+						&experience.company_name,
+						&experience.occupation,
+						&experience.start_date,
+					))
+				})
+				.collect();
+		}
+		experience.invalid = invalid;
+
+		experience
+	}
+}
+
+impl EducationChanges {
+	fn new(imported: Vec<EducationDto>, existing: Vec<Education>, replace: bool) -> Self {
+		let mut known_education: HashSet<_> = existing
+			.iter()
+			.map(|education| {
+				education_key(
+					&education.institution,
+					education.degree.as_deref(),
+					&education.start_date,
+				)
+			})
+			.collect();
+
+		let mut education = Self::default();
+		let mut imported_keys = HashSet::new();
+		for education_dto in imported {
+			let key = education_key(
+				&education_dto.institution,
+				education_dto.degree.as_deref(),
+				&education_dto.start_date,
+			);
+			imported_keys.insert(key.clone());
+
+			if known_education.insert(key) {
+				education.added.push(education_dto);
+			} else {
+				education.duplicates.push(education_dto);
+			}
+		}
+		if replace {
+			education.removed = existing
+				.into_iter()
+				.filter(|education| {
+					!imported_keys.contains(&education_key(
+						&education.institution,
+						education.degree.as_deref(),
+						&education.start_date,
+					))
+				})
+				.collect();
+		}
+
+		education
+	}
+}
+
+impl SkillChanges {
+	fn new(names: Vec<String>, existing: Vec<Skill>, catalog: &[Skill], aliases: &[SkillAlias], replace: bool) -> Self {
+		let mut skills = Self::default();
+		let mut matched_ids = HashSet::new();
+		for name in names {
+			match find_skill(catalog, aliases, &name) {
+				Some((skill, is_fuzzy)) if matched_ids.insert(skill.id) => {
+					if existing.iter().any(|existing| existing.id == skill.id) {
+						skills.unchanged.push(skill.clone());
+					} else {
+						skills.added.push(skill.clone());
+					}
+
+					if is_fuzzy {
+						skills.fuzzy_matched.push(FuzzySkillMatch {
+							name,
+							skill: skill.clone(),
+						});
+					}
+				}
+				None if !skills
+					.unmatched
+					.iter()
+					.any(|unmatched| normalize_skill_name(unmatched) == normalize_skill_name(&name)) =>
+				{
+					skills.unmatched.push(name);
+				}
+				Some(_) | None => {}
+			}
+		}
+		for skill in existing {
+			if !matched_ids.contains(&skill.id) {
+				if replace {
+					skills.removed.push(skill);
+				} else {
+					skills.unchanged.push(skill);
+				}
+			}
+		}
+
+		skills
+	}
+}
+
+impl ImportPreview {
+	pub fn new(
+		imported: ImportedProfile,
+		query: &ImportQuery,
+		existing_experience: Vec<Experience>,
+		existing_education: Vec<Education>,
+		existing_skills: Vec<Skill>,
+		catalog: &[Skill],
+		aliases: &[SkillAlias],
+	) -> Self {
+		let replace = query.mode == ImportMode::Replace;
+
+		let experience = ExperienceChanges::new(
+			imported.experience,
+			imported.invalid_experience,
+			existing_experience,
+			replace,
+		);
+		let education = EducationChanges::new(imported.education, existing_education, replace);
+		let skills = SkillChanges::new(imported.skill_names, existing_skills, catalog, aliases, replace);
+
+		Self {
+			mode: query.mode,
+			dry_run: query.dry_run,
+			experience,
+			education,
+			skills,
+			notes: imported.notes,
+		}
+	}
+}
@@ -0,0 +1,381 @@
+//! Mapping between profile data and the [JSON Resume](https://jsonresume.org/schema) interchange format
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError, ValidationErrors, ValidationErrorsKind};
+
+use crate::{
+	education::dtos::EducationDto,
+	experience::{dtos::ExperienceDto, year_month::YearMonth},
+	sections::dtos::CustomSectionDto,
+};
+
+use super::{dtos::Resume, import::ImportedProfile};
+
+const SCHEMA_URL: &str = "https://raw.githubusercontent.com/jsonresume/resume-schema/v1.0.0/schema.json";
+
+#[derive(Debug, Default, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonResume {
+	#[serde(rename = "$schema", skip_serializing_if = "Option::is_none")]
+	pub schema: Option<String>,
+	#[serde(default)]
+	#[validate(nested)]
+	pub basics: Basics,
+	#[serde(default)]
+	#[validate(nested)]
+	pub work: Vec<Work>,
+	#[serde(default)]
+	#[validate(nested)]
+	pub education: Vec<EducationEntry>,
+	#[serde(default)]
+	#[validate(nested)]
+	pub skills: Vec<SkillEntry>,
+	/// Not part of the schema, which allows extra properties; carries user defined sections as-is
+	#[serde(default, skip_serializing_if = "Vec::is_empty", skip_deserializing)]
+	pub custom_sections: Vec<CustomSectionDto>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub meta: Option<Meta>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct Basics {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub name: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub label: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	#[validate(email(message = "Must be a valid email address"))]
+	pub email: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub url: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub summary: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub location: Option<Location>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Location {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub city: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub region: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub country_code: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct Work {
+	#[validate(required(message = "Company name is required"))]
+	pub name: Option<String>,
+	#[validate(required(message = "Position is required"))]
+	pub position: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub location: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub url: Option<String>,
+	#[validate(required(message = "Start date is required"), custom(function = "validate_date"))]
+	pub start_date: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	#[validate(custom(function = "validate_date"))]
+	pub end_date: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub summary: Option<String>,
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub highlights: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct EducationEntry {
+	#[validate(required(message = "Institution is required"))]
+	pub institution: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub url: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub area: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub study_type: Option<String>,
+	#[validate(required(message = "Start date is required"), custom(function = "validate_date"))]
+	pub start_date: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	#[validate(custom(function = "validate_date"))]
+	pub end_date: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub score: Option<String>,
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub courses: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillEntry {
+	#[validate(required(message = "Skill name is required"), length(min = 1, max = 255))]
+	pub name: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub level: Option<String>,
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub keywords: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Meta {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub canonical: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub version: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub last_modified: Option<String>,
+}
+
+impl JsonResume {
+	pub fn from_resume(resume: &Resume) -> Self {
+		Self {
+			schema: Some(SCHEMA_URL.to_string()),
+			basics: Basics {
+				name: Some(resume.user.username.clone()),
+				label: resume.user.headline.clone(),
+				email: Some(resume.user.email.clone()),
+				summary: resume.user.bio.clone(),
+				..Basics::default()
+			},
+			work: resume
+				.experience
+				.iter()
+				.map(|experience| Work {
+					name: Some(experience.company_name.clone()),
+					position: Some(experience.occupation.clone()),
+					location: Some(experience.location_name.clone()),
+					url: None,
+					start_date: Some(experience.start_date.to_string()),
+					end_date: experience.end_date.as_ref().map(ToString::to_string),
+					summary: Some(experience.description.clone()),
+					highlights: Vec::new(),
+				})
+				.collect(),
+			education: resume
+				.education
+				.iter()
+				.map(|education| EducationEntry {
+					institution: Some(education.institution.clone()),
+					url: None,
+					area: education.field_of_study.clone(),
+					study_type: education.degree.clone(),
+					start_date: Some(education.start_date.format("%Y-%m-%d").to_string()),
+					end_date: education.end_date.map(|date| date.format("%Y-%m-%d").to_string()),
+					score: education.grade.clone(),
+					courses: Vec::new(),
+				})
+				.collect(),
+			skills: resume
+				.skills
+				.iter()
+				.map(|skill| SkillEntry {
+					name: Some(skill.name.clone()),
+					level: skill.proficiency.map(|proficiency| proficiency.label().to_owned()),
+					..SkillEntry::default()
+				})
+				.collect(),
+			custom_sections: resume
+				.custom_sections
+				.iter()
+				.map(|section| CustomSectionDto {
+					title: section.title.clone(),
+					blocks: section.blocks.to_vec(),
+				})
+				.collect(),
+			meta: Some(Meta {
+				version: Some(String::from("v1.0.0")),
+				last_modified: Some(Utc::now().to_rfc3339()),
+				..Meta::default()
+			}),
+		}
+	}
+
+	/// Converts a validated document into profile data, checking every work and education entry
+	/// against the same rules `ExperienceDto` and `EducationDto` enforce on the regular endpoints
+	pub fn into_import(self) -> Result<ImportedProfile, ValidationErrors> {
+		let fallback_location = self.basics.location.as_ref().and_then(Location::display_name);
+
+		let mut experience = Vec::with_capacity(self.work.len());
+		let mut work_errors = BTreeMap::new();
+
+		for (index, work) in self.work.into_iter().enumerate() {
+			let experience_dto = work.into_experience_dto(fallback_location.as_deref());
+
+			match experience_dto.validate() {
+				Ok(()) => experience.push(experience_dto),
+				Err(errors) => {
+					work_errors.insert(index, Box::new(errors));
+				}
+			}
+		}
+
+		let mut education = Vec::with_capacity(self.education.len());
+		let mut education_errors = BTreeMap::new();
+
+		for (index, entry) in self.education.into_iter().enumerate() {
+			let education_dto = entry.into_education_dto();
+
+			match education_dto.validate() {
+				Ok(()) => education.push(education_dto),
+				Err(errors) => {
+					education_errors.insert(index, Box::new(errors));
+				}
+			}
+		}
+
+		if !work_errors.is_empty() || !education_errors.is_empty() {
+			let mut errors = ValidationErrors::new();
+			for (field, field_errors) in [("work", work_errors), ("education", education_errors)] {
+				if !field_errors.is_empty() {
+					errors
+						.errors_mut()
+						.insert(field.into(), ValidationErrorsKind::List(field_errors));
+				}
+			}
+			return Err(errors);
+		}
+
+		// Keywords usually hold the concrete technologies ("Rust", "Axum") behind a broad skill name
+		let skill_names = self
+			.skills
+			.into_iter()
+			.flat_map(|skill| skill.name.into_iter().chain(skill.keywords))
+			.collect();
+
+		Ok(ImportedProfile {
+			experience,
+			education,
+			skill_names,
+			..ImportedProfile::default()
+		})
+	}
+}
+
+impl Location {
+	fn display_name(&self) -> Option<String> {
+		let parts: Vec<&str> = [&self.city, &self.region, &self.country_code]
+			.into_iter()
+			.filter_map(|part| part.as_deref())
+			.filter(|part| !part.trim().is_empty())
+			.collect();
+
+		(!parts.is_empty()).then(|| parts.join(", "))
+	}
+}
+
+impl Work {
+	fn into_experience_dto(self, fallback_location: Option<&str>) -> ExperienceDto {
+		let location_name = self
+			.location
+			.filter(|location| !location.trim().is_empty())
+			.or_else(|| fallback_location.map(str::to_string))
+			.unwrap_or_default();
+
+		let location_type = if location_name.to_lowercase().contains("remote") {
+			"remote"
+		} else {
+			"on-site"
+		};
+
+		let position = self.position.unwrap_or_default();
+		let mut description = self.summary.unwrap_or_default();
+		for highlight in self.highlights {
+			if !description.is_empty() {
+				description.push('\n');
+			}
+			description.push_str("- ");
+			description.push_str(&highlight);
+		}
+		if description.trim().is_empty() {
+			description.clone_from(&position);
+		}
+
+		let end_date = self.end_date.as_deref().and_then(parse_year_month);
+
+		ExperienceDto {
+			company_id: None,
+			skill_ids: Vec::new(),
+			company_name: self.name.unwrap_or_default(),
+			occupation: position,
+			location_name,
+			location_id: None,
+			location_type: location_type.to_string(),
+			employment_type: String::from("full-time"),
+			start_date: self
+				.start_date
+				.as_deref()
+				.and_then(parse_year_month)
+				.unwrap_or_default(),
+			is_current: end_date.is_none(),
+			end_date,
+			description,
+		}
+	}
+}
+
+impl EducationEntry {
+	fn into_education_dto(self) -> EducationDto {
+		let non_empty = |value: Option<String>| value.filter(|value| !value.trim().is_empty());
+		let courses = self.courses.join(", ");
+
+		EducationDto {
+			institution: self.institution.unwrap_or_default(),
+			degree: non_empty(self.study_type),
+			field_of_study: non_empty(self.area),
+			start_date: self
+				.start_date
+				.as_deref()
+				.and_then(parse_date)
+				.map(|date| date.date_naive())
+				.unwrap_or_default(),
+			end_date: self
+				.end_date
+				.as_deref()
+				.and_then(parse_date)
+				.map(|date| date.date_naive()),
+			grade: non_empty(self.score),
+			activities: None,
+			description: non_empty(Some(courses)),
+		}
+	}
+}
+
+/// Keeps the day only when the date has one, a bare year becomes its January
+fn parse_year_month(date: &str) -> Option<YearMonth> {
+	date.parse()
+		.ok()
+		.or_else(|| parse_date(date).map(|date| YearMonth::from_month(&date)))
+}
+
+/// Parses the ISO 8601 subsets JSON Resume allows: `YYYY`, `YYYY-MM` and `YYYY-MM-DD`
+fn parse_date(date: &str) -> Option<DateTime<Utc>> {
+	let full_date = match date.len() {
+		4 => format!("{date}-01-01"),
+		7 => format!("{date}-01"),
+		_ => date.to_string(),
+	};
+
+	NaiveDate::parse_from_str(&full_date, "%Y-%m-%d")
+		.ok()
+		.and_then(|date| date.and_hms_opt(0, 0, 0))
+		.map(|date| date.and_utc())
+}
+
+fn validate_date(date: &str) -> Result<(), ValidationError> {
+	if parse_date(date).is_some() {
+		Ok(())
+	} else {
+		Err(ValidationError::new(
+			"Wrong date format, expected YYYY, YYYY-MM or YYYY-MM-DD",
+		))
+	}
+}
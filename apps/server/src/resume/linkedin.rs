@@ -0,0 +1,244 @@
+//! Parser for the archive produced by LinkedIn's "Get a copy of your data" export
+
+use std::{
+	collections::HashMap,
+	io::{Cursor, Read},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use validator::{Validate, ValidationError, ValidationErrors};
+
+use crate::{
+	education::dtos::EducationDto,
+	experience::{dtos::ExperienceDto, year_month::YearMonth},
+};
+
+use super::import::{ImportedProfile, InvalidExperience};
+
+/// Upper bound for a single extracted CSV file, so a crafted archive can't exhaust memory
+const MAX_FILE_SIZE: u64 = 10 * 1024 * 1024;
+
+type Row = HashMap<String, String>;
+
+pub fn parse(archive: &[u8]) -> Result<ImportedProfile> {
+	let mut archive = zip::ZipArchive::new(Cursor::new(archive)).context("Not a ZIP archive")?;
+
+	let profile = read_csv(&mut archive, "Profile.csv")?;
+	let positions = read_csv(&mut archive, "Positions.csv")?;
+	let skills = read_csv(&mut archive, "Skills.csv")?;
+	let education = read_csv(&mut archive, "Education.csv")?;
+
+	if profile.is_none() && positions.is_none() && skills.is_none() && education.is_none() {
+		bail!("Archive contains none of Profile.csv, Positions.csv, Skills.csv or Education.csv");
+	}
+
+	let fallback_location = profile
+		.as_ref()
+		.and_then(|rows| rows.first())
+		.and_then(|row| field(row, "Geo Location").or_else(|| field(row, "Location")));
+
+	let mut imported = ImportedProfile::default();
+
+	for (index, row) in positions.unwrap_or_default().iter().enumerate() {
+		let experience_dto = position_to_experience_dto(row, fallback_location);
+
+		match validate_position(row, &experience_dto) {
+			Ok(()) => imported.experience.push(experience_dto),
+			Err(errors) => imported.invalid_experience.push(InvalidExperience {
+				source: format!("Positions.csv row {}", index + 1),
+				entry: experience_dto,
+				errors,
+			}),
+		}
+	}
+
+	imported.skill_names = skills
+		.unwrap_or_default()
+		.iter()
+		.filter_map(|row| field(row, "Name"))
+		.map(str::to_string)
+		.collect();
+
+	for (index, row) in education.unwrap_or_default().iter().enumerate() {
+		match row_to_education_dto(row) {
+			Some(education_dto) if education_dto.validate().is_ok() => imported.education.push(education_dto),
+			_ => imported.notes.push(format!(
+				"Education.csv row {}: skipped, school name or start date missing",
+				index + 1
+			)),
+		}
+	}
+
+	Ok(imported)
+}
+
+fn read_csv(archive: &mut zip::ZipArchive<Cursor<&[u8]>>, file_name: &str) -> Result<Option<Vec<Row>>> {
+	// The export nests files in a folder whose name varies, so match on the file name alone
+	let Some(path) = archive
+		.file_names()
+		.find(|path| {
+			path.rsplit('/')
+				.next()
+				.is_some_and(|name| name.eq_ignore_ascii_case(file_name))
+		})
+		.map(str::to_string)
+	else {
+		return Ok(None);
+	};
+
+	let file = archive
+		.by_name(&path)
+		.with_context(|| format!("Failed to open {file_name}"))?;
+
+	if file.size() > MAX_FILE_SIZE {
+		bail!("{file_name} is larger than {} MB", MAX_FILE_SIZE / 1024 / 1024);
+	}
+
+	// The declared size can't be trusted in a crafted archive, so reading one byte past the limit tells
+	let mut content = Vec::new();
+	file.take(MAX_FILE_SIZE + 1)
+		.read_to_end(&mut content)
+		.with_context(|| format!("Failed to read {file_name}"))?;
+	if content.len() as u64 > MAX_FILE_SIZE {
+		bail!("{file_name} is larger than {} MB", MAX_FILE_SIZE / 1024 / 1024);
+	}
+	let content = String::from_utf8(content).with_context(|| format!("{file_name} is not valid UTF-8"))?;
+
+	let mut reader = csv::ReaderBuilder::new()
+		.flexible(true)
+		.from_reader(content.trim_start_matches('\u{feff}').as_bytes());
+
+	let headers: Vec<String> = reader
+		.headers()
+		.map_err(|error| anyhow!(error).context(format!("Failed to read {file_name} header")))?
+		.iter()
+		.map(|header| header.trim().to_string())
+		.collect();
+
+	let rows = reader
+		.records()
+		.map(|record| {
+			record
+				.map(|record| headers.iter().cloned().zip(record.iter().map(str::to_string)).collect())
+				.map_err(|error| anyhow!(error).context(format!("Failed to read {file_name}")))
+		})
+		.collect::<Result<Vec<Row>>>()?;
+
+	Ok(Some(rows))
+}
+
+fn field<'a>(row: &'a Row, name: &str) -> Option<&'a str> {
+	row.get(name)
+		.map(|value| value.trim())
+		.filter(|value| !value.is_empty())
+}
+
+fn position_to_experience_dto(row: &Row, fallback_location: Option<&str>) -> ExperienceDto {
+	let title = field(row, "Title").unwrap_or_default();
+	let location_name = field(row, "Location").or(fallback_location).unwrap_or_default();
+
+	// LinkedIn only records the month of a position
+	let month = |name: &str| {
+		field(row, name)
+			.and_then(parse_date)
+			.map(|date| YearMonth::from_month(&date))
+	};
+	let end_date = month("Finished On");
+
+	ExperienceDto {
+		company_id: None,
+		skill_ids: Vec::new(),
+		company_name: field(row, "Company Name").unwrap_or_default().to_string(),
+		occupation: title.to_string(),
+		location_name: location_name.to_string(),
+		location_id: None,
+		location_type: location_type(field(row, "Location Type"), location_name).to_string(),
+		employment_type: employment_type(field(row, "Employment Type"), title).to_string(),
+		start_date: month("Started On").unwrap_or_default(),
+		is_current: end_date.is_none(),
+		end_date,
+		description: field(row, "Description").unwrap_or(title).to_string(),
+	}
+}
+
+fn row_to_education_dto(row: &Row) -> Option<EducationDto> {
+	let optional = |name: &str| field(row, name).map(str::to_string);
+
+	Some(EducationDto {
+		institution: field(row, "School Name")?.to_string(),
+		degree: optional("Degree Name"),
+		field_of_study: None,
+		start_date: field(row, "Start Date").and_then(parse_date)?.date_naive(),
+		end_date: field(row, "End Date")
+			.and_then(parse_date)
+			.map(|date| date.date_naive()),
+		grade: None,
+		activities: optional("Activities"),
+		description: optional("Notes"),
+	})
+}
+
+fn validate_position(row: &Row, experience_dto: &ExperienceDto) -> Result<(), ValidationErrors> {
+	let mut errors = experience_dto.validate().err().unwrap_or_default();
+
+	if field(row, "Started On").and_then(parse_date).is_none() {
+		errors.add("start_date", ValidationError::new("Start date is required"));
+	}
+
+	if errors.is_empty() {
+		Ok(())
+	} else {
+		Err(errors)
+	}
+}
+
+/// Maps LinkedIn's location types ("On-site", "Hybrid", "Remote") onto ours,
+/// falling back to hints in the location text when the column is missing
+fn location_type(explicit: Option<&str>, location_name: &str) -> &'static str {
+	let text = explicit.unwrap_or(location_name).to_lowercase();
+
+	if text.contains("remote") {
+		"remote"
+	} else if text.contains("hybrid") {
+		"hybrid"
+	} else {
+		"on-site"
+	}
+}
+
+/// Maps LinkedIn's employment types ("Self-employed", "Apprenticeship", "Seasonal", ...) onto ours,
+/// falling back to hints in the title when the column is missing
+fn employment_type(explicit: Option<&str>, title: &str) -> &'static str {
+	let text = explicit
+		.unwrap_or(title)
+		.to_lowercase()
+		.replace("self employed", "self-employed")
+		.replace("part time", "part-time");
+
+	let mentions = |hints: &[&str]| {
+		text.split(|char: char| !char.is_alphanumeric() && char != '-')
+			.any(|word| hints.contains(&word))
+	};
+
+	if mentions(&["intern", "internship", "apprentice", "apprenticeship", "trainee"]) {
+		"internship"
+	} else if mentions(&["freelance", "freelancer", "self-employed"]) {
+		"freelance"
+	} else if mentions(&["part-time"]) {
+		"part-time"
+	} else if mentions(&["contract", "contractor", "temporary", "seasonal"]) {
+		"contract"
+	} else {
+		"full-time"
+	}
+}
+
+/// LinkedIn writes dates as `Jan 2020`, or just `2020` when the month is unknown
+fn parse_date(date: &str) -> Option<DateTime<Utc>> {
+	NaiveDate::parse_from_str(&format!("1 {date}"), "%d %b %Y")
+		.or_else(|_| NaiveDate::parse_from_str(&format!("{date}-01-01"), "%Y-%m-%d"))
+		.ok()
+		.and_then(|date| date.and_hms_opt(0, 0, 0))
+		.map(|date| date.and_utc())
+}
@@ -0,0 +1,153 @@
+use chrono::Datelike;
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+	#[default]
+	En,
+	De,
+	Fr,
+	Es,
+}
+
+pub struct Labels {
+	pub experience: &'static str,
+	pub skills: &'static str,
+	pub education: &'static str,
+	pub present: &'static str,
+	pub page: &'static str,
+	pub of: &'static str,
+	pub months: [&'static str; 12],
+}
+
+const EN: Labels = Labels {
+	experience: "Experience",
+	skills: "Skills",
+	education: "Education",
+	present: "Present",
+	page: "Page",
+	of: "of",
+	months: [
+		"Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+	],
+};
+
+const DE: Labels = Labels {
+	experience: "Berufserfahrung",
+	skills: "Kenntnisse",
+	education: "Ausbildung",
+	present: "heute",
+	page: "Seite",
+	of: "von",
+	months: [
+		"Jan.", "Feb.", "März", "Apr.", "Mai", "Juni", "Juli", "Aug.", "Sept.", "Okt.", "Nov.", "Dez.",
+	],
+};
+
+const FR: Labels = Labels {
+	experience: "Expérience",
+	skills: "Compétences",
+	education: "Formation",
+	present: "aujourd'hui",
+	page: "Page",
+	of: "sur",
+	months: [
+		"janv.", "févr.", "mars", "avr.", "mai", "juin", "juil.", "août", "sept.", "oct.", "nov.", "déc.",
+	],
+};
+
+const ES: Labels = Labels {
+	experience: "Experiencia",
+	skills: "Habilidades",
+	education: "Educación",
+	present: "actualidad",
+	page: "Página",
+	of: "de",
+	months: [
+		"ene.", "feb.", "mar.", "abr.", "may.", "jun.", "jul.", "ago.", "sept.", "oct.", "nov.", "dic.",
+	],
+};
+
+impl Locale {
+	pub const fn labels(self) -> &'static Labels {
+		match self {
+			Self::En => &EN,
+			Self::De => &DE,
+			Self::Fr => &FR,
+			Self::Es => &ES,
+		}
+	}
+
+	pub const fn code(self) -> &'static str {
+		match self {
+			Self::En => "en",
+			Self::De => "de",
+			Self::Fr => "fr",
+			Self::Es => "es",
+		}
+	}
+
+	/// Label language for a content locale, matching on the language alone (`de-AT` gets German labels)
+	pub fn from_tag(tag: &str) -> Option<Self> {
+		match tag.split('-').next()? {
+			"en" => Some(Self::En),
+			"de" => Some(Self::De),
+			"fr" => Some(Self::Fr),
+			"es" => Some(Self::Es),
+			_ => None,
+		}
+	}
+
+	pub fn format_month(self, date: &impl Datelike) -> String {
+		let month = self.labels().months[date.month0() as usize];
+		format!("{month} {}", date.year())
+	}
+
+	pub fn format_period<D: Datelike>(self, start_date: &D, end_date: Option<&D>) -> String {
+		let end = end_date.map_or_else(|| self.labels().present.to_string(), |date| self.format_month(date));
+		format!("{} – {end}", self.format_month(start_date))
+	}
+
+	pub fn location_type(self, location_type: &str) -> &str {
+		match (self, location_type) {
+			(Self::En, "on-site") => "On-site",
+			(Self::En | Self::De, "remote") => "Remote",
+			(Self::En | Self::De, "hybrid") => "Hybrid",
+			(Self::De, "on-site") => "Vor Ort",
+			(Self::Fr, "on-site") => "Sur site",
+			(Self::Fr, "remote") => "À distance",
+			(Self::Fr, "hybrid") => "Hybride",
+			(Self::Es, "on-site") => "Presencial",
+			(Self::Es, "remote") => "En remoto",
+			(Self::Es, "hybrid") => "Híbrido",
+			_ => location_type,
+		}
+	}
+
+	pub fn employment_type(self, employment_type: &str) -> &str {
+		match (self, employment_type) {
+			(Self::En, "full-time") => "Full-time",
+			(Self::En, "part-time") => "Part-time",
+			(Self::En, "contract") => "Contract",
+			(Self::En, "freelance") => "Freelance",
+			(Self::En, "internship") => "Internship",
+			(Self::De, "full-time") => "Vollzeit",
+			(Self::De, "part-time") => "Teilzeit",
+			(Self::De, "contract") => "Befristet",
+			(Self::De, "freelance") => "Freiberuflich",
+			(Self::De, "internship") => "Praktikum",
+			(Self::Fr, "full-time") => "Temps plein",
+			(Self::Fr, "part-time") => "Temps partiel",
+			(Self::Fr, "contract") => "Contrat",
+			(Self::Fr, "freelance") => "Indépendant",
+			(Self::Fr, "internship") => "Stage",
+			(Self::Es, "full-time") => "Jornada completa",
+			(Self::Es, "part-time") => "Media jornada",
+			(Self::Es, "contract") => "Contrato",
+			(Self::Es, "freelance") => "Autónomo",
+			(Self::Es, "internship") => "Prácticas",
+			_ => employment_type,
+		}
+	}
+}
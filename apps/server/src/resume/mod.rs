@@ -0,0 +1,9 @@
+pub mod routes;
+
+mod dtos;
+mod import;
+mod json_resume;
+mod linkedin;
+mod locale;
+mod pdf;
+mod repository;
@@ -0,0 +1,453 @@
+use anyhow::{anyhow, Result};
+use printpdf::{Color, IndirectFontRef, Line, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference, Point, Rgb};
+
+use crate::sections::{dtos::Block, repository::CustomSection};
+
+use super::{
+	dtos::{CvTemplate, PageSize, Resume},
+	locale::Locale,
+};
+
+/// Millimeters per typographic point
+const PT_TO_MM: f32 = 0.352_778;
+
+// Embedded rather than the builtin PDF fonts, which only cover Western European text
+const SANS: &[u8] = include_bytes!("../../data/fonts/DejaVuSans.ttf");
+const SANS_BOLD: &[u8] = include_bytes!("../../data/fonts/DejaVuSans-Bold.ttf");
+const SANS_OBLIQUE: &[u8] = include_bytes!("../../data/fonts/DejaVuSans-Oblique.ttf");
+const SERIF: &[u8] = include_bytes!("../../data/fonts/DejaVuSerif.ttf");
+const SERIF_BOLD: &[u8] = include_bytes!("../../data/fonts/DejaVuSerif-Bold.ttf");
+const SERIF_ITALIC: &[u8] = include_bytes!("../../data/fonts/DejaVuSerif-Italic.ttf");
+
+struct Style {
+	/// TrueType font files
+	regular: &'static [u8],
+	bold: &'static [u8],
+	italic: &'static [u8],
+	/// Average glyph width relative to the font size, used for line wrapping
+	glyph_width: f32,
+	name_size: f32,
+	heading_size: f32,
+	body_size: f32,
+	small_size: f32,
+	line_height: f32,
+	section_gap: f32,
+	entry_gap: f32,
+	margin: f32,
+	accent: (f32, f32, f32),
+	heading_rule: bool,
+	uppercase_headings: bool,
+}
+
+impl CvTemplate {
+	const fn style(self) -> Style {
+		match self {
+			Self::Classic => Style {
+				regular: SERIF,
+				bold: SERIF_BOLD,
+				italic: SERIF_ITALIC,
+				glyph_width: 0.52,
+				name_size: 24.0,
+				heading_size: 14.0,
+				body_size: 11.0,
+				small_size: 10.0,
+				line_height: 1.3,
+				section_gap: 7.0,
+				entry_gap: 4.0,
+				margin: 22.0,
+				accent: (0.0, 0.0, 0.0),
+				heading_rule: true,
+				uppercase_headings: false,
+			},
+			Self::Modern => Style {
+				regular: SANS,
+				bold: SANS_BOLD,
+				italic: SANS_OBLIQUE,
+				glyph_width: 0.56,
+				name_size: 26.0,
+				heading_size: 12.0,
+				body_size: 10.0,
+				small_size: 9.0,
+				line_height: 1.35,
+				section_gap: 8.0,
+				entry_gap: 5.0,
+				margin: 20.0,
+				accent: (0.13, 0.39, 0.71),
+				heading_rule: false,
+				uppercase_headings: true,
+			},
+			Self::Compact => Style {
+				regular: SANS,
+				bold: SANS_BOLD,
+				italic: SANS_OBLIQUE,
+				glyph_width: 0.56,
+				name_size: 18.0,
+				heading_size: 11.0,
+				body_size: 9.0,
+				small_size: 8.0,
+				line_height: 1.2,
+				section_gap: 4.0,
+				entry_gap: 2.5,
+				margin: 14.0,
+				accent: (0.25, 0.25, 0.25),
+				heading_rule: true,
+				uppercase_headings: true,
+			},
+		}
+	}
+}
+
+#[derive(Clone, Copy)]
+enum FontKind {
+	Regular,
+	Bold,
+	Italic,
+}
+
+struct Fonts {
+	regular: IndirectFontRef,
+	bold: IndirectFontRef,
+	italic: IndirectFontRef,
+}
+
+/// Lays text out top to bottom, starting a new page whenever the current one runs out of space
+struct Writer {
+	document: PdfDocumentReference,
+	layers: Vec<PdfLayerReference>,
+	fonts: Fonts,
+	style: Style,
+	width: f32,
+	height: f32,
+	cursor: f32,
+}
+
+impl Writer {
+	fn new(title: &str, page_size: PageSize, style: Style) -> Result<Self> {
+		let (width, height) = page_size.dimensions();
+		let (document, page, layer) = PdfDocument::new(title, Mm(width), Mm(height), "Layer 1");
+
+		let fonts = Fonts {
+			regular: add_font(&document, style.regular)?,
+			bold: add_font(&document, style.bold)?,
+			italic: add_font(&document, style.italic)?,
+		};
+
+		let layer = document.get_page(page).get_layer(layer);
+
+		Ok(Self {
+			document,
+			layers: vec![layer],
+			fonts,
+			cursor: height - style.margin,
+			style,
+			width,
+			height,
+		})
+	}
+
+	fn layer(&self) -> &PdfLayerReference {
+		self.layers.last().expect("document always has a page")
+	}
+
+	const fn font(&self, kind: FontKind) -> &IndirectFontRef {
+		match kind {
+			FontKind::Regular => &self.fonts.regular,
+			FontKind::Bold => &self.fonts.bold,
+			FontKind::Italic => &self.fonts.italic,
+		}
+	}
+
+	const fn content_width(&self) -> f32 {
+		self.style.margin.mul_add(-2.0, self.width)
+	}
+
+	fn line_height(&self, size: f32) -> f32 {
+		size * self.style.line_height * PT_TO_MM
+	}
+
+	fn add_page(&mut self) {
+		let (page, layer) = self.document.add_page(Mm(self.width), Mm(self.height), "Layer 1");
+		self.layers.push(self.document.get_page(page).get_layer(layer));
+		self.cursor = self.height - self.style.margin;
+	}
+
+	/// Starts a new page unless `height` millimeters still fit above the bottom margin
+	fn ensure_space(&mut self, height: f32) {
+		if self.cursor - height < self.style.margin {
+			self.add_page();
+		}
+	}
+
+	fn gap(&mut self, height: f32) {
+		self.cursor -= height;
+	}
+
+	fn set_color(&self, (r, g, b): (f32, f32, f32)) {
+		self.layer().set_fill_color(Color::Rgb(Rgb::new(r, g, b, None)));
+	}
+
+	fn text_line(&mut self, text: &str, kind: FontKind, size: f32) {
+		let line_height = self.line_height(size);
+		self.ensure_space(line_height);
+		self.cursor -= line_height;
+
+		let font = self.font(kind).clone();
+		self.layer()
+			.use_text(text, size, Mm(self.style.margin), Mm(self.cursor), &font);
+	}
+
+	fn paragraph(&mut self, text: &str, kind: FontKind, size: f32) {
+		let max_chars = self.max_chars(size);
+
+		for paragraph in text.lines() {
+			for line in wrap(paragraph, max_chars) {
+				self.text_line(&line, kind, size);
+			}
+		}
+	}
+
+	fn heading(&mut self, text: &str) {
+		let size = self.style.heading_size;
+		let text = if self.style.uppercase_headings {
+			text.to_uppercase()
+		} else {
+			text.to_string()
+		};
+
+		self.gap(self.style.section_gap);
+		// Keep the heading together with at least the first line of its section
+		self.ensure_space(
+			self.line_height(self.style.body_size)
+				.mul_add(2.0, self.line_height(size)),
+		);
+
+		self.set_color(self.style.accent);
+		self.text_line(&text, FontKind::Bold, size);
+		self.set_color((0.0, 0.0, 0.0));
+
+		if self.style.heading_rule {
+			self.gap(1.5);
+			self.rule();
+		}
+
+		self.gap(self.style.entry_gap / 2.0);
+	}
+
+	fn rule(&self) {
+		let layer = self.layer();
+		layer.set_outline_color(Color::Rgb(Rgb::new(0.6, 0.6, 0.6, None)));
+		layer.set_outline_thickness(0.5);
+		layer.add_line(Line {
+			points: vec![
+				(Point::new(Mm(self.style.margin), Mm(self.cursor)), false),
+				(Point::new(Mm(self.width - self.style.margin), Mm(self.cursor)), false),
+			],
+			is_closed: false,
+		});
+	}
+
+	#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+	fn max_chars(&self, size: f32) -> usize {
+		(self.content_width() / (size * self.style.glyph_width * PT_TO_MM))
+			.floor()
+			.max(1.0) as usize
+	}
+
+	/// Stamps "Page N of M" on every page and serializes the document
+	fn finish(self, locale: Locale) -> Result<Vec<u8>> {
+		let labels = locale.labels();
+		let total = self.layers.len();
+		let size = self.style.small_size;
+
+		for (index, layer) in self.layers.iter().enumerate() {
+			let footer = format!("{} {} {} {total}", labels.page, index + 1, labels.of);
+			layer.set_fill_color(Color::Rgb(Rgb::new(0.45, 0.45, 0.45, None)));
+			layer.use_text(
+				footer,
+				size,
+				Mm(self.style.margin),
+				Mm(self.style.margin / 2.0),
+				&self.fonts.regular,
+			);
+		}
+
+		self.document
+			.save_to_bytes()
+			.map_err(|error| anyhow!(error).context("Failed to save PDF"))
+	}
+}
+
+fn add_font(document: &PdfDocumentReference, font: &[u8]) -> Result<IndirectFontRef> {
+	document
+		.add_external_font(font)
+		.map_err(|error| anyhow!(error).context("Failed to add font"))
+}
+
+/// Greedy word wrap on character count, hard-splitting words longer than a line
+fn wrap(text: &str, max_chars: usize) -> Vec<String> {
+	let mut lines = Vec::new();
+	let mut current = String::new();
+
+	for word in text.split_whitespace() {
+		let mut word: Vec<char> = word.chars().collect();
+
+		while word.len() > max_chars {
+			if !current.is_empty() {
+				lines.push(std::mem::take(&mut current));
+			}
+			lines.push(word.drain(..max_chars).collect());
+		}
+
+		let current_len = current.chars().count();
+		if current_len > 0 && current_len + 1 + word.len() > max_chars {
+			lines.push(std::mem::take(&mut current));
+		}
+		if !current.is_empty() {
+			current.push(' ');
+		}
+		current.extend(word);
+	}
+
+	if !current.is_empty() {
+		lines.push(current);
+	}
+
+	lines
+}
+
+pub fn render(resume: &Resume, template: CvTemplate, page_size: PageSize, locale: Locale) -> Result<Vec<u8>> {
+	let labels = locale.labels();
+	let style = template.style();
+	let (name_size, body_size, small_size, entry_gap) =
+		(style.name_size, style.body_size, style.small_size, style.entry_gap);
+
+	let mut writer = Writer::new(&resume.user.username, page_size, style)?;
+
+	writer.text_line(&resume.user.username, FontKind::Bold, name_size);
+	writer.gap(1.0);
+	if let Some(headline) = &resume.user.headline {
+		writer.paragraph(headline, FontKind::Italic, body_size);
+	}
+	writer.text_line(&resume.user.email, FontKind::Regular, small_size);
+
+	if let Some(bio) = &resume.user.bio {
+		writer.gap(entry_gap);
+		writer.paragraph(bio, FontKind::Regular, body_size);
+	}
+
+	if !resume.experience.is_empty() {
+		writer.heading(labels.experience);
+
+		for (index, experience) in resume.experience.iter().enumerate() {
+			if index > 0 {
+				writer.gap(entry_gap);
+			}
+
+			writer.paragraph(
+				&format!("{} — {}", experience.occupation, experience.company_name),
+				FontKind::Bold,
+				body_size,
+			);
+
+			let details = [
+				locale.format_period(
+					&experience.start_date.first_day(),
+					experience.end_date.map(|end_date| end_date.first_day()).as_ref(),
+				),
+				experience.location_name.clone(),
+				locale.location_type(&experience.location_type).to_string(),
+				locale.employment_type(&experience.employment_type).to_string(),
+			]
+			.join(" · ");
+			writer.paragraph(&details, FontKind::Italic, small_size);
+
+			writer.gap(1.0);
+			writer.paragraph(&experience.description, FontKind::Regular, body_size);
+		}
+	}
+
+	if !resume.education.is_empty() {
+		writer.heading(labels.education);
+
+		for (index, education) in resume.education.iter().enumerate() {
+			if index > 0 {
+				writer.gap(entry_gap);
+			}
+
+			let title = [education.degree.as_deref(), education.field_of_study.as_deref()]
+				.into_iter()
+				.flatten()
+				.collect::<Vec<_>>()
+				.join(", ");
+			let heading = if title.is_empty() {
+				education.institution.clone()
+			} else {
+				format!("{title} — {}", education.institution)
+			};
+			writer.paragraph(&heading, FontKind::Bold, body_size);
+
+			let mut details = vec![locale.format_period(&education.start_date, education.end_date.as_ref())];
+			details.extend(education.grade.clone());
+			writer.paragraph(&details.join(" · "), FontKind::Italic, small_size);
+
+			if let Some(description) = &education.description {
+				writer.gap(1.0);
+				writer.paragraph(description, FontKind::Regular, body_size);
+			}
+		}
+	}
+
+	if !resume.skills.is_empty() {
+		writer.heading(labels.skills);
+
+		let skills = resume
+			.skills
+			.iter()
+			.map(|skill| skill.name.as_str())
+			.collect::<Vec<_>>()
+			.join(", ");
+		writer.paragraph(&skills, FontKind::Regular, body_size);
+	}
+
+	for section in &resume.custom_sections {
+		write_custom_section(&mut writer, section, locale);
+	}
+
+	writer.finish(locale)
+}
+
+/// User defined sections keep their own title, so only the date ranges in them are localized
+fn write_custom_section(writer: &mut Writer, section: &CustomSection, locale: Locale) {
+	let (body_size, small_size) = (writer.style.body_size, writer.style.small_size);
+
+	writer.heading(&section.title);
+
+	for block in section.blocks.iter() {
+		match block {
+			Block::Title(title) => {
+				writer.gap(writer.style.entry_gap);
+				writer.paragraph(&title.text, FontKind::Bold, body_size);
+			}
+			Block::DateRange(date_range) => {
+				let period = locale.format_period(&date_range.start_date, date_range.end_date.as_ref());
+				writer.paragraph(&period, FontKind::Italic, small_size);
+			}
+			Block::Link(link) => {
+				let text = link
+					.label
+					.as_ref()
+					.map_or_else(|| link.url.clone(), |label| format!("{label}: {}", link.url));
+				writer.paragraph(&text, FontKind::Regular, small_size);
+			}
+			Block::Markdown(markdown) => {
+				writer.gap(1.0);
+				writer.paragraph(&markdown.text, FontKind::Regular, body_size);
+			}
+			Block::List(list) => {
+				for item in &list.items {
+					writer.paragraph(&format!("• {item}"), FontKind::Regular, body_size);
+				}
+			}
+		}
+	}
+}
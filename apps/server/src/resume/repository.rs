@@ -0,0 +1,44 @@
+use sqlx::{Postgres, Transaction};
+
+use crate::{
+	education::repository::EducationRepository, experience::repository::ExperienceRepository,
+	skills::repository::SkillsRepository,
+};
+
+use super::import::{ImportMode, ImportPreview};
+
+pub struct ResumeRepository;
+
+impl ResumeRepository {
+	/// Applies a previewed import inside the caller's transaction, so a failure leaves the profile untouched
+	pub async fn apply_import(
+		transaction: &mut Transaction<'_, Postgres>,
+		user_id: &i64,
+		preview: &ImportPreview,
+	) -> anyhow::Result<()> {
+		if preview.mode == ImportMode::Replace {
+			for experience in &preview.experience.removed {
+				ExperienceRepository::delete(&mut **transaction, &experience.id, user_id).await?;
+			}
+			for education in &preview.education.removed {
+				EducationRepository::delete(&mut **transaction, &education.id, user_id).await?;
+			}
+			// Skills the import lists again keep their proficiency, years, position and pin
+			let removed_ids: Vec<i64> = preview.skills.removed.iter().map(|skill| skill.id).collect();
+			SkillsRepository::remove_user_skills(&mut **transaction, user_id, &removed_ids).await?;
+		}
+
+		for experience_dto in &preview.experience.added {
+			ExperienceRepository::insert(&mut **transaction, user_id, experience_dto.clone()).await?;
+		}
+
+		for education_dto in &preview.education.added {
+			EducationRepository::insert(&mut **transaction, user_id, education_dto.clone()).await?;
+		}
+
+		let added_ids: Vec<i64> = preview.skills.added.iter().map(|skill| skill.id).collect();
+		SkillsRepository::add_user_skills(&mut **transaction, user_id, &added_ids).await?;
+
+		Ok(())
+	}
+}
@@ -0,0 +1,317 @@
+use std::sync::Arc;
+
+use axum::{
+	body::Bytes,
+	extract::{DefaultBodyLimit, Path, Query, State},
+	http::{header, StatusCode},
+	middleware,
+	response::{IntoResponse, Response},
+	routing::{get, post},
+	Extension, Json, Router,
+};
+use thiserror::Error;
+use validator::ValidationErrors;
+
+use crate::{
+	app::{ApiErrorResponse, ApiResult},
+	database::Database,
+	education::repository::EducationRepository,
+	experience::repository::ExperienceRepository,
+	history::repository::{HistoryRepository, Section},
+	i18n::RequestedLocales,
+	sections::repository::SectionsRepository,
+	skills::repository::{Skill, SkillsRepository},
+	users::{
+		auth::{self, CurrentUser},
+		repository::UsersRepostory,
+	},
+	validation::ValidatedJson,
+};
+
+use super::{
+	dtos::{CvQuery, Resume},
+	import::{ImportPreview, ImportQuery, ImportedProfile},
+	json_resume::JsonResume,
+	linkedin,
+	locale::Locale,
+	pdf,
+	repository::ResumeRepository,
+};
+
+/// LinkedIn exports include messages and media metadata, so they are much larger than a JSON body
+const LINKEDIN_ARCHIVE_LIMIT: usize = 50 * 1024 * 1024;
+
+#[derive(Clone)]
+#[allow(clippy::struct_field_names)]
+pub struct ResumeState {
+	pub users_repository: UsersRepostory,
+	pub experience_repository: ExperienceRepository,
+	pub education_repository: EducationRepository,
+	pub skills_repository: SkillsRepository,
+	pub history_repository: HistoryRepository,
+	pub sections_repository: SectionsRepository,
+}
+
+impl ResumeState {
+	pub fn new(database: &Arc<Database>) -> Self {
+		Self {
+			users_repository: UsersRepostory::new(database),
+			experience_repository: ExperienceRepository::new(database),
+			education_repository: EducationRepository::new(database),
+			skills_repository: SkillsRepository::new(database),
+			history_repository: HistoryRepository::new(database),
+			sections_repository: SectionsRepository::new(database),
+		}
+	}
+
+	async fn load_resume(
+		&self,
+		username: &str,
+		requested_locales: &RequestedLocales,
+	) -> Result<Resume, ResumeApiError> {
+		let user = self
+			.users_repository
+			.find_user_by_username(username)
+			.await
+			.map_err(|_| ResumeApiError::UserNotFound(username.to_string()))?;
+
+		let locale = self
+			.users_repository
+			.negotiate_locale(&user.id, requested_locales)
+			.await
+			.map_err(|_| ResumeApiError::Load())?;
+
+		let user = self
+			.users_repository
+			.localize(user, &locale)
+			.await
+			.map_err(|_| ResumeApiError::Load())?;
+
+		let experience = self
+			.experience_repository
+			.get_by_user_id(user.id, locale.translation())
+			.await
+			.map_err(|_| ResumeApiError::Load())?;
+
+		let education = self
+			.education_repository
+			.get_by_user_id(user.id)
+			.await
+			.map_err(|_| ResumeApiError::Load())?;
+
+		let skills = self
+			.skills_repository
+			.get_user_skills(&user.id)
+			.await
+			.map_err(|_| ResumeApiError::Load())?;
+
+		let custom_sections = self
+			.sections_repository
+			.get_by_user_id(&user.id)
+			.await
+			.map_err(|_| ResumeApiError::Load())?;
+
+		Ok(Resume {
+			locale: locale.locale,
+			user,
+			experience,
+			education,
+			skills,
+			custom_sections,
+		})
+	}
+}
+
+impl ResumeState {
+	/// Diffs imported data against the user's profile and, unless this is a dry run, applies it
+	async fn import(
+		&self,
+		current_user: &CurrentUser,
+		imported: ImportedProfile,
+		query: &ImportQuery,
+	) -> Result<(StatusCode, ImportPreview), ResumeApiError> {
+		let existing_experience = self
+			.experience_repository
+			.get_by_user_id(current_user.user_id, None)
+			.await
+			.map_err(|_| ResumeApiError::Load())?;
+
+		let existing_education = self
+			.education_repository
+			.get_by_user_id(current_user.user_id)
+			.await
+			.map_err(|_| ResumeApiError::Load())?;
+
+		let existing_skills = self
+			.skills_repository
+			.get_user_skills(&current_user.user_id)
+			.await
+			.map_err(|_| ResumeApiError::Load())?;
+
+		let catalog = self
+			.skills_repository
+			.list()
+			.await
+			.map_err(|_| ResumeApiError::Load())?;
+
+		let aliases = self
+			.skills_repository
+			.list_aliases()
+			.await
+			.map_err(|_| ResumeApiError::Load())?;
+
+		let preview = ImportPreview::new(
+			imported,
+			query,
+			existing_experience,
+			existing_education,
+			existing_skills.into_iter().map(Skill::from).collect(),
+			&catalog,
+			&aliases,
+		);
+
+		if query.dry_run {
+			return Ok((StatusCode::OK, preview));
+		}
+
+		let mut transaction = self
+			.history_repository
+			.track(
+				&current_user.user_id,
+				&current_user.user_id,
+				// Replacing experience also deletes the translations of removed entries
+				&[
+					Section::Experience,
+					Section::Education,
+					Section::Skills,
+					Section::Translations,
+				],
+			)
+			.await
+			.map_err(|_| ResumeApiError::Import())?;
+
+		ResumeRepository::apply_import(&mut transaction, &current_user.user_id, &preview)
+			.await
+			.map_err(|_| ResumeApiError::Import())?;
+
+		transaction.commit().await.map_err(|_| ResumeApiError::Import())?;
+
+		Ok((StatusCode::CREATED, preview))
+	}
+}
+
+pub fn init() -> Router<ResumeState> {
+	Router::new()
+		.route("/users/me/import/json-resume", post(import_json_resume_route))
+		.route(
+			"/users/me/import/linkedin",
+			post(import_linkedin_route).layer(DefaultBodyLimit::max(LINKEDIN_ARCHIVE_LIMIT)),
+		)
+		.route_layer(middleware::from_fn(auth::middleware))
+		.route("/users/{username}/cv.pdf", get(cv_pdf_route))
+		.route("/users/{username}/resume.json", get(json_resume_route))
+}
+
+async fn cv_pdf_route(
+	Path(username): Path<String>,
+	Query(query): Query<CvQuery>,
+	requested_locales: RequestedLocales,
+	State(state): State<ResumeState>,
+) -> ApiResult<([(header::HeaderName, String); 3], Vec<u8>)> {
+	let requested_locales = match query.locale {
+		Some(locale) => requested_locales.prefer(locale.code()),
+		None => requested_locales,
+	};
+
+	let resume = state.load_resume(&username, &requested_locales).await?;
+
+	let locale = query
+		.locale
+		.or_else(|| Locale::from_tag(&resume.locale))
+		.unwrap_or_default();
+
+	let pdf = pdf::render(&resume, query.template, query.page_size, locale).map_err(|_| ResumeApiError::RenderPdf())?;
+
+	let headers = [
+		(header::CONTENT_LANGUAGE, resume.locale.clone()),
+		(header::CONTENT_TYPE, String::from("application/pdf")),
+		(
+			header::CONTENT_DISPOSITION,
+			format!("inline; filename=\"{}-cv.pdf\"", resume.user.username),
+		),
+	];
+
+	Ok((StatusCode::OK, (headers, pdf)))
+}
+
+async fn json_resume_route(
+	Path(username): Path<String>,
+	requested_locales: RequestedLocales,
+	State(state): State<ResumeState>,
+) -> ApiResult<([(header::HeaderName, String); 1], Json<JsonResume>)> {
+	let resume = state.load_resume(&username, &requested_locales).await?;
+
+	let headers = [(header::CONTENT_LANGUAGE, resume.locale.clone())];
+
+	Ok((StatusCode::OK, (headers, Json(JsonResume::from_resume(&resume)))))
+}
+
+async fn import_json_resume_route(
+	Extension(current_user): Extension<CurrentUser>,
+	Query(query): Query<ImportQuery>,
+	State(state): State<ResumeState>,
+	ValidatedJson(request): ValidatedJson<JsonResume>,
+) -> ApiResult<Json<ImportPreview>> {
+	let imported = request.into_import().map_err(ResumeApiError::InvalidDocument)?;
+
+	let (status_code, preview) = state.import(&current_user, imported, &query).await?;
+
+	Ok((status_code, Json(preview)))
+}
+
+async fn import_linkedin_route(
+	Extension(current_user): Extension<CurrentUser>,
+	Query(query): Query<ImportQuery>,
+	State(state): State<ResumeState>,
+	archive: Bytes,
+) -> ApiResult<Json<ImportPreview>> {
+	let imported = linkedin::parse(&archive).map_err(|error| ResumeApiError::InvalidArchive(format!("{error:#}")))?;
+
+	let (status_code, preview) = state.import(&current_user, imported, &query).await?;
+
+	Ok((status_code, Json(preview)))
+}
+
+#[derive(Debug, Error)]
+pub enum ResumeApiError {
+	#[error("User not found: {0}")]
+	UserNotFound(String),
+
+	#[error("Failed to load resume")]
+	Load(),
+
+	#[error("Failed to render PDF")]
+	RenderPdf(),
+
+	#[error("Invalid document: {0}")]
+	InvalidDocument(ValidationErrors),
+
+	#[error("Invalid archive: {0}")]
+	InvalidArchive(String),
+
+	#[error("Failed to import resume")]
+	Import(),
+}
+
+impl IntoResponse for ResumeApiError {
+	fn into_response(self) -> Response {
+		let status_code = match self {
+			Self::UserNotFound(_) => StatusCode::NOT_FOUND,
+			Self::InvalidDocument(_) | Self::InvalidArchive(_) => StatusCode::BAD_REQUEST,
+			Self::Load() | Self::RenderPdf() | Self::Import() => StatusCode::INTERNAL_SERVER_ERROR,
+		};
+
+		log::error!("{self:?}");
+		ApiErrorResponse::new(status_code, self.to_string()).into_response()
+	}
+}
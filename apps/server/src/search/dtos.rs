@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::{languages::repository::CefrLevel, skills::repository::SkillProficiency};
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchUsersQuery {
+	/// Part of the username
+	#[validate(length(min = 1, max = 100, message = "Wrong query length"))]
+	pub q: Option<String>,
+	pub skill_id: Option<i64>,
+	/// Whole years of roles `skill_id` was used in; only applies together with it
+	#[validate(range(min = 0, max = 70, message = "Skill years must be between 0 and 70"))]
+	pub min_skill_years: Option<i32>,
+	/// Proficiency the user gives `skill_id` at least; only applies together with it
+	pub min_skill_proficiency: Option<SkillProficiency>,
+	/// ISO 639-1 code of a language the user must speak
+	#[validate(length(equal = 2, message = "Language code must be an ISO 639-1 code"))]
+	pub language: Option<String>,
+	/// Only applies together with `language`
+	pub min_proficiency: Option<CefrLevel>,
+	/// Total experience in whole years, overlapping roles counted once
+	#[validate(range(min = 0, max = 70, message = "Experience years must be between 0 and 70"))]
+	pub min_experience_years: Option<i32>,
+	#[validate(range(min = 0, max = 70, message = "Experience years must be between 0 and 70"))]
+	pub max_experience_years: Option<i32>,
+	/// A city from location autocomplete, matched against the profile location and current roles
+	pub location_id: Option<i64>,
+	/// ISO 3166-1 alpha-2 code of the country the profile location or a current role is in
+	#[validate(length(equal = 2, message = "Country code must be an ISO 3166-1 alpha-2 code"))]
+	pub country: Option<String>,
+	#[serde(default)]
+	pub sort: SearchSort,
+	#[serde(default = "default_limit")]
+	#[validate(range(min = 1, max = 100, message = "Limit must be between 1 and 100"))]
+	pub limit: i64,
+	#[serde(default)]
+	#[validate(range(min = 0, message = "Offset must not be negative"))]
+	pub offset: i64,
+}
+
+impl SearchUsersQuery {
+	/// Whether results are filtered or ordered by experience, which needs every candidate's tenure
+	pub fn depends_on_tenure(&self) -> bool {
+		self.min_experience_years.is_some()
+			|| self.max_experience_years.is_some()
+			|| (self.skill_id.is_some() && self.min_skill_years.is_some())
+			|| self.sort != SearchSort::Username
+	}
+}
+
+const fn default_limit() -> i64 {
+	20
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SearchSort {
+	#[default]
+	Username,
+	/// Most experienced first
+	Experience,
+	/// Most experienced with `skill_id` first
+	SkillExperience,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct UserSearchResult {
+	pub id: i64,
+	pub username: String,
+	pub experience_months: i32,
+	/// Only filled in when searching by skill
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub skill_experience_months: Option<i32>,
+}
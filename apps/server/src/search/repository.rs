@@ -0,0 +1,104 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+
+use crate::{
+	database::{escape_like, Database},
+	languages::repository::CefrLevel,
+	skills::repository::SkillProficiency,
+};
+
+use super::dtos::{SearchSort, SearchUsersQuery, UserSearchResult};
+
+#[derive(Clone)]
+pub struct SearchRepository {
+	pub(crate) database: Arc<Database>,
+}
+
+impl SearchRepository {
+	pub fn new(database: &Arc<Database>) -> Self {
+		Self {
+			database: Arc::clone(database),
+		}
+	}
+
+	/// Finds verified users matching every filter given in the query
+	pub async fn search_users(&self, query: &SearchUsersQuery) -> anyhow::Result<Vec<UserSearchResult>> {
+		let users = sqlx::query_as!(
+			UserSearchResult,
+			r#"
+				-- Tenure is computed per user, so the other filters narrow the candidates down first. When nothing
+				-- filters or sorts by tenure, they are paged right away and only the page's tenure is computed.
+				WITH candidates AS MATERIALIZED (
+					SELECT u.id, u.username
+					FROM users u
+					WHERE u.is_verified
+						AND ($1::TEXT IS NULL OR u.username ILIKE $1)
+						AND ($2::BIGINT IS NULL OR EXISTS (
+							SELECT 1 FROM user_skills us
+							WHERE us.user_id = u.id
+								AND us.skill_id = $2
+								AND ($14::skill_proficiency IS NULL OR us.proficiency >= $14)
+						))
+						AND ($3::TEXT IS NULL OR EXISTS (
+							SELECT 1 FROM user_languages ul
+							WHERE ul.user_id = u.id
+								AND ul.language_code = LOWER($3)
+								AND ($4::cefr_level IS NULL OR ul.proficiency >= $4)
+						))
+						AND ($12::BIGINT IS NULL AND $13::TEXT IS NULL OR EXISTS (
+							SELECT 1
+							FROM locations l
+							WHERE ($12::BIGINT IS NULL OR l.id = $12) AND ($13::TEXT IS NULL OR l.country_code = UPPER($13))
+								AND (
+									l.id = u.location_id
+									OR EXISTS (
+										SELECT 1 FROM experience e
+										WHERE e.user_id = u.id AND e.location_id = l.id AND (e.is_current OR e.end_date IS NULL)
+									)
+								)
+						))
+					ORDER BY u.username
+					LIMIT CASE WHEN $15::BOOLEAN THEN NULL ELSE $5::BIGINT END
+					OFFSET CASE WHEN $15 THEN 0 ELSE $6::BIGINT END
+				)
+				SELECT c.id, c.username, tenure.months AS "experience_months!", tenure.skill_months AS skill_experience_months
+				FROM candidates c
+				CROSS JOIN LATERAL (
+					SELECT
+						experience_months(c.id) AS months,
+						CASE WHEN $2::BIGINT IS NOT NULL THEN skill_experience_months(c.id, $2) END AS skill_months
+				) tenure
+				WHERE ($7::INTEGER IS NULL OR tenure.months >= $7 * 12)
+					-- A maximum of 5 years still includes 5 years and 11 months
+					AND ($8::INTEGER IS NULL OR tenure.months < ($8 + 1) * 12)
+					AND ($10::INTEGER IS NULL OR $2::BIGINT IS NULL OR tenure.skill_months >= $10 * 12)
+				ORDER BY
+					CASE WHEN $9 THEN tenure.months END DESC,
+					CASE WHEN $11 THEN tenure.skill_months END DESC NULLS LAST,
+					c.username
+				LIMIT $5 OFFSET CASE WHEN $15 THEN $6 ELSE 0 END
+			"#,
+			query.q.as_deref().map(|q| format!("%{}%", escape_like(q))),
+			query.skill_id,
+			query.language,
+			query.min_proficiency as Option<CefrLevel>,
+			query.limit,
+			query.offset,
+			query.min_experience_years,
+			query.max_experience_years,
+			query.sort == SearchSort::Experience,
+			query.min_skill_years,
+			query.sort == SearchSort::SkillExperience,
+			query.location_id,
+			query.country,
+			query.min_skill_proficiency as Option<SkillProficiency>,
+			query.depends_on_tenure()
+		)
+		.fetch_all(&*self.database.pool)
+		.await
+		.context("Failed to search users")?;
+
+		Ok(users)
+	}
+}
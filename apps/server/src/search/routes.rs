@@ -0,0 +1,78 @@
+use std::sync::Arc;
+
+use axum::{
+	extract::{Query, State},
+	http::StatusCode,
+	middleware,
+	response::{IntoResponse, Response},
+	routing::get,
+	Json, Router,
+};
+use thiserror::Error;
+use validator::{Validate, ValidationErrors};
+
+use crate::{
+	app::{ApiErrorResponse, ApiResult},
+	database::Database,
+	users::auth,
+};
+
+use super::{
+	dtos::{SearchUsersQuery, UserSearchResult},
+	repository::SearchRepository,
+};
+
+#[derive(Clone)]
+pub struct SearchState {
+	pub search_repository: SearchRepository,
+}
+
+impl SearchState {
+	pub fn new(database: &Arc<Database>) -> Self {
+		Self {
+			search_repository: SearchRepository::new(database),
+		}
+	}
+}
+
+pub fn init() -> Router<SearchState> {
+	Router::new()
+		.route("/search/users", get(search_users_route))
+		.route_layer(middleware::from_fn(auth::middleware))
+}
+
+async fn search_users_route(
+	Query(query): Query<SearchUsersQuery>,
+	State(state): State<SearchState>,
+) -> ApiResult<Json<Vec<UserSearchResult>>> {
+	query.validate().map_err(SearchApiError::InvalidQuery)?;
+
+	let users = state
+		.search_repository
+		.search_users(&query)
+		.await
+		.map_err(|_| SearchApiError::SearchUsers())?;
+
+	Ok((StatusCode::OK, Json(users)))
+}
+
+#[derive(Debug, Error)]
+pub enum SearchApiError {
+	#[error("Invalid search query: {0}")]
+	InvalidQuery(ValidationErrors),
+
+	#[error("Failed to search users")]
+	SearchUsers(),
+}
+
+impl IntoResponse for SearchApiError {
+	fn into_response(self) -> Response {
+		let status_code = match self {
+			Self::InvalidQuery(_) => StatusCode::BAD_REQUEST,
+			Self::SearchUsers() => StatusCode::INTERNAL_SERVER_ERROR,
+		};
+
+		log::error!("{self:?}");
+		ApiErrorResponse::new(status_code, self.to_string()).into_response()
+	}
+}
@@ -0,0 +1,110 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError, ValidationErrors};
+
+use crate::validation::{field_error, validate_http_url};
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomSectionDto {
+	#[validate(length(min = 1, max = 100, message = "Wrong section title length"))]
+	pub title: String,
+	#[validate(
+		length(min = 1, max = 50, message = "A section needs between 1 and 50 blocks"),
+		nested
+	)]
+	pub blocks: Vec<Block>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ReorderSectionsDto {
+	#[validate(length(min = 1, message = "At least one section is required"))]
+	pub ids: Vec<i64>,
+}
+
+/// A piece of a custom section, tagged by `type` in JSON
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Block {
+	Title(TitleBlock),
+	DateRange(DateRangeBlock),
+	Link(LinkBlock),
+	Markdown(MarkdownBlock),
+	List(ListBlock),
+}
+
+impl Validate for Block {
+	fn validate(&self) -> Result<(), ValidationErrors> {
+		match self {
+			Self::Title(block) => block.validate(),
+			Self::DateRange(block) => block.validate(),
+			Self::Link(block) => block.validate(),
+			Self::Markdown(block) => block.validate(),
+			Self::List(block) => block.validate(),
+		}
+	}
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct TitleBlock {
+	#[validate(length(min = 1, max = 255, message = "Wrong title length"))]
+	pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+#[validate(schema(function = "validate_date_range"))]
+pub struct DateRangeBlock {
+	pub start_date: NaiveDate,
+	/// `None` for something still ongoing
+	pub end_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkBlock {
+	#[validate(custom(function = "validate_http_url"), length(max = 2048))]
+	pub url: String,
+	#[validate(length(min = 1, max = 255, message = "Wrong link label length"))]
+	pub label: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkdownBlock {
+	#[validate(length(min = 1, max = 10000, message = "Text must be between 1 and 10000 characters"))]
+	pub text: String,
+	/// `text` rendered to sanitized HTML, only filled in when sections are served for display
+	#[serde(default, skip_deserializing, skip_serializing_if = "Option::is_none")]
+	pub html: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ListBlock {
+	#[validate(
+		length(min = 1, max = 50, message = "A list needs between 1 and 50 items"),
+		custom(function = "validate_list_items")
+	)]
+	pub items: Vec<String>,
+}
+
+fn validate_date_range(date_range: &DateRangeBlock) -> Result<(), ValidationError> {
+	match date_range.end_date {
+		Some(end_date) if end_date < date_range.start_date => Err(field_error(
+			"endDate",
+			ValidationError::new("End date must not be before the start date"),
+		)),
+		_ => Ok(()),
+	}
+}
+
+fn validate_list_items(items: &[String]) -> Result<(), ValidationError> {
+	if items.iter().all(|item| (1..=500).contains(&item.chars().count())) {
+		Ok(())
+	} else {
+		Err(ValidationError::new("List items must be between 1 and 500 characters"))
+	}
+}
@@ -0,0 +1,139 @@
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use serde::Serialize;
+use sqlx::{types::Json, PgExecutor};
+
+use crate::database::Database;
+
+use super::dtos::{Block, CustomSectionDto};
+
+#[derive(Clone)]
+pub struct SectionsRepository {
+	pub(crate) database: Arc<Database>,
+}
+
+impl SectionsRepository {
+	pub fn new(database: &Arc<Database>) -> Self {
+		Self {
+			database: Arc::clone(database),
+		}
+	}
+
+	pub async fn create(
+		executor: impl PgExecutor<'_>,
+		user_id: &i64,
+		section_dto: CustomSectionDto,
+	) -> anyhow::Result<CustomSection> {
+		let section = sqlx::query_as!(
+			CustomSection,
+			r#"
+				INSERT INTO custom_sections (user_id, title, blocks)
+				VALUES ($1, $2, $3)
+				RETURNING user_id, id, title, blocks AS "blocks: Json<Vec<Block>>", position
+			"#,
+			user_id,
+			section_dto.title,
+			Json(section_dto.blocks) as _
+		)
+		.fetch_one(executor)
+		.await
+		.map_err(|error| anyhow!(error).context("Failed to create custom section"))?;
+
+		Ok(section)
+	}
+
+	/// Returns `None` when the section doesn't exist or belongs to someone else
+	pub async fn update(
+		executor: impl PgExecutor<'_>,
+		id: &i64,
+		user_id: &i64,
+		section_dto: CustomSectionDto,
+	) -> anyhow::Result<Option<CustomSection>> {
+		let section = sqlx::query_as!(
+			CustomSection,
+			r#"
+				UPDATE custom_sections
+				SET title = $3, blocks = $4
+				WHERE id = $1 AND user_id = $2
+				RETURNING user_id, id, title, blocks AS "blocks: Json<Vec<Block>>", position
+			"#,
+			id,
+			user_id,
+			section_dto.title,
+			Json(section_dto.blocks) as _
+		)
+		.fetch_optional(executor)
+		.await
+		.map_err(|error| anyhow!(error).context("Failed to update custom section"))?;
+
+		Ok(section)
+	}
+
+	pub async fn delete(executor: impl PgExecutor<'_>, id: &i64, user_id: &i64) -> anyhow::Result<()> {
+		sqlx::query!(
+			"DELETE FROM custom_sections WHERE id = $1 AND user_id = $2",
+			id,
+			user_id
+		)
+		.execute(executor)
+		.await
+		.map_err(|error| anyhow!(error).context("Failed to delete custom section"))?;
+
+		Ok(())
+	}
+
+	/// Manually ordered sections come first in the given order, the rest follow in creation order
+	pub async fn get_by_user_id(&self, user_id: &i64) -> anyhow::Result<Vec<CustomSection>> {
+		let sections = sqlx::query_as!(
+			CustomSection,
+			r#"
+				SELECT user_id, id, title, blocks AS "blocks: Json<Vec<Block>>", position
+				FROM custom_sections
+				WHERE user_id = $1
+				ORDER BY position ASC NULLS LAST, id ASC
+			"#,
+			user_id
+		)
+		.fetch_all(&*self.database.pool)
+		.await
+		.map_err(|error| anyhow!(error).context("Failed to get custom sections by user id"))?;
+
+		Ok(sections)
+	}
+
+	/// Positions the listed sections in the given order; sections left out follow in creation order
+	/// and ids that don't belong to the user are ignored
+	pub async fn reorder(executor: impl PgExecutor<'_>, user_id: &i64, ids: &[i64]) -> anyhow::Result<()> {
+		sqlx::query!(
+			r#"
+				UPDATE custom_sections
+				SET position = ordered.position::INTEGER
+				FROM (
+					SELECT custom_sections.id, ids.position
+					FROM custom_sections
+					LEFT JOIN UNNEST($2::BIGINT[]) WITH ORDINALITY AS ids (id, position) ON ids.id = custom_sections.id
+					WHERE custom_sections.user_id = $1
+				) AS ordered
+				WHERE custom_sections.id = ordered.id
+			"#,
+			user_id,
+			ids
+		)
+		.execute(executor)
+		.await
+		.map_err(|error| anyhow!(error).context("Failed to reorder custom sections"))?;
+
+		Ok(())
+	}
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomSection {
+	user_id: i64,
+	pub id: i64,
+	pub title: String,
+	pub blocks: Json<Vec<Block>>,
+	pub position: Option<i32>,
+}
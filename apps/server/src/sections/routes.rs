@@ -0,0 +1,202 @@
+use std::sync::Arc;
+
+use axum::{
+	extract::{Path, State},
+	http::StatusCode,
+	middleware,
+	response::{IntoResponse, Response},
+	routing::{delete, get, post, put},
+	Extension, Json, Router,
+};
+use thiserror::Error;
+
+use crate::{
+	app::{ApiErrorResponse, ApiResult},
+	database::Database,
+	history::repository::{HistoryRepository, Section},
+	services::markdown::MarkdownService,
+	users::auth::{self, CurrentUser},
+	validation::ValidatedJson,
+};
+
+use super::{
+	dtos::{Block, CustomSectionDto, MarkdownBlock, ReorderSectionsDto},
+	repository::{CustomSection, SectionsRepository},
+};
+
+#[derive(Clone)]
+pub struct SectionsState {
+	pub sections_repository: SectionsRepository,
+	pub markdown_service: MarkdownService,
+	pub history_repository: HistoryRepository,
+}
+
+impl SectionsState {
+	pub fn new(database: &Arc<Database>) -> Self {
+		Self {
+			sections_repository: SectionsRepository::new(database),
+			markdown_service: MarkdownService::new(database),
+			history_repository: HistoryRepository::new(database),
+		}
+	}
+}
+
+pub fn init() -> Router<SectionsState> {
+	Router::new()
+		.route("/sections", post(create_section_route))
+		.route("/sections/order", put(reorder_sections_route))
+		.route("/sections/{section_id}", put(update_section_route))
+		.route("/sections/{section_id}", delete(delete_section_route))
+		.route_layer(middleware::from_fn(auth::middleware))
+		.route("/{user_id}/sections", get(user_sections_route))
+}
+
+async fn create_section_route(
+	Extension(current_user): Extension<CurrentUser>,
+	State(state): State<SectionsState>,
+	ValidatedJson(request): ValidatedJson<CustomSectionDto>,
+) -> ApiResult<Json<i64>> {
+	let mut transaction = state
+		.history_repository
+		.track(&current_user.user_id, &current_user.user_id, &[Section::CustomSections])
+		.await
+		.map_err(|_| SectionsApiError::Create())?;
+
+	let section = SectionsRepository::create(&mut **transaction, &current_user.user_id, request)
+		.await
+		.map_err(|_| SectionsApiError::Create())?;
+
+	transaction.commit().await.map_err(|_| SectionsApiError::Create())?;
+
+	Ok((StatusCode::CREATED, Json(section.id)))
+}
+
+async fn update_section_route(
+	Extension(current_user): Extension<CurrentUser>,
+	State(state): State<SectionsState>,
+	Path(section_id): Path<i64>,
+	ValidatedJson(request): ValidatedJson<CustomSectionDto>,
+) -> ApiResult<Json<CustomSection>> {
+	let mut transaction = state
+		.history_repository
+		.track(&current_user.user_id, &current_user.user_id, &[Section::CustomSections])
+		.await
+		.map_err(|_| SectionsApiError::Update())?;
+
+	let section = SectionsRepository::update(&mut **transaction, &section_id, &current_user.user_id, request)
+		.await
+		.map_err(|_| SectionsApiError::Update())?
+		.ok_or(SectionsApiError::NotFound(section_id))?;
+
+	transaction.commit().await.map_err(|_| SectionsApiError::Update())?;
+
+	Ok((StatusCode::OK, Json(section)))
+}
+
+async fn delete_section_route(
+	Extension(current_user): Extension<CurrentUser>,
+	State(state): State<SectionsState>,
+	Path(section_id): Path<i64>,
+) -> ApiResult<()> {
+	let mut transaction = state
+		.history_repository
+		.track(&current_user.user_id, &current_user.user_id, &[Section::CustomSections])
+		.await
+		.map_err(|_| SectionsApiError::Delete())?;
+
+	SectionsRepository::delete(&mut **transaction, &section_id, &current_user.user_id)
+		.await
+		.map_err(|_| SectionsApiError::Delete())?;
+
+	transaction.commit().await.map_err(|_| SectionsApiError::Delete())?;
+
+	Ok((StatusCode::OK, ()))
+}
+
+async fn reorder_sections_route(
+	Extension(current_user): Extension<CurrentUser>,
+	State(state): State<SectionsState>,
+	ValidatedJson(request): ValidatedJson<ReorderSectionsDto>,
+) -> ApiResult<()> {
+	let mut transaction = state
+		.history_repository
+		.track(&current_user.user_id, &current_user.user_id, &[Section::CustomSections])
+		.await
+		.map_err(|_| SectionsApiError::Reorder())?;
+
+	SectionsRepository::reorder(&mut **transaction, &current_user.user_id, &request.ids)
+		.await
+		.map_err(|_| SectionsApiError::Reorder())?;
+
+	transaction.commit().await.map_err(|_| SectionsApiError::Reorder())?;
+
+	Ok((StatusCode::OK, ()))
+}
+
+async fn user_sections_route(
+	Path(user_id): Path<i64>,
+	State(state): State<SectionsState>,
+) -> ApiResult<Json<Vec<CustomSection>>> {
+	let mut sections = state
+		.sections_repository
+		.get_by_user_id(&user_id)
+		.await
+		.map_err(|_| SectionsApiError::GetUser())?;
+
+	let mut markdown_blocks: Vec<&mut MarkdownBlock> = sections
+		.iter_mut()
+		.flat_map(|section| section.blocks.iter_mut())
+		.filter_map(|block| match block {
+			Block::Markdown(markdown) => Some(markdown),
+			_ => None,
+		})
+		.collect();
+
+	let texts: Vec<&str> = markdown_blocks.iter().map(|markdown| markdown.text.as_str()).collect();
+	let rendered = state
+		.markdown_service
+		.render_all(&texts)
+		.await
+		.map_err(|_| SectionsApiError::GetUser())?;
+
+	for (markdown, html) in markdown_blocks.iter_mut().zip(rendered) {
+		markdown.html = Some(html);
+	}
+
+	Ok((StatusCode::OK, Json(sections)))
+}
+
+#[derive(Debug, Error)]
+pub enum SectionsApiError {
+	#[error("Failed to get user sections")]
+	GetUser(),
+
+	#[error("Failed to create section")]
+	Create(),
+
+	#[error("Failed to update section")]
+	Update(),
+
+	#[error("Failed to delete section")]
+	Delete(),
+
+	#[error("Failed to reorder sections")]
+	Reorder(),
+
+	#[error("Section not found: {0}")]
+	NotFound(i64),
+}
+
+impl IntoResponse for SectionsApiError {
+	fn into_response(self) -> Response {
+		let status_code = match self {
+			Self::NotFound(_) => StatusCode::NOT_FOUND,
+			Self::GetUser() | Self::Create() | Self::Update() | Self::Delete() | Self::Reorder() => {
+				StatusCode::INTERNAL_SERVER_ERROR
+			}
+		};
+
+		log::error!("{self:?}");
+		ApiErrorResponse::new(status_code, self.to_string()).into_response()
+	}
+}
@@ -0,0 +1,57 @@
+//! DNS lookups for proving control of a domain
+
+use anyhow::Context;
+use hickory_resolver::{
+	config::{NameServerConfigGroup, ResolverConfig, ResolverOpts},
+	error::ResolveErrorKind,
+	TokioAsyncResolver,
+};
+
+use crate::settings::SETTINGS;
+
+#[derive(Clone)]
+pub struct DnsService {
+	resolver: TokioAsyncResolver,
+}
+
+impl DnsService {
+	/// Uses the nameserver from `DNS_RESOLVER` when it's set, so a local stand-in can answer instead of the
+	/// real DNS, and the system's resolvers otherwise. Hosts without a readable resolver configuration, like
+	/// containers missing `/etc/resolv.conf`, fall back to hickory's default public resolvers.
+	pub fn new() -> Self {
+		let resolver = SETTINGS.dns.resolver.map_or_else(
+			|| {
+				TokioAsyncResolver::tokio_from_system_conf().unwrap_or_else(|error| {
+					log::warn!("Failed to read system DNS configuration, using default resolvers: {error}");
+					TokioAsyncResolver::tokio(ResolverConfig::default(), ResolverOpts::default())
+				})
+			},
+			|address| {
+				let nameservers = NameServerConfigGroup::from_ips_clear(&[address.ip()], address.port(), true);
+				TokioAsyncResolver::tokio(
+					ResolverConfig::from_parts(None, Vec::new(), nameservers),
+					ResolverOpts::default(),
+				)
+			},
+		);
+
+		Self { resolver }
+	}
+
+	/// Every TXT record of the name, each joined into one string. A name without any is an empty list.
+	pub async fn txt_records(&self, name: &str) -> anyhow::Result<Vec<String>> {
+		match self.resolver.txt_lookup(name).await {
+			Ok(lookup) => Ok(lookup
+				.iter()
+				.map(|txt| {
+					txt.txt_data()
+						.iter()
+						.map(|data| String::from_utf8_lossy(data))
+						.collect()
+				})
+				.collect()),
+			Err(error) if matches!(error.kind(), ResolveErrorKind::NoRecordsFound { .. }) => Ok(Vec::new()),
+			Err(error) => Err(error).context("Failed to look up TXT records"),
+		}
+	}
+}
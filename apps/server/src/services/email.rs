@@ -1,4 +1,5 @@
 use anyhow::{anyhow, Context, Result};
+use chrono::NaiveDate;
 use lettre::{
 	message::{header::ContentType, MessageBuilder},
 	transport::smtp::authentication::Credentials,
@@ -18,13 +19,13 @@ const SMTP_RELAY: &str = "smtp.gmail.com";
 #[allow(dead_code)]
 impl EmailService {
 	pub fn new() -> Self {
-		let credentials = Credentials::new(SETTINGS.smtp.username.to_string(), SETTINGS.smtp.password.to_string());
+		let credentials = Credentials::new(SETTINGS.smtp.username.clone(), SETTINGS.smtp.password.clone());
 		let transport = SmtpTransport::relay(SMTP_RELAY)
 			.context("Failed to create SMTP relay")
 			.unwrap();
 
 		Self {
-			sender_email: SETTINGS.smtp.sender_email.to_string(),
+			sender_email: SETTINGS.smtp.sender_email.clone(),
 			mailer: transport.credentials(credentials).build(),
 		}
 	}
@@ -81,6 +82,37 @@ impl EmailService {
 		Ok(())
 	}
 
+	pub fn send_certification_expiry_email(
+		&self,
+		recipient_email: &str,
+		certification_name: &str,
+		expiry_date: NaiveDate,
+	) -> Result<()> {
+		let email = self
+			.prepare_email(recipient_email)
+			.subject("Certification Expiring Soon")
+			.body(certification_expiry_email_html(certification_name, expiry_date))?;
+
+		self.mailer
+			.send(&email)
+			.map_err(|error| anyhow!("Failed to send certification expiry email: {}", error))?;
+
+		Ok(())
+	}
+
+	pub fn send_work_email_code(&self, recipient_email: &str, company_name: &str, code: &str) -> Result<()> {
+		let email = self
+			.prepare_email(recipient_email)
+			.subject("Confirm your work email")
+			.body(work_email_code_html(company_name, code))?;
+
+		self.mailer
+			.send(&email)
+			.map_err(|error| anyhow!("Failed to send work email code: {}", error))?;
+
+		Ok(())
+	}
+
 	fn prepare_email(&self, recipient_email: &str) -> MessageBuilder {
 		Message::builder()
 			.from(self.sender_email.parse().unwrap())
@@ -171,3 +203,52 @@ fn password_changed_email_html() -> String {
 		"#
 	.to_string()
 }
+
+fn certification_expiry_email_html(certification_name: &str, expiry_date: NaiveDate) -> String {
+	// Certification names come from what users typed
+	let certification_name = ammonia::clean_text(certification_name);
+
+	format!(
+		r#"
+		<!doctype html>
+		<html lang="en">
+		<head>
+			<meta charset="UTF-8">
+			<meta name="viewport"
+			content="width=device-width, user-scalable=no, initial-scale=1.0, maximum-scale=1.0, minimum-scale=1.0">
+			<meta http-equiv="X-UA-Compatible" content="ie=edge">
+			<title>Moner - Certification expiring soon</title>
+		</head>
+		<body>
+			<h1>Moner - Certification expiring soon</h1>
+			<p>Your certification "{certification_name}" expires on {expiry_date}. Renew it and update your profile to keep it current.</p>
+		</body>
+		</html>
+		"#,
+	)
+}
+
+fn work_email_code_html(company_name: &str, code: &str) -> String {
+	// Company names come from what users typed
+	let company_name = ammonia::clean_text(company_name);
+
+	format!(
+		r#"
+		<!doctype html>
+		<html lang="en">
+		<head>
+			<meta charset="UTF-8">
+			<meta name="viewport"
+			content="width=device-width, user-scalable=no, initial-scale=1.0, maximum-scale=1.0, minimum-scale=1.0">
+			<meta http-equiv="X-UA-Compatible" content="ie=edge">
+			<title>Moner - Confirm your work email</title>
+		</head>
+		<body>
+			<h1>Moner - Confirm your work email</h1>
+			<p>Your code for confirming your role at {company_name} is: {code}</p>
+			<p>If you didn't ask for it, you can ignore this email.</p>
+		</body>
+		</html>
+		"#,
+	)
+}
@@ -0,0 +1,221 @@
+//! Markdown that users write in long-form fields, rendered to HTML that's safe to embed as is
+
+use std::{collections::HashSet, sync::Arc, time::Duration};
+
+use ammonia::{Builder, UrlRelative};
+use anyhow::Context;
+use pulldown_cmark::{html, Options, Parser};
+
+use crate::database::Database;
+
+/// Part of the cache key, bump it whenever the output of `render_markdown` changes
+const RENDERER_VERSION: &str = "1";
+
+const PRUNE_INTERVAL: Duration = Duration::from_hours(1);
+
+#[derive(Clone)]
+pub struct MarkdownService {
+	database: Arc<Database>,
+}
+
+impl MarkdownService {
+	pub fn new(database: &Arc<Database>) -> Self {
+		Self {
+			database: Arc::clone(database),
+		}
+	}
+
+	/// Renders every source, in order. Each distinct text is rendered once and kept in `markdown_renders`, so
+	/// a description is only rendered again after it's edited.
+	pub async fn render_all(&self, sources: &[&str]) -> anyhow::Result<Vec<String>> {
+		// Refreshing the last use at most daily keeps reads from turning into a write each time
+		let cached = sqlx::query!(
+			r#"
+				WITH used AS (
+					UPDATE markdown_renders
+					SET last_used_at = CURRENT_TIMESTAMP
+					WHERE source_hash IN (SELECT MD5($2 || source) FROM UNNEST($1::TEXT[]) AS sources (source))
+						AND last_used_at < CURRENT_TIMESTAMP - INTERVAL '1 day'
+				)
+				SELECT mr.html AS "html?"
+				FROM UNNEST($1::TEXT[]) WITH ORDINALITY AS sources (source, position)
+				LEFT JOIN markdown_renders mr ON mr.source_hash = MD5($2 || sources.source)
+				ORDER BY sources.position
+			"#,
+			sources as &[&str],
+			RENDERER_VERSION
+		)
+		.fetch_all(&*self.database.pool)
+		.await
+		.context("Failed to read rendered markdown")?;
+
+		let mut rendered = Vec::with_capacity(sources.len());
+		let mut missing_sources = Vec::new();
+		let mut missing_html = Vec::new();
+		for (source, row) in sources.iter().zip(cached) {
+			let html = row.html.unwrap_or_else(|| {
+				let html = render_markdown(source);
+				missing_sources.push(*source);
+				missing_html.push(html.clone());
+				html
+			});
+			rendered.push(html);
+		}
+
+		if !missing_sources.is_empty() {
+			sqlx::query!(
+				r#"
+					INSERT INTO markdown_renders (source_hash, html)
+					SELECT MD5($1 || source), html FROM UNNEST($2::TEXT[], $3::TEXT[]) AS renders (source, html)
+					ON CONFLICT DO NOTHING
+				"#,
+				RENDERER_VERSION,
+				&missing_sources as &[&str],
+				&missing_html
+			)
+			.execute(&*self.database.pool)
+			.await
+			.context("Failed to cache rendered markdown")?;
+		}
+
+		Ok(rendered)
+	}
+
+	pub async fn render(&self, source: &str) -> anyhow::Result<String> {
+		let mut rendered = self.render_all(&[source]).await?;
+		Ok(rendered.pop().unwrap_or_default())
+	}
+
+	/// Drops renders nothing has read for a month, such as those of edited text or an old renderer version
+	pub async fn prune(&self) -> anyhow::Result<()> {
+		sqlx::query!("DELETE FROM markdown_renders WHERE last_used_at <= CURRENT_TIMESTAMP - INTERVAL '30 days'")
+			.execute(&*self.database.pool)
+			.await
+			.context("Failed to prune rendered markdown")?;
+
+		Ok(())
+	}
+}
+
+/// Background job pruning the render cache, which otherwise keeps a row for every text ever rendered
+pub fn spawn_pruning(database: &Arc<Database>) {
+	let markdown_service = MarkdownService::new(database);
+
+	tokio::spawn(async move {
+		let mut interval = tokio::time::interval(PRUNE_INTERVAL);
+
+		loop {
+			interval.tick().await;
+
+			if let Err(error) = markdown_service.prune().await {
+				log::error!("{error:?}");
+			}
+		}
+	});
+}
+
+/// `CommonMark` plus tables and strikethrough, cleaned against a strict allowlist: formatting, lists, code,
+/// quotes, tables and links. Raw HTML that isn't on it is dropped, images aren't allowed so profiles can't
+/// embed trackers, and links must be absolute http(s) or mailto URLs and open without a referrer.
+pub fn render_markdown(source: &str) -> String {
+	let parser = Parser::new_ext(source, Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH);
+	let mut unsafe_html = String::new();
+	html::push_html(&mut unsafe_html, parser);
+
+	Builder::empty()
+		.tags(HashSet::from([
+			"p",
+			"br",
+			"hr",
+			"h1",
+			"h2",
+			"h3",
+			"h4",
+			"h5",
+			"h6",
+			"em",
+			"strong",
+			"del",
+			"code",
+			"pre",
+			"blockquote",
+			"ul",
+			"ol",
+			"li",
+			"a",
+			"table",
+			"thead",
+			"tbody",
+			"tr",
+			"th",
+			"td",
+		]))
+		.tag_attributes([("a", HashSet::from(["href"])), ("ol", HashSet::from(["start"]))].into())
+		.url_schemes(HashSet::from(["http", "https", "mailto"]))
+		.url_relative(UrlRelative::Deny)
+		.link_rel(Some("nofollow noopener noreferrer"))
+		.clean(&unsafe_html)
+		.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn renders_formatting() {
+		assert_eq!(
+			render_markdown("**Rust** and *Go*"),
+			"<p><strong>Rust</strong> and <em>Go</em></p>\n"
+		);
+		assert_eq!(
+			render_markdown("- one\n- two"),
+			"<ul>\n<li>one</li>\n<li>two</li>\n</ul>\n"
+		);
+		assert_eq!(render_markdown("~~old~~"), "<p><del>old</del></p>\n");
+	}
+
+	#[test]
+	fn keeps_http_links_without_referrer() {
+		assert_eq!(
+			render_markdown("[site](https://example.com)"),
+			"<p><a href=\"https://example.com\" rel=\"nofollow noopener noreferrer\">site</a></p>\n"
+		);
+	}
+
+	#[test]
+	fn strips_script_urls_from_links() {
+		for source in [
+			"[click](javascript:alert(1))",
+			"[click](JavaScript:alert(1))",
+			"[click](data:text/html;base64,PHNjcmlwdD4=)",
+			"<a href=\"javascript:alert(1)\">click</a>",
+			"[click](/relative/path)",
+		] {
+			let html = render_markdown(source);
+			assert!(!html.contains("href"), "{source:?} rendered {html:?}");
+			assert!(html.contains("click"), "{source:?} lost its text: {html:?}");
+		}
+	}
+
+	#[test]
+	fn strips_raw_script_tags() {
+		let html = render_markdown("Hi\n\n<script>alert(1)</script>\n\n<p onclick=\"alert(1)\">there</p>");
+		assert!(!html.contains("<script"), "{html:?}");
+		assert!(!html.contains("alert"), "{html:?}");
+		assert!(!html.contains("onclick"), "{html:?}");
+		assert!(html.contains("there"), "{html:?}");
+	}
+
+	#[test]
+	fn strips_images() {
+		for source in [
+			"![tracker](https://example.com/pixel.png)",
+			"<img src=\"https://example.com/pixel.png\">",
+		] {
+			let html = render_markdown(source);
+			assert!(!html.contains("<img"), "{source:?} rendered {html:?}");
+			assert!(!html.contains("pixel.png"), "{source:?} rendered {html:?}");
+		}
+	}
+}
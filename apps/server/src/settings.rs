@@ -1,9 +1,8 @@
+use std::{net::SocketAddr, path::PathBuf, sync::LazyLock};
+
 use dotenvy::{dotenv, var};
-use lazy_static::lazy_static;
 
-lazy_static! {
-	pub static ref SETTINGS: Settings = Settings::new();
-}
+pub static SETTINGS: LazyLock<Settings> = LazyLock::new(Settings::new);
 
 #[derive(Debug, Clone)]
 pub struct Settings {
@@ -15,6 +14,8 @@ pub struct Settings {
 	pub server: Server,
 	pub smtp: Smtp,
 	pub auth: Auth,
+	pub dns: Dns,
+	pub geonames: Geonames,
 }
 
 impl Settings {
@@ -41,6 +42,14 @@ impl Settings {
 			auth: Auth {
 				jwt_secret: get_env("JWT_SECRET"),
 			},
+
+			dns: Dns {
+				resolver: get_optional_env("DNS_RESOLVER"),
+			},
+
+			geonames: Geonames {
+				dump_dir: get_optional_env("GEONAMES_DUMP_DIR"),
+			},
 		}
 	}
 }
@@ -62,6 +71,19 @@ pub struct Auth {
 	pub jwt_secret: String,
 }
 
+#[derive(Debug, Clone)]
+pub struct Dns {
+	/// Nameserver for domain ownership checks, like `127.0.0.1:5353`; the system's resolvers when unset
+	pub resolver: Option<SocketAddr>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Geonames {
+	/// Directory with the full `cities15000.txt`, `admin1CodesASCII.txt` and `countryInfo.txt`; the bundled
+	/// extract when unset
+	pub dump_dir: Option<PathBuf>,
+}
+
 fn get_env<T: std::str::FromStr>(key: &str) -> T {
 	let value_str = var(key).unwrap_or_else(|_| panic!("{key} must be set"));
 
@@ -72,3 +94,8 @@ fn get_env<T: std::str::FromStr>(key: &str) -> T {
 		)
 	})
 }
+
+/// Like `get_env`, but an unset or empty variable is `None`
+fn get_optional_env<T: std::str::FromStr>(key: &str) -> Option<T> {
+	var(key).ok().filter(|value| !value.is_empty()).map(|_| get_env(key))
+}
@@ -1,9 +1,162 @@
-use serde::Deserialize;
-use validator::Validate;
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError};
 
-use super::repository::Skill;
+use crate::{
+	experience::{repository::Experience, tenure::Tenure, year_month::YearMonth},
+	validation::field_error,
+};
+
+use super::repository::{SkillCategory, SkillProficiency, UserSkill};
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchSkillsQuery {
+	/// Start or a misspelling of a skill name
+	#[validate(length(min = 1, max = 100, message = "Wrong query length"))]
+	pub q: String,
+	#[serde(default = "default_limit")]
+	#[validate(range(min = 1, max = 50, message = "Limit must be between 1 and 50"))]
+	pub limit: i64,
+}
+
+const fn default_limit() -> i64 {
+	10
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateSkillDto {
+	#[validate(length(min = 1, max = 255, message = "Wrong skill name length"))]
+	pub name: String,
+	pub category: Option<SkillCategory>,
+	/// A broader skill this one is part of, like Rust for Axum
+	pub parent_id: Option<i64>,
+}
 
 #[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeSkillsDto {
+	/// Skills folded into the one in the path; their users move over and their names become aliases
+	#[validate(length(min = 1, max = 50, message = "Between 1 and 50 duplicates can be merged at once"))]
+	pub duplicate_ids: Vec<i64>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ProposeSkillDto {
+	#[validate(length(min = 1, max = 100, message = "Wrong skill name length"))]
+	pub name: String,
+}
+
+impl ProposeSkillDto {
+	/// The name with surrounding whitespace trimmed and inner runs of it collapsed
+	pub fn normalized_name(&self) -> String {
+		self.name.split_whitespace().collect::<Vec<_>>().join(" ")
+	}
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct MapSkillProposalDto {
+	/// The skill the proposal turns out to be, given by id or by one of its aliases
+	pub skill_id: Option<i64>,
+	pub alias: Option<String>,
+}
+
+/// The skills in the order the user wants them shown
+#[derive(Debug, Deserialize, Validate)]
+#[validate(schema(function = "validate_pinned"))]
 pub struct UpdateUserSkillsDto {
-	pub skills: Vec<Skill>,
+	#[validate(nested)]
+	pub skills: Vec<UserSkillDto>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UserSkillDto {
+	pub id: i64,
+	pub proficiency: Option<SkillProficiency>,
+	#[validate(range(min = 0, max = 70, message = "Skill years must be between 0 and 70"))]
+	pub years: Option<i32>,
+	#[validate(custom(function = "validate_last_used"))]
+	pub last_used: Option<YearMonth>,
+	/// One of the user's top skills, shown before the rest
+	#[serde(default)]
+	pub is_pinned: bool,
+}
+
+/// Top skills a user can pin, so pinning still stands out
+const MAX_PINNED_SKILLS: usize = 5;
+
+fn validate_pinned(skills_dto: &UpdateUserSkillsDto) -> Result<(), ValidationError> {
+	if skills_dto.skills.iter().filter(|skill| skill.is_pinned).count() <= MAX_PINNED_SKILLS {
+		Ok(())
+	} else {
+		Err(field_error(
+			"skills",
+			ValidationError::new("At most 5 skills can be pinned"),
+		))
+	}
+}
+
+fn validate_last_used(last_used: &YearMonth) -> Result<(), ValidationError> {
+	if last_used.first_day() <= Utc::now().date_naive() {
+		Ok(())
+	} else {
+		Err(ValidationError::new("last_used_in_future").with_message("Last used must not be in the future".into()))
+	}
+}
+
+/// Where a user's skill was used, so a listed skill can be told apart from one used in production
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillUsage {
+	#[serde(flatten)]
+	pub skill: UserSkill,
+	/// Months with at least one role the skill was used in
+	pub months: i32,
+	pub experience: Vec<SkillUsageEntry>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillUsageEntry {
+	pub experience_id: i64,
+	pub company_id: Option<i64>,
+	pub company_name: String,
+	pub occupation: String,
+	pub start_date: YearMonth,
+	pub end_date: Option<YearMonth>,
+}
+
+impl SkillUsage {
+	/// One usage per listed skill, in the order of `skills`, with entries in the order of `experience`
+	pub fn for_skills(skills: Vec<UserSkill>, experience: &[Experience], today: NaiveDate) -> Vec<Self> {
+		let tenure = Tenure::new(experience, today);
+
+		skills
+			.into_iter()
+			.map(|skill| Self {
+				months: tenure
+					.skills
+					.iter()
+					.find(|skill_tenure| skill_tenure.skill_id == skill.id)
+					.map_or(0, |skill_tenure| skill_tenure.months),
+				experience: experience
+					.iter()
+					.filter(|entry| entry.skill_ids.contains(&skill.id))
+					.map(|entry| SkillUsageEntry {
+						experience_id: entry.id,
+						company_id: entry.company_id,
+						company_name: entry.company_name.clone(),
+						occupation: entry.occupation.clone(),
+						start_date: entry.start_date,
+						end_date: entry.end_date,
+					})
+					.collect(),
+				skill,
+			})
+			.collect()
+	}
 }
@@ -1,4 +1,3 @@
+pub mod dtos;
+pub mod repository;
 pub mod routes;
-
-mod dtos;
-mod repository;
@@ -1,16 +1,22 @@
 use std::sync::Arc;
 
 use anyhow::Context;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sqlx::{PgExecutor, Postgres, Transaction};
 
-use crate::database::Database;
+use crate::{
+	database::{escape_like, Database},
+	experience::year_month::YearMonth,
+};
+
+use super::dtos::{UpdateSkillDto, UserSkillDto};
 
 #[derive(Clone)]
 pub struct SkillsRepository {
 	pub(crate) database: Arc<Database>,
 }
 
-#[allow(dead_code)]
 impl SkillsRepository {
 	pub fn new(database: &Arc<Database>) -> Self {
 		Self {
@@ -18,32 +24,546 @@ impl SkillsRepository {
 		}
 	}
 
-	pub async fn create(&self, name: &str) -> anyhow::Result<Skill> {
-		let skill = sqlx::query_as!(Skill, "INSERT INTO skills (name) VALUES ($1) RETURNING *", name)
-			.fetch_one(&*self.database.pool)
+	pub async fn list(&self) -> anyhow::Result<Vec<Skill>> {
+		let skills = sqlx::query_as!(Skill, "SELECT id, name FROM skills WHERE status = 'approved'")
+			.fetch_all(&*self.database.pool)
 			.await
-			.context("Failed to follow user")?;
+			.context("Failed to fetch skills")?;
+
+		Ok(skills)
+	}
+
+	/// Every approved skill with its category, parent and aliases, by name
+	pub async fn catalog(&self) -> anyhow::Result<Vec<CatalogSkill>> {
+		let skills = sqlx::query_as!(
+			CatalogSkill,
+			r#"
+				SELECT
+					s.id, s.name, s.category AS "category: SkillCategory", s.parent_id,
+					ARRAY(SELECT a.name FROM skill_aliases a WHERE a.skill_id = s.id ORDER BY a.name) AS "aliases!"
+				FROM skills s
+				WHERE s.status = 'approved'
+				ORDER BY s.name
+			"#
+		)
+		.fetch_all(&*self.database.pool)
+		.await
+		.context("Failed to get skills catalog")?;
+
+		Ok(skills)
+	}
+
+	pub async fn find(&self, id: &i64) -> anyhow::Result<Option<CatalogSkill>> {
+		let skill = sqlx::query_as!(
+			CatalogSkill,
+			r#"
+				SELECT
+					s.id, s.name, s.category AS "category: SkillCategory", s.parent_id,
+					ARRAY(SELECT a.name FROM skill_aliases a WHERE a.skill_id = s.id ORDER BY a.name) AS "aliases!"
+				FROM skills s
+				WHERE s.id = $1
+			"#,
+			id
+		)
+		.fetch_optional(&*self.database.pool)
+		.await
+		.context("Failed to find skill")?;
 
 		Ok(skill)
 	}
 
-	pub async fn list(&self) -> anyhow::Result<Vec<Skill>> {
-		let skills = sqlx::query_as!(Skill, "SELECT * FROM skills")
+	pub async fn list_aliases(&self) -> anyhow::Result<Vec<SkillAlias>> {
+		let aliases = sqlx::query_as!(SkillAlias, "SELECT name, skill_id FROM skill_aliases")
 			.fetch_all(&*self.database.pool)
 			.await
-			.context("Failed to fetch skills")?;
+			.context("Failed to get skill aliases")?;
+
+		Ok(aliases)
+	}
+
+	/// Whether a skill other than `except_id` is named so, or goes by it as an alias, ignoring case
+	pub async fn is_name_taken(&self, name: &str, except_id: &i64) -> anyhow::Result<bool> {
+		let taken = sqlx::query_scalar!(
+			r#"
+				SELECT
+					EXISTS (SELECT 1 FROM skills WHERE LOWER(name) = LOWER(TRIM($1)) AND id <> $2)
+					OR EXISTS (SELECT 1 FROM skill_aliases WHERE name = LOWER(TRIM($1)) AND skill_id <> $2)
+					AS "taken!"
+			"#,
+			name,
+			except_id
+		)
+		.fetch_one(&*self.database.pool)
+		.await
+		.context("Failed to check skill name")?;
+
+		Ok(taken)
+	}
+
+	/// Whether making `parent_id` the parent of `id` would make the skill its own ancestor
+	pub async fn is_descendant(&self, parent_id: &i64, id: &i64) -> anyhow::Result<bool> {
+		let is_descendant = sqlx::query_scalar!(
+			r#"
+				WITH RECURSIVE ancestors AS (
+					SELECT id, parent_id FROM skills WHERE id = $1
+					UNION
+					SELECT s.id, s.parent_id FROM skills s JOIN ancestors a ON s.id = a.parent_id
+				)
+				SELECT EXISTS (SELECT 1 FROM ancestors WHERE id = $2) AS "is_descendant!"
+			"#,
+			parent_id,
+			id
+		)
+		.fetch_one(&*self.database.pool)
+		.await
+		.context("Failed to check skill hierarchy")?;
+
+		Ok(is_descendant)
+	}
+
+	/// Returns `None` when the skill doesn't exist
+	pub async fn update(&self, id: &i64, skill_dto: UpdateSkillDto) -> anyhow::Result<Option<CatalogSkill>> {
+		let updated = sqlx::query_scalar!(
+			r#"
+				UPDATE skills
+				SET name = TRIM($2), category = $3, parent_id = $4
+				WHERE id = $1
+				RETURNING id
+			"#,
+			id,
+			skill_dto.name,
+			skill_dto.category as Option<SkillCategory>,
+			skill_dto.parent_id
+		)
+		.fetch_optional(&*self.database.pool)
+		.await
+		.context("Failed to update skill")?;
+
+		if updated.is_none() {
+			return Ok(None);
+		}
+
+		self.find(id).await
+	}
+
+	pub async fn add_alias(&self, id: &i64, alias: &str) -> anyhow::Result<()> {
+		sqlx::query!(
+			r#"
+				INSERT INTO skill_aliases (name, skill_id) VALUES (LOWER(TRIM($2)), $1)
+				ON CONFLICT (name) DO NOTHING
+			"#,
+			id,
+			alias
+		)
+		.execute(&*self.database.pool)
+		.await
+		.context("Failed to add skill alias")?;
+
+		Ok(())
+	}
+
+	pub async fn delete_alias(&self, id: &i64, alias: &str) -> anyhow::Result<()> {
+		sqlx::query!(
+			"DELETE FROM skill_aliases WHERE skill_id = $1 AND name = LOWER(TRIM($2))",
+			id,
+			alias
+		)
+		.execute(&*self.database.pool)
+		.await
+		.context("Failed to delete skill alias")?;
+
+		Ok(())
+	}
+
+	/// Moves every user, role and certification listing the duplicates over to the target, re-parents their
+	/// children and keeps their names as aliases of it, so they resolve to the target from now on. Returns `None`
+	/// when the target doesn't exist.
+	pub async fn merge(&self, target_id: &i64, duplicate_ids: &[i64]) -> anyhow::Result<Option<CatalogSkill>> {
+		let mut transaction = self.database.pool.begin().await?;
+
+		let target = sqlx::query_scalar!("SELECT id FROM skills WHERE id = $1 FOR UPDATE", target_id)
+			.fetch_optional(&mut *transaction)
+			.await
+			.context("Failed to find merge target")?;
+
+		if target.is_none() {
+			return Ok(None);
+		}
+
+		sqlx::query!(
+			r#"
+				-- A user listing several of the skills, the target among them, keeps the strongest, and where the
+				-- first of them was
+				INSERT INTO user_skills (user_id, skill_id, proficiency, years, last_used, position, is_pinned)
+				SELECT
+					user_id, $1::BIGINT, MAX(proficiency), MAX(years), MAX(last_used), MIN(position), BOOL_OR(is_pinned)
+				FROM user_skills
+				WHERE skill_id = $1 OR skill_id = ANY($2)
+				GROUP BY user_id
+				HAVING BOOL_OR(skill_id = ANY($2))
+				ON CONFLICT (user_id, skill_id) DO UPDATE
+				SET
+					proficiency = GREATEST(user_skills.proficiency, EXCLUDED.proficiency),
+					years = GREATEST(user_skills.years, EXCLUDED.years),
+					last_used = GREATEST(user_skills.last_used, EXCLUDED.last_used),
+					position = LEAST(user_skills.position, EXCLUDED.position),
+					is_pinned = user_skills.is_pinned OR EXCLUDED.is_pinned
+			"#,
+			target_id,
+			duplicate_ids
+		)
+		.execute(&mut *transaction)
+		.await
+		.context("Failed to move user skills to merged skill")?;
+
+		sqlx::query!(
+			r#"
+				INSERT INTO experience_skills (experience_id, skill_id)
+				SELECT DISTINCT experience_id, $1::BIGINT FROM experience_skills WHERE skill_id = ANY($2)
+				ON CONFLICT DO NOTHING
+			"#,
+			target_id,
+			duplicate_ids
+		)
+		.execute(&mut *transaction)
+		.await
+		.context("Failed to move experience skills to merged skill")?;
+
+		sqlx::query!(
+			r#"
+				INSERT INTO certification_skills (certification_id, skill_id)
+				SELECT DISTINCT certification_id, $1::BIGINT FROM certification_skills WHERE skill_id = ANY($2)
+				ON CONFLICT DO NOTHING
+			"#,
+			target_id,
+			duplicate_ids
+		)
+		.execute(&mut *transaction)
+		.await
+		.context("Failed to move certification skills to merged skill")?;
+
+		// The target can't become its own parent, it loses a parent that's being merged into it instead
+		sqlx::query!(
+			r#"
+				UPDATE skills
+				SET parent_id = CASE WHEN id = $1 THEN NULL ELSE $1 END
+				WHERE parent_id = ANY($2)
+			"#,
+			target_id,
+			duplicate_ids
+		)
+		.execute(&mut *transaction)
+		.await
+		.context("Failed to move children of merged skills")?;
+
+		sqlx::query!(
+			"UPDATE skill_aliases SET skill_id = $1 WHERE skill_id = ANY($2)",
+			target_id,
+			duplicate_ids
+		)
+		.execute(&mut *transaction)
+		.await
+		.context("Failed to move aliases of merged skills")?;
+
+		sqlx::query!(
+			r#"
+				INSERT INTO skill_aliases (name, skill_id)
+				SELECT LOWER(name), $1 FROM skills WHERE id = ANY($2)
+				ON CONFLICT (name) DO UPDATE SET skill_id = EXCLUDED.skill_id
+			"#,
+			target_id,
+			duplicate_ids
+		)
+		.execute(&mut *transaction)
+		.await
+		.context("Failed to alias merged skills")?;
+
+		sqlx::query!("DELETE FROM skills WHERE id = ANY($1)", duplicate_ids)
+			.execute(&mut *transaction)
+			.await
+			.context("Failed to delete merged skills")?;
+
+		transaction.commit().await?;
+
+		self.find(target_id).await
+	}
+
+	/// Proposals a user is still waiting on a moderator for
+	pub async fn count_pending_proposals(&self, user_id: &i64) -> anyhow::Result<i64> {
+		let pending = sqlx::query_scalar!(
+			r#"
+				SELECT COUNT(*) AS "pending!"
+				FROM skill_proposals p
+				JOIN skills s ON s.id = p.skill_id
+				WHERE p.user_id = $1 AND s.status = 'pending'
+			"#,
+			user_id
+		)
+		.fetch_one(&*self.database.pool)
+		.await
+		.context("Failed to count skill proposals")?;
+
+		Ok(pending)
+	}
+
+	/// Lists the skill for the user. A name the catalog already has, as a skill or an alias, resolves to it;
+	/// anything else becomes a pending skill only the users proposing it list, until a moderator approves it or
+	/// `auto_approve_at` users have proposed it. Returns `None` when a moderator rejected the name.
+	pub async fn propose(
+		transaction: &mut Transaction<'_, Postgres>,
+		user_id: &i64,
+		name: &str,
+		auto_approve_at: i64,
+	) -> anyhow::Result<Option<ProposedSkill>> {
+		let aliased = sqlx::query_as!(
+			ProposedSkill,
+			r#"
+				SELECT s.id, s.name, s.status AS "status: SkillStatus"
+				FROM skill_aliases a
+				JOIN skills s ON s.id = a.skill_id
+				WHERE a.name = LOWER($1)
+			"#,
+			name
+		)
+		.fetch_optional(&mut **transaction)
+		.await
+		.context("Failed to find skill alias")?;
+
+		let mut skill = match aliased {
+			Some(skill) => skill,
+			None => sqlx::query_as!(
+				ProposedSkill,
+				r#"
+					INSERT INTO skills (name, status) VALUES ($1, 'pending')
+					ON CONFLICT ((LOWER(name))) DO UPDATE SET name = skills.name
+					RETURNING id, name, status AS "status: SkillStatus"
+				"#,
+				name
+			)
+			.fetch_one(&mut **transaction)
+			.await
+			.context("Failed to create proposed skill")?,
+		};
+
+		match skill.status {
+			SkillStatus::Rejected => return Ok(None),
+			SkillStatus::Approved => {}
+			SkillStatus::Pending => {
+				sqlx::query!(
+					"INSERT INTO skill_proposals (skill_id, user_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+					skill.id,
+					user_id
+				)
+				.execute(&mut **transaction)
+				.await
+				.context("Failed to record skill proposal")?;
+
+				skill.status = sqlx::query_scalar!(
+					r#"
+						UPDATE skills
+						SET status = CASE
+							WHEN (SELECT COUNT(*) FROM skill_proposals WHERE skill_id = $1) >= $2 THEN 'approved'
+							ELSE status
+						END
+						WHERE id = $1
+						RETURNING status AS "status: SkillStatus"
+					"#,
+					skill.id,
+					auto_approve_at
+				)
+				.fetch_one(&mut **transaction)
+				.await
+				.context("Failed to auto-approve skill proposal")?;
+			}
+		}
+
+		Self::add_user_skills(&mut **transaction, user_id, &[skill.id]).await?;
+
+		Ok(Some(skill))
+	}
+
+	/// Skills the user proposed, however moderators ruled on them, newest first. Proposals mapped to another
+	/// skill are gone, the user lists that skill instead.
+	pub async fn get_user_proposals(&self, user_id: &i64) -> anyhow::Result<Vec<SkillProposal>> {
+		let proposals = sqlx::query_as!(
+			SkillProposal,
+			r#"
+				SELECT s.id, s.name, s.status AS "status: SkillStatus", p.created_at AS proposed_at
+				FROM skill_proposals p
+				JOIN skills s ON s.id = p.skill_id
+				WHERE p.user_id = $1
+				ORDER BY p.created_at DESC
+			"#,
+			user_id
+		)
+		.fetch_all(&*self.database.pool)
+		.await
+		.context("Failed to get skill proposals")?;
+
+		Ok(proposals)
+	}
+
+	/// The moderation queue, proposals waiting longest first
+	pub async fn get_pending_proposals(&self) -> anyhow::Result<Vec<PendingSkill>> {
+		let skills = sqlx::query_as!(
+			PendingSkill,
+			r#"
+				SELECT s.id, s.name, COUNT(p.user_id) AS "proposers!", MIN(p.created_at) AS "proposed_at!"
+				FROM skills s
+				JOIN skill_proposals p ON p.skill_id = s.id
+				WHERE s.status = 'pending'
+				GROUP BY s.id
+				ORDER BY MIN(p.created_at), s.id
+			"#
+		)
+		.fetch_all(&*self.database.pool)
+		.await
+		.context("Failed to get pending skills")?;
 
 		Ok(skills)
 	}
 
-	pub async fn get_user_skills(&self, user_id: &i64) -> anyhow::Result<Vec<Skill>> {
+	pub async fn get_status(&self, id: &i64) -> anyhow::Result<Option<SkillStatus>> {
+		let status = sqlx::query_scalar!(
+			r#"SELECT status AS "status: SkillStatus" FROM skills WHERE id = $1"#,
+			id
+		)
+		.fetch_optional(&*self.database.pool)
+		.await
+		.context("Failed to get skill status")?;
+
+		Ok(status)
+	}
+
+	pub async fn find_alias(&self, alias: &str) -> anyhow::Result<Option<i64>> {
+		let skill_id = sqlx::query_scalar!("SELECT skill_id FROM skill_aliases WHERE name = LOWER(TRIM($1))", alias)
+			.fetch_optional(&*self.database.pool)
+			.await
+			.context("Failed to find skill alias")?;
+
+		Ok(skill_id)
+	}
+
+	/// Returns `None` when the skill isn't pending
+	pub async fn approve(&self, id: &i64) -> anyhow::Result<Option<CatalogSkill>> {
+		let approved = sqlx::query_scalar!(
+			"UPDATE skills SET status = 'approved' WHERE id = $1 AND status = 'pending' RETURNING id",
+			id
+		)
+		.fetch_optional(&*self.database.pool)
+		.await
+		.context("Failed to approve skill")?;
+
+		if approved.is_none() {
+			return Ok(None);
+		}
+
+		self.find(id).await
+	}
+
+	/// Takes the skill off everyone who listed it. The skill stays behind, so the name can't be proposed again.
+	/// Returns `false` when the skill isn't pending.
+	pub async fn reject(&self, id: &i64) -> anyhow::Result<bool> {
+		let mut transaction = self.database.pool.begin().await?;
+
+		let rejected = sqlx::query_scalar!(
+			"UPDATE skills SET status = 'rejected' WHERE id = $1 AND status = 'pending' RETURNING id",
+			id
+		)
+		.fetch_optional(&mut *transaction)
+		.await
+		.context("Failed to reject skill")?;
+
+		if rejected.is_none() {
+			return Ok(false);
+		}
+
+		sqlx::query!("DELETE FROM user_skills WHERE skill_id = $1", id)
+			.execute(&mut *transaction)
+			.await
+			.context("Failed to delete rejected user skills")?;
+
+		sqlx::query!("DELETE FROM experience_skills WHERE skill_id = $1", id)
+			.execute(&mut *transaction)
+			.await
+			.context("Failed to delete rejected experience skills")?;
+
+		sqlx::query!("DELETE FROM certification_skills WHERE skill_id = $1", id)
+			.execute(&mut *transaction)
+			.await
+			.context("Failed to delete rejected certification skills")?;
+
+		transaction.commit().await?;
+
+		Ok(true)
+	}
+
+	/// Exact and prefix matches first, then similar names, the skills most users list first within each. Aliases
+	/// match too and resolve to their skill. Skills the user already lists are left out.
+	pub async fn search(&self, user_id: &i64, query: &str, limit: i64) -> anyhow::Result<Vec<SkillSearchResult>> {
+		let term = query.trim().to_lowercase();
+		if term.is_empty() {
+			return Ok(Vec::new());
+		}
+
+		// Bound as a value rather than computed in SQL, so the planner sees a constant prefix and can use
+		// `skills_name_prefix_idx`
+		let prefix = format!("{}%", escape_like(&term));
+
+		let skills = sqlx::query_as!(
+			SkillSearchResult,
+			r#"
+				WITH candidates AS (
+					SELECT s.id AS skill_id, LOWER(s.name) AS name, NULL AS alias
+					FROM skills s
+					WHERE s.status = 'approved' AND (LOWER(s.name) LIKE $3 OR LOWER(s.name) % $2)
+					UNION ALL
+					SELECT a.skill_id, a.name, a.name
+					FROM skill_aliases a
+					WHERE a.name LIKE $3 OR a.name % $2
+				), best AS (
+					-- The closest of a skill's names decides its rank
+					SELECT DISTINCT ON (c.skill_id)
+						c.skill_id,
+						c.alias,
+						c.name = $2 AS is_exact,
+						c.name LIKE $3 AS is_prefix,
+						SIMILARITY(c.name, $2) AS similarity
+					FROM candidates c
+					ORDER BY c.skill_id, c.name = $2 DESC, c.name LIKE $3 DESC, SIMILARITY(c.name, $2) DESC
+				)
+				SELECT s.id, s.name, b.alias AS "alias?", popularity.users AS "users!"
+				FROM best b
+				JOIN skills s ON s.id = b.skill_id
+				CROSS JOIN LATERAL (SELECT COUNT(*) AS users FROM user_skills us WHERE us.skill_id = s.id) popularity
+				WHERE NOT EXISTS (SELECT 1 FROM user_skills us WHERE us.user_id = $1 AND us.skill_id = s.id)
+				ORDER BY b.is_exact DESC, b.is_prefix DESC, popularity.users DESC, b.similarity DESC, s.name
+				LIMIT $4
+			"#,
+			user_id,
+			term,
+			prefix,
+			limit
+		)
+		.fetch_all(&*self.database.pool)
+		.await
+		.context("Failed to search skills")?;
+
+		Ok(skills)
+	}
+
+	/// Approved skills the user lists, pinned ones first, then in the user's order
+	pub async fn get_user_skills(&self, user_id: &i64) -> anyhow::Result<Vec<UserSkill>> {
 		let skills = sqlx::query_as!(
-			Skill,
+			UserSkill,
 			r#"
-				SELECT s.id, s.name
+				SELECT
+					s.id, s.name, us.proficiency AS "proficiency: SkillProficiency", us.years,
+					us.last_used AS "last_used: YearMonth", us.is_pinned
 				FROM skills s
 				JOIN user_skills us ON s.id = us.skill_id
-				WHERE us.user_id = $1;
+				WHERE us.user_id = $1 AND s.status = 'approved'
+				ORDER BY us.is_pinned DESC, us.position ASC NULLS LAST, s.name
 			"#,
 			user_id
 		)
@@ -54,34 +574,234 @@ impl SkillsRepository {
 		Ok(skills)
 	}
 
-	pub async fn update_user_skills(&self, user_id: &i64, skills: Vec<Skill>) -> anyhow::Result<()> {
-		sqlx::query!("DELETE FROM user_skills WHERE user_id = $1", user_id)
-			.execute(&*self.database.pool)
-			.await
-			.context("Failed to delete user skills")?;
+	/// Replaces the skills a user lists, keeping them in the given order. Besides approved skills, they can keep
+	/// the pending ones they proposed.
+	pub async fn update_user_skills(
+		transaction: &mut Transaction<'_, Postgres>,
+		user_id: &i64,
+		skills: Vec<UserSkillDto>,
+	) -> anyhow::Result<()> {
+		let mut skill_ids = Vec::with_capacity(skills.len());
+		let mut proficiencies = Vec::with_capacity(skills.len());
+		let mut years = Vec::with_capacity(skills.len());
+		let mut last_used = Vec::with_capacity(skills.len());
+		let mut pinned = Vec::with_capacity(skills.len());
+		for skill in skills {
+			// A skill listed twice keeps its first position
+			if skill_ids.contains(&skill.id) {
+				continue;
+			}
+			skill_ids.push(skill.id);
+			proficiencies.push(skill.proficiency);
+			years.push(skill.years);
+			last_used.push(skill.last_used.map(|month| month.to_string()));
+			pinned.push(skill.is_pinned);
+		}
+
+		Self::delete_user_skills(&mut **transaction, user_id).await?;
+
+		let inserted = sqlx::query!(
+			r#"
+				INSERT INTO user_skills (user_id, skill_id, proficiency, years, last_used, position, is_pinned)
+				SELECT $1, s.id, input.proficiency, input.years, input.last_used::year_month, input.position, input.is_pinned
+				FROM UNNEST($2::BIGINT[], $3::skill_proficiency[], $4::INTEGER[], $5::VARCHAR[], $6::BOOLEAN[])
+					WITH ORDINALITY AS input (skill_id, proficiency, years, last_used, is_pinned, position)
+				JOIN skills s ON s.id = input.skill_id
+				WHERE s.status = 'approved'
+					OR s.status = 'pending' AND EXISTS (
+						SELECT 1 FROM skill_proposals p WHERE p.skill_id = s.id AND p.user_id = $1
+					)
+			"#,
+			user_id,
+			&skill_ids,
+			&proficiencies as &[Option<SkillProficiency>],
+			&years as &[Option<i32>],
+			&last_used as &[Option<String>],
+			&pinned
+		)
+		.execute(&mut **transaction)
+		.await
+		.context("Failed to insert user skills")?;
 
-		if skills.is_empty() {
-			return Ok(());
+		if inserted.rows_affected() < skill_ids.len() as u64 {
+			anyhow::bail!("Unknown or unapproved skills in {skill_ids:?}");
 		}
 
-		// TODO: Use bulk insert
-		for skill in skills {
-			sqlx::query!(
-				"INSERT INTO user_skills (user_id, skill_id) VALUES ($1, $2)",
-				user_id,
-				skill.id
-			)
-			.execute(&*self.database.pool)
+		Ok(())
+	}
+
+	pub async fn add_user_skills(
+		executor: impl PgExecutor<'_>,
+		user_id: &i64,
+		skill_ids: &[i64],
+	) -> anyhow::Result<()> {
+		sqlx::query!(
+			r#"
+				INSERT INTO user_skills (user_id, skill_id)
+				SELECT $1, skill_id FROM UNNEST($2::BIGINT[]) AS skill_id
+				ON CONFLICT DO NOTHING
+			"#,
+			user_id,
+			skill_ids
+		)
+		.execute(executor)
+		.await
+		.context("Failed to add user skills")?;
+
+		Ok(())
+	}
+
+	pub async fn remove_user_skills(
+		executor: impl PgExecutor<'_>,
+		user_id: &i64,
+		skill_ids: &[i64],
+	) -> anyhow::Result<()> {
+		sqlx::query!(
+			"DELETE FROM user_skills WHERE user_id = $1 AND skill_id = ANY($2)",
+			user_id,
+			skill_ids
+		)
+		.execute(executor)
+		.await
+		.context("Failed to remove user skills")?;
+
+		Ok(())
+	}
+
+	pub async fn delete_user_skills(executor: impl PgExecutor<'_>, user_id: &i64) -> anyhow::Result<()> {
+		sqlx::query!("DELETE FROM user_skills WHERE user_id = $1", user_id)
+			.execute(executor)
 			.await
-			.context("Failed to insert user skills")?;
-		}
+			.context("Failed to delete user skills")?;
 
 		Ok(())
 	}
 }
 
-#[derive(Debug, Deserialize, Serialize, sqlx::FromRow)]
+#[derive(Debug, Clone, Deserialize, Serialize, sqlx::FromRow)]
 pub struct Skill {
 	pub id: i64,
 	pub name: String,
 }
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserSkill {
+	pub id: i64,
+	pub name: String,
+	pub proficiency: Option<SkillProficiency>,
+	/// Years of experience with the skill, as the user puts it
+	pub years: Option<i32>,
+	pub last_used: Option<YearMonth>,
+	pub is_pinned: bool,
+}
+
+impl From<UserSkill> for Skill {
+	fn from(user_skill: UserSkill) -> Self {
+		Self {
+			id: user_skill.id,
+			name: user_skill.name,
+		}
+	}
+}
+
+/// Ordered from weakest to strongest like the `skill_proficiency` enum in the database
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "skill_proficiency", rename_all = "lowercase")]
+pub enum SkillProficiency {
+	Beginner,
+	Intermediate,
+	Advanced,
+	Expert,
+}
+
+impl SkillProficiency {
+	pub const fn label(self) -> &'static str {
+		match self {
+			Self::Beginner => "Beginner",
+			Self::Intermediate => "Intermediate",
+			Self::Advanced => "Advanced",
+			Self::Expert => "Expert",
+		}
+	}
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillSearchResult {
+	pub id: i64,
+	pub name: String,
+	/// The alias the query matched, when it didn't match the name
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub alias: Option<String>,
+	/// Users listing the skill
+	pub users: i64,
+}
+
+/// Like the `skill_status` enum in the database
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "skill_status", rename_all = "lowercase")]
+pub enum SkillStatus {
+	Pending,
+	Approved,
+	Rejected,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProposedSkill {
+	pub id: i64,
+	pub name: String,
+	pub status: SkillStatus,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillProposal {
+	pub id: i64,
+	pub name: String,
+	pub status: SkillStatus,
+	pub proposed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingSkill {
+	pub id: i64,
+	pub name: String,
+	/// Distinct users who proposed the skill
+	pub proposers: i64,
+	/// When the first of them did
+	pub proposed_at: DateTime<Utc>,
+}
+
+/// Like the `skill_category` enum in the database
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "skill_category", rename_all = "lowercase")]
+pub enum SkillCategory {
+	Language,
+	Framework,
+	Tool,
+	Soft,
+	Design,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogSkill {
+	pub id: i64,
+	pub name: String,
+	pub category: Option<SkillCategory>,
+	/// A broader skill this one is part of, like Rust for Axum
+	pub parent_id: Option<i64>,
+	/// Other names the skill goes by, lowercased
+	pub aliases: Vec<String>,
+}
+
+pub struct SkillAlias {
+	pub name: String,
+	pub skill_id: i64,
+}
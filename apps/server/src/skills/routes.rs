@@ -2,62 +2,115 @@ use std::sync::Arc;
 
 use axum::{
 	debug_handler,
-	extract::{Path, State},
+	extract::{Path, Query, State},
 	http::StatusCode,
 	middleware,
 	response::{IntoResponse, Response},
-	routing::{get, post},
+	routing::{delete, get, post, put},
 	Extension, Json, Router,
 };
+use chrono::Utc;
 use thiserror::Error;
+use validator::{Validate, ValidationErrors};
 
 use crate::{
 	app::{ApiErrorResponse, ApiResult},
 	database::Database,
+	experience::repository::ExperienceRepository,
+	history::repository::{HistoryRepository, Section},
 	users::auth::{self, CurrentUser},
 	validation::ValidatedJson,
 };
 
 use super::{
-	dtos::UpdateUserSkillsDto,
-	repository::{Skill, SkillsRepository},
+	dtos::{
+		MapSkillProposalDto, MergeSkillsDto, ProposeSkillDto, SearchSkillsQuery, SkillUsage, UpdateSkillDto,
+		UpdateUserSkillsDto,
+	},
+	repository::{
+		CatalogSkill, PendingSkill, ProposedSkill, SkillProposal, SkillSearchResult, SkillStatus, SkillsRepository,
+		UserSkill,
+	},
 };
 
+/// Distinct users proposing the same name that approve it without a moderator
+const AUTO_APPROVE_PROPOSERS: i64 = 5;
+/// Proposals a user can have waiting on a moderator at once
+const MAX_PENDING_PROPOSALS: i64 = 20;
+
 #[derive(Clone)]
+#[allow(clippy::struct_field_names)]
 pub struct SkillsState {
 	pub skills_repository: SkillsRepository,
+	pub history_repository: HistoryRepository,
+	pub experience_repository: ExperienceRepository,
 }
 
 impl SkillsState {
 	pub fn new(database: &Arc<Database>) -> Self {
 		Self {
 			skills_repository: SkillsRepository::new(database),
+			history_repository: HistoryRepository::new(database),
+			experience_repository: ExperienceRepository::new(database),
 		}
 	}
 }
 
 pub fn init() -> Router<SkillsState> {
 	Router::new()
+		.route("/admin/skills/{skill_id}", put(update_skill_route))
+		.route("/admin/skills/{skill_id}/merge", post(merge_skills_route))
+		.route("/admin/skills/{skill_id}/aliases/{alias}", put(add_alias_route))
+		.route("/admin/skills/{skill_id}/aliases/{alias}", delete(delete_alias_route))
+		.route("/admin/skill-proposals", get(get_pending_proposals_route))
+		.route(
+			"/admin/skill-proposals/{skill_id}/approve",
+			post(approve_proposal_route),
+		)
+		.route("/admin/skill-proposals/{skill_id}/reject", post(reject_proposal_route))
+		.route("/admin/skill-proposals/{skill_id}/map", post(map_proposal_route))
+		.route_layer(middleware::from_fn(auth::admin_middleware))
 		.route("/skills", post(update_user_skills_route))
+		.route("/skills/proposals", get(get_user_proposals_route))
+		.route("/skills/proposals", post(propose_skill_route))
 		.route("/skills/suggestions", get(get_suggestions))
+		.route("/skills/search", get(search_skills_route))
 		.route_layer(middleware::from_fn(auth::middleware))
 		.route("/skills/{user_id}", get(get_user_skills_route))
+		.route("/skills/{user_id}/usage", get(get_user_skill_usage_route))
 }
 
-async fn get_suggestions(State(state): State<SkillsState>) -> ApiResult<Json<Vec<Skill>>> {
+/// The whole catalog, each skill with the aliases that resolve to it
+async fn get_suggestions(State(state): State<SkillsState>) -> ApiResult<Json<Vec<CatalogSkill>>> {
 	let skills = state
 		.skills_repository
-		.list()
+		.catalog()
 		.await
 		.map_err(|_| SkillsApiError::GetSuggestions())?;
 
-	Ok((StatusCode::CREATED, Json(skills)))
+	Ok((StatusCode::OK, Json(skills)))
+}
+
+async fn search_skills_route(
+	Extension(current_user): Extension<CurrentUser>,
+	Query(query): Query<SearchSkillsQuery>,
+	State(state): State<SkillsState>,
+) -> ApiResult<Json<Vec<SkillSearchResult>>> {
+	query.validate().map_err(SkillsApiError::InvalidQuery)?;
+
+	let skills = state
+		.skills_repository
+		.search(&current_user.user_id, &query.q, query.limit)
+		.await
+		.map_err(|_| SkillsApiError::Search())?;
+
+	Ok((StatusCode::OK, Json(skills)))
 }
 
 async fn get_user_skills_route(
 	Path(user_id): Path<i64>,
 	State(state): State<SkillsState>,
-) -> ApiResult<Json<Vec<Skill>>> {
+) -> ApiResult<Json<Vec<UserSkill>>> {
 	let user_skills = state
 		.skills_repository
 		.get_user_skills(&user_id)
@@ -67,26 +120,364 @@ async fn get_user_skills_route(
 	Ok((StatusCode::CREATED, Json(user_skills)))
 }
 
+async fn get_user_skill_usage_route(
+	Path(user_id): Path<i64>,
+	State(state): State<SkillsState>,
+) -> ApiResult<Json<Vec<SkillUsage>>> {
+	let user_skills = state
+		.skills_repository
+		.get_user_skills(&user_id)
+		.await
+		.map_err(|_| SkillsApiError::GetUserSkills())?;
+
+	let experience = state
+		.experience_repository
+		.get_by_user_id(user_id, None)
+		.await
+		.map_err(|_| SkillsApiError::GetUserSkills())?;
+
+	let usage = SkillUsage::for_skills(user_skills, &experience, Utc::now().date_naive());
+
+	Ok((StatusCode::OK, Json(usage)))
+}
+
 #[debug_handler]
 async fn update_user_skills_route(
 	Extension(current_user): Extension<CurrentUser>,
 	State(state): State<SkillsState>,
 	ValidatedJson(request): ValidatedJson<UpdateUserSkillsDto>,
 ) -> ApiResult<()> {
-	state
-		.skills_repository
-		.update_user_skills(&current_user.user_id, request.skills)
+	let mut transaction = state
+		.history_repository
+		.track(&current_user.user_id, &current_user.user_id, &[Section::Skills])
+		.await
+		.map_err(|_| SkillsApiError::UpdateUserSkills())?;
+
+	SkillsRepository::update_user_skills(&mut transaction, &current_user.user_id, request.skills)
+		.await
+		.map_err(|_| SkillsApiError::UpdateUserSkills())?;
+
+	transaction
+		.commit()
 		.await
 		.map_err(|_| SkillsApiError::UpdateUserSkills())?;
 
 	Ok((StatusCode::OK, ()))
 }
 
+async fn get_user_proposals_route(
+	Extension(current_user): Extension<CurrentUser>,
+	State(state): State<SkillsState>,
+) -> ApiResult<Json<Vec<SkillProposal>>> {
+	let proposals = state
+		.skills_repository
+		.get_user_proposals(&current_user.user_id)
+		.await
+		.map_err(|_| SkillsApiError::GetProposals())?;
+
+	Ok((StatusCode::OK, Json(proposals)))
+}
+
+/// Adds a skill the catalog may not have yet to the user's skills
+async fn propose_skill_route(
+	Extension(current_user): Extension<CurrentUser>,
+	State(state): State<SkillsState>,
+	ValidatedJson(request): ValidatedJson<ProposeSkillDto>,
+) -> ApiResult<Json<ProposedSkill>> {
+	let name = request.normalized_name();
+	if name.is_empty() {
+		return Err(SkillsApiError::InvalidName(request.name))?;
+	}
+
+	let pending = state
+		.skills_repository
+		.count_pending_proposals(&current_user.user_id)
+		.await
+		.map_err(|_| SkillsApiError::Propose())?;
+
+	if pending >= MAX_PENDING_PROPOSALS {
+		return Err(SkillsApiError::TooManyProposals())?;
+	}
+
+	let mut transaction = state
+		.history_repository
+		.track(&current_user.user_id, &current_user.user_id, &[Section::Skills])
+		.await
+		.map_err(|_| SkillsApiError::Propose())?;
+
+	let skill = SkillsRepository::propose(&mut transaction, &current_user.user_id, &name, AUTO_APPROVE_PROPOSERS)
+		.await
+		.map_err(|_| SkillsApiError::Propose())?
+		.ok_or(SkillsApiError::Rejected(name))?;
+
+	transaction.commit().await.map_err(|_| SkillsApiError::Propose())?;
+
+	Ok((StatusCode::CREATED, Json(skill)))
+}
+
+async fn get_pending_proposals_route(State(state): State<SkillsState>) -> ApiResult<Json<Vec<PendingSkill>>> {
+	let skills = state
+		.skills_repository
+		.get_pending_proposals()
+		.await
+		.map_err(|_| SkillsApiError::GetProposals())?;
+
+	Ok((StatusCode::OK, Json(skills)))
+}
+
+async fn approve_proposal_route(
+	State(state): State<SkillsState>,
+	Path(skill_id): Path<i64>,
+) -> ApiResult<Json<CatalogSkill>> {
+	let skill = state
+		.skills_repository
+		.approve(&skill_id)
+		.await
+		.map_err(|_| SkillsApiError::Moderate())?
+		.ok_or(SkillsApiError::ProposalNotFound(skill_id))?;
+
+	Ok((StatusCode::OK, Json(skill)))
+}
+
+async fn reject_proposal_route(State(state): State<SkillsState>, Path(skill_id): Path<i64>) -> ApiResult<()> {
+	let rejected = state
+		.skills_repository
+		.reject(&skill_id)
+		.await
+		.map_err(|_| SkillsApiError::Moderate())?;
+
+	if !rejected {
+		return Err(SkillsApiError::ProposalNotFound(skill_id))?;
+	}
+
+	Ok((StatusCode::OK, ()))
+}
+
+/// Folds the proposal into the skill it duplicates, so its name becomes an alias of that skill
+async fn map_proposal_route(
+	State(state): State<SkillsState>,
+	Path(skill_id): Path<i64>,
+	ValidatedJson(request): ValidatedJson<MapSkillProposalDto>,
+) -> ApiResult<Json<CatalogSkill>> {
+	let target_id = match (request.skill_id, request.alias) {
+		(Some(target_id), _) => target_id,
+		(None, Some(alias)) => state
+			.skills_repository
+			.find_alias(&alias)
+			.await
+			.map_err(|_| SkillsApiError::Moderate())?
+			.ok_or(SkillsApiError::InvalidAlias(alias))?,
+		(None, None) => return Err(SkillsApiError::MissingMapTarget())?,
+	};
+
+	if state
+		.skills_repository
+		.get_status(&skill_id)
+		.await
+		.map_err(|_| SkillsApiError::Moderate())?
+		!= Some(SkillStatus::Pending)
+	{
+		return Err(SkillsApiError::ProposalNotFound(skill_id))?;
+	}
+
+	if state
+		.skills_repository
+		.get_status(&target_id)
+		.await
+		.map_err(|_| SkillsApiError::Moderate())?
+		!= Some(SkillStatus::Approved)
+	{
+		return Err(SkillsApiError::NotFound(target_id))?;
+	}
+
+	let skill = state
+		.skills_repository
+		.merge(&target_id, &[skill_id])
+		.await
+		.map_err(|_| SkillsApiError::Moderate())?
+		.ok_or(SkillsApiError::NotFound(target_id))?;
+
+	Ok((StatusCode::OK, Json(skill)))
+}
+
+async fn update_skill_route(
+	State(state): State<SkillsState>,
+	Path(skill_id): Path<i64>,
+	ValidatedJson(request): ValidatedJson<UpdateSkillDto>,
+) -> ApiResult<Json<CatalogSkill>> {
+	if state
+		.skills_repository
+		.is_name_taken(&request.name, &skill_id)
+		.await
+		.map_err(|_| SkillsApiError::Update())?
+	{
+		return Err(SkillsApiError::NameTaken(request.name))?;
+	}
+
+	if let Some(parent_id) = request.parent_id {
+		state
+			.skills_repository
+			.find(&parent_id)
+			.await
+			.map_err(|_| SkillsApiError::Update())?
+			.ok_or(SkillsApiError::NotFound(parent_id))?;
+
+		if state
+			.skills_repository
+			.is_descendant(&parent_id, &skill_id)
+			.await
+			.map_err(|_| SkillsApiError::Update())?
+		{
+			return Err(SkillsApiError::ParentCycle(parent_id))?;
+		}
+	}
+
+	let skill = state
+		.skills_repository
+		.update(&skill_id, request)
+		.await
+		.map_err(|_| SkillsApiError::Update())?
+		.ok_or(SkillsApiError::NotFound(skill_id))?;
+
+	Ok((StatusCode::OK, Json(skill)))
+}
+
+async fn merge_skills_route(
+	State(state): State<SkillsState>,
+	Path(skill_id): Path<i64>,
+	ValidatedJson(request): ValidatedJson<MergeSkillsDto>,
+) -> ApiResult<Json<CatalogSkill>> {
+	if request.duplicate_ids.contains(&skill_id) {
+		return Err(SkillsApiError::MergeIntoItself(skill_id))?;
+	}
+
+	let skill = state
+		.skills_repository
+		.merge(&skill_id, &request.duplicate_ids)
+		.await
+		.map_err(|_| SkillsApiError::Merge())?
+		.ok_or(SkillsApiError::NotFound(skill_id))?;
+
+	Ok((StatusCode::OK, Json(skill)))
+}
+
+async fn add_alias_route(
+	State(state): State<SkillsState>,
+	Path((skill_id, alias)): Path<(i64, String)>,
+) -> ApiResult<Json<CatalogSkill>> {
+	let alias = alias.trim().to_lowercase();
+	if alias.is_empty() || alias.len() > 255 {
+		return Err(SkillsApiError::InvalidAlias(alias))?;
+	}
+
+	state
+		.skills_repository
+		.find(&skill_id)
+		.await
+		.map_err(|_| SkillsApiError::UpdateAliases())?
+		.ok_or(SkillsApiError::NotFound(skill_id))?;
+
+	if state
+		.skills_repository
+		.is_name_taken(&alias, &skill_id)
+		.await
+		.map_err(|_| SkillsApiError::UpdateAliases())?
+	{
+		return Err(SkillsApiError::NameTaken(alias))?;
+	}
+
+	state
+		.skills_repository
+		.add_alias(&skill_id, &alias)
+		.await
+		.map_err(|_| SkillsApiError::UpdateAliases())?;
+
+	skill_response(&state, &skill_id).await
+}
+
+async fn delete_alias_route(
+	State(state): State<SkillsState>,
+	Path((skill_id, alias)): Path<(i64, String)>,
+) -> ApiResult<Json<CatalogSkill>> {
+	state
+		.skills_repository
+		.delete_alias(&skill_id, &alias)
+		.await
+		.map_err(|_| SkillsApiError::UpdateAliases())?;
+
+	skill_response(&state, &skill_id).await
+}
+
+async fn skill_response(state: &SkillsState, skill_id: &i64) -> ApiResult<Json<CatalogSkill>> {
+	let skill = state
+		.skills_repository
+		.find(skill_id)
+		.await
+		.map_err(|_| SkillsApiError::UpdateAliases())?
+		.ok_or(SkillsApiError::NotFound(*skill_id))?;
+
+	Ok((StatusCode::OK, Json(skill)))
+}
+
 #[derive(Debug, Error)]
 pub enum SkillsApiError {
+	#[error("Invalid skill search query: {0}")]
+	InvalidQuery(ValidationErrors),
+
 	#[error("Failed to get skills suggestions")]
 	GetSuggestions(),
 
+	#[error("Failed to search skills")]
+	Search(),
+
+	#[error("Skill not found: {0}")]
+	NotFound(i64),
+
+	#[error("Skill {0} can't be merged into itself")]
+	MergeIntoItself(i64),
+
+	#[error("A skill is already named or aliased so: {0}")]
+	NameTaken(String),
+
+	#[error("Skill {0} is part of the skill, so it can't be its parent")]
+	ParentCycle(i64),
+
+	#[error("Invalid alias: {0}")]
+	InvalidAlias(String),
+
+	#[error("Invalid skill name: {0}")]
+	InvalidName(String),
+
+	#[error("Too many skill proposals are waiting on a moderator")]
+	TooManyProposals(),
+
+	#[error("The skill was rejected by a moderator: {0}")]
+	Rejected(String),
+
+	#[error("No pending skill proposal: {0}")]
+	ProposalNotFound(i64),
+
+	#[error("Either a skill id or an alias to map the proposal to is required")]
+	MissingMapTarget(),
+
+	#[error("Failed to propose skill")]
+	Propose(),
+
+	#[error("Failed to get skill proposals")]
+	GetProposals(),
+
+	#[error("Failed to moderate skill proposal")]
+	Moderate(),
+
+	#[error("Failed to update skill")]
+	Update(),
+
+	#[error("Failed to merge skills")]
+	Merge(),
+
+	#[error("Failed to update skill aliases")]
+	UpdateAliases(),
+
 	#[error("Failed to get user skills")]
 	GetUserSkills(),
 
@@ -97,8 +488,26 @@ pub enum SkillsApiError {
 impl IntoResponse for SkillsApiError {
 	fn into_response(self) -> Response {
 		let status_code = match self {
-			Self::GetUserSkills() | Self::GetSuggestions() => StatusCode::INTERNAL_SERVER_ERROR,
-			Self::UpdateUserSkills() => StatusCode::BAD_REQUEST,
+			Self::GetUserSkills()
+			| Self::GetSuggestions()
+			| Self::Search()
+			| Self::Update()
+			| Self::Merge()
+			| Self::UpdateAliases()
+			| Self::Propose()
+			| Self::GetProposals()
+			| Self::Moderate() => StatusCode::INTERNAL_SERVER_ERROR,
+			Self::InvalidQuery(_)
+			| Self::InvalidName(_)
+			| Self::Rejected(_)
+			| Self::MissingMapTarget()
+			| Self::UpdateUserSkills()
+			| Self::MergeIntoItself(_)
+			| Self::ParentCycle(_)
+			| Self::InvalidAlias(_) => StatusCode::BAD_REQUEST,
+			Self::NotFound(_) | Self::ProposalNotFound(_) => StatusCode::NOT_FOUND,
+			Self::TooManyProposals() => StatusCode::TOO_MANY_REQUESTS,
+			Self::NameTaken(_) => StatusCode::CONFLICT,
 		};
 
 		log::error!("{self:?}");
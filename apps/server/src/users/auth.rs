@@ -1,13 +1,15 @@
+use std::sync::Arc;
+
 use anyhow::{anyhow, Error, Result};
-use axum::{extract::Request, http::StatusCode, middleware::Next, response::Response};
+use axum::{extract::Request, http::StatusCode, middleware::Next, response::Response, Extension};
 use chrono::{Duration, Utc};
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, TokenData, Validation};
 use serde::{Deserialize, Serialize};
 use tower_cookies::{Cookie, Cookies};
 
-use crate::settings::SETTINGS;
+use crate::{database::Database, settings::SETTINGS};
 
-use super::repository::User;
+use super::repository::{User, UsersRepostory};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CurrentUser {
@@ -16,16 +18,6 @@ pub struct CurrentUser {
 	pub username: String,
 }
 
-impl CurrentUser {
-	pub fn new(user_id: i64, email: &str, username: &str) -> Self {
-		Self {
-			user_id,
-			email: email.to_string(),
-			username: username.to_string(),
-		}
-	}
-}
-
 impl From<User> for CurrentUser {
 	fn from(user: User) -> Self {
 		Self {
@@ -49,6 +41,28 @@ pub async fn middleware(cookies: Cookies, mut request: Request, next: Next) -> R
 	}
 }
 
+/// Must be layered inside [`middleware`], which provides the current user
+pub async fn admin_middleware(
+	Extension(database): Extension<Arc<Database>>,
+	Extension(current_user): Extension<CurrentUser>,
+	request: Request,
+	next: Next,
+) -> Result<Response, StatusCode> {
+	let is_admin = UsersRepostory::new(&database)
+		.is_admin(&current_user.user_id)
+		.await
+		.map_err(|error| {
+			log::error!("{error:?}");
+			StatusCode::INTERNAL_SERVER_ERROR
+		})?;
+
+	if is_admin {
+		Ok(next.run(request).await)
+	} else {
+		Err(StatusCode::FORBIDDEN)
+	}
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Claims {
 	pub exp: i64,
@@ -57,8 +71,7 @@ pub struct Claims {
 }
 
 pub fn authorize_jwt(cookies: &Cookies, user: &CurrentUser) -> Result<()> {
-	let access_token = create_jwt(user.user_id, &user.email, &user.username)
-		.map_err(|error| anyhow!(error).context("Failed to create JWT"))?;
+	let access_token = create_jwt(user.clone()).map_err(|error| anyhow!(error).context("Failed to create JWT"))?;
 
 	let access_token_cookie = Cookie::build(("access_token", access_token))
 		.path("/")
@@ -71,12 +84,12 @@ pub fn authorize_jwt(cookies: &Cookies, user: &CurrentUser) -> Result<()> {
 	Ok(())
 }
 
-fn create_jwt(user_id: i64, email: &str, username: &str) -> Result<String, Error> {
+fn create_jwt(user: CurrentUser) -> Result<String, Error> {
 	let now = Utc::now().timestamp();
 	let claims = Claims {
 		exp: now + Duration::hours(12).num_seconds(),
 		iat: now,
-		user: CurrentUser::new(user_id, email, username),
+		user,
 	};
 
 	encode(
@@ -2,6 +2,13 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
+use crate::{
+	completeness::repository::Completeness,
+	experience::tenure::Tenure,
+	i18n::validate_locale,
+	validation::{double_option, validate_https_url},
+};
+
 use super::repository::User;
 
 #[derive(Debug, Serialize)]
@@ -11,6 +18,15 @@ pub struct UserResponse {
 	pub email: String,
 	pub username: String,
 	pub created_at: DateTime<Utc>,
+	pub headline: Option<String>,
+	pub bio: Option<String>,
+	pub default_locale: String,
+	pub avatar_url: Option<String>,
+	pub location_name: Option<String>,
+	pub location_id: Option<i64>,
+	/// Only filled in for the signed in user's own profile
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub completeness: Option<Completeness>,
 }
 
 #[derive(Debug, Serialize)]
@@ -19,18 +35,108 @@ pub struct PublicUserResponse {
 	pub id: i64,
 	pub email: String,
 	pub username: String,
+	pub headline: Option<String>,
+	pub bio: Option<String>,
+	/// The Markdown bio rendered to sanitized HTML, filled in where the profile is served for display
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub bio_html: Option<String>,
+	pub avatar_url: Option<String>,
+	pub location_name: Option<String>,
+	pub location_id: Option<i64>,
+	/// Locale the headline and bio were served in
+	pub locale: String,
+	/// Filled in where the user's experience is loaded alongside
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub tenure: Option<Tenure>,
 }
 
 impl PublicUserResponse {
-	pub fn from_user(user: User) -> Self {
+	pub fn from_user(user: User, locale: String) -> Self {
 		Self {
 			id: user.id,
 			email: user.email,
 			username: user.username,
+			headline: user.headline,
+			bio: user.bio,
+			bio_html: None,
+			avatar_url: user.avatar_url,
+			location_name: user.location_name,
+			location_id: user.location_id,
+			locale,
+			tenure: None,
 		}
 	}
 }
 
+/// Fields left out are kept as they are, and fields sent as `null` are cleared
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+#[allow(clippy::option_option)]
+pub struct UpdateProfileRequest {
+	#[serde(default, deserialize_with = "double_option")]
+	#[validate(length(min = 1, max = 255, message = "Headline must be between 1 and 255 characters"))]
+	pub headline: Option<Option<String>>,
+	#[serde(default, deserialize_with = "double_option")]
+	#[validate(length(min = 1, max = 5000, message = "Bio must be between 1 and 5000 characters"))]
+	pub bio: Option<Option<String>>,
+	/// Locale the untranslated profile text is written in, left unchanged when omitted
+	#[validate(custom(function = "validate_locale"))]
+	pub default_locale: Option<String>,
+	#[serde(default, deserialize_with = "double_option")]
+	#[validate(custom(function = "validate_https_url"), length(max = 2048))]
+	pub avatar_url: Option<Option<String>>,
+	#[serde(default, deserialize_with = "double_option")]
+	#[validate(length(min = 1, max = 255, message = "Wrong location name length"))]
+	pub location_name: Option<Option<String>>,
+	/// A city picked from autocomplete; when omitted or unknown, one is resolved from the location name
+	#[serde(default, deserialize_with = "double_option")]
+	pub location_id: Option<Option<i64>>,
+}
+
+impl UpdateProfileRequest {
+	/// Names of the fields sent as `null`
+	pub fn cleared(&self) -> Vec<String> {
+		[
+			("headline", matches!(self.headline, Some(None))),
+			("bio", matches!(self.bio, Some(None))),
+			("avatar_url", matches!(self.avatar_url, Some(None))),
+			("location_name", matches!(self.location_name, Some(None))),
+			("location_id", matches!(self.location_id, Some(None))),
+		]
+		.into_iter()
+		.filter(|(_, is_cleared)| *is_cleared)
+		.map(|(field, _)| field.to_string())
+		.collect()
+	}
+}
+
+/// Fields left out are kept as they are, and fields sent as `null` are cleared
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+#[allow(clippy::option_option)]
+pub struct ProfileTranslationRequest {
+	#[serde(default, deserialize_with = "double_option")]
+	#[validate(length(min = 1, max = 255, message = "Headline must be between 1 and 255 characters"))]
+	pub headline: Option<Option<String>>,
+	#[serde(default, deserialize_with = "double_option")]
+	#[validate(length(min = 1, max = 5000, message = "Bio must be between 1 and 5000 characters"))]
+	pub bio: Option<Option<String>>,
+}
+
+impl ProfileTranslationRequest {
+	/// Names of the fields sent as `null`
+	pub fn cleared(&self) -> Vec<String> {
+		[
+			("headline", matches!(self.headline, Some(None))),
+			("bio", matches!(self.bio, Some(None))),
+		]
+		.into_iter()
+		.filter(|(_, is_cleared)| *is_cleared)
+		.map(|(field, _)| field.to_string())
+		.collect()
+	}
+}
+
 #[derive(Debug, Deserialize, Validate)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateUserRequest {
@@ -58,6 +164,7 @@ pub struct ResendVerificationRequest {
 	pub user_id: i64,
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Deserialize, Validate)]
 #[serde(rename_all = "camelCase")]
 pub struct ResetPasswordRequest {
@@ -65,6 +172,7 @@ pub struct ResetPasswordRequest {
 	pub email: String,
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Deserialize, Validate)]
 #[serde(rename_all = "camelCase")]
 pub struct VerifyPasswordRequest {
@@ -1,6 +1,6 @@
 pub mod auth;
+pub mod dtos;
+pub mod repository;
 pub mod routes;
 
-mod dtos;
 mod password;
-mod repository;
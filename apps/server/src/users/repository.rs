@@ -3,11 +3,14 @@ use std::sync::Arc;
 use anyhow::anyhow;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::postgres::PgQueryResult;
+use sqlx::{postgres::PgQueryResult, PgExecutor};
 
-use crate::database::Database;
+use crate::{
+	database::Database,
+	i18n::{ContentLocales, NegotiatedLocale, RequestedLocales, DEFAULT_LOCALE},
+};
 
-use super::dtos::UserResponse;
+use super::dtos::{ProfileTranslationRequest, UpdateProfileRequest, UserResponse};
 
 #[derive(Clone)]
 pub struct UsersRepostory {
@@ -22,6 +25,16 @@ impl UsersRepostory {
 		}
 	}
 
+	/// Read on every admin request rather than trusted from the token, so revoking admin takes effect at once
+	pub async fn is_admin(&self, id: &i64) -> anyhow::Result<bool> {
+		let is_admin = sqlx::query_scalar!("SELECT is_admin FROM users WHERE id = $1", id)
+			.fetch_optional(&*self.database.pool)
+			.await
+			.map_err(|error| anyhow!(error).context("Failed to check admin"))?;
+
+		Ok(is_admin.unwrap_or(false))
+	}
+
 	pub async fn find_user_by_id(&self, id: &i64) -> anyhow::Result<User> {
 		let user = sqlx::query_as!(User, "SELECT * FROM users WHERE id = $1", id)
 			.fetch_one(&*self.database.pool)
@@ -129,6 +142,170 @@ impl UsersRepostory {
 
 		Ok(query_result)
 	}
+
+	pub async fn update_profile(
+		executor: impl PgExecutor<'_>,
+		user_id: &i64,
+		request: UpdateProfileRequest,
+	) -> anyhow::Result<User> {
+		let user = sqlx::query_as!(
+			User,
+			r#"
+				UPDATE users
+				SET
+					headline = CASE WHEN 'headline' = ANY($8) THEN NULL ELSE COALESCE($2, headline) END,
+					bio = CASE WHEN 'bio' = ANY($8) THEN NULL ELSE COALESCE($3, bio) END,
+					default_locale = COALESCE($4, default_locale),
+					avatar_url = CASE WHEN 'avatar_url' = ANY($8) THEN NULL ELSE COALESCE($5, avatar_url) END,
+					location_name = CASE WHEN 'location_name' = ANY($8) THEN NULL ELSE COALESCE($6, location_name) END,
+					location_id = CASE WHEN 'location_id' = ANY($8) THEN NULL ELSE COALESCE($7, location_id) END
+				WHERE id = $1 RETURNING *
+			"#,
+			user_id,
+			request.headline.clone().flatten(),
+			request.bio.clone().flatten(),
+			request.default_locale,
+			request.avatar_url.clone().flatten(),
+			request.location_name.clone().flatten(),
+			request.location_id.flatten(),
+			&request.cleared()
+		)
+		.fetch_one(executor)
+		.await
+		.map_err(|error| anyhow!(error).context("Failed to update profile"))?;
+
+		Ok(user)
+	}
+
+	/// Every locale the user's profile or experience has text in, `None` for unknown users
+	pub async fn get_content_locales(&self, user_id: &i64) -> anyhow::Result<Option<ContentLocales>> {
+		let content_locales = sqlx::query_as!(
+			ContentLocales,
+			r#"
+				SELECT
+					u.default_locale,
+					ARRAY(
+						SELECT locale FROM user_translations WHERE user_id = u.id
+						UNION
+						SELECT et.locale
+						FROM experience_translations et
+						JOIN experience e ON e.id = et.experience_id
+						WHERE e.user_id = u.id
+					) AS "translated!"
+				FROM users u
+				WHERE u.id = $1
+			"#,
+			user_id
+		)
+		.fetch_optional(&*self.database.pool)
+		.await
+		.map_err(|error| anyhow!(error).context("Failed to get content locales"))?;
+
+		Ok(content_locales)
+	}
+
+	pub async fn negotiate_locale(
+		&self,
+		user_id: &i64,
+		requested: &RequestedLocales,
+	) -> anyhow::Result<NegotiatedLocale> {
+		let content_locales = self
+			.get_content_locales(user_id)
+			.await?
+			.unwrap_or_else(|| ContentLocales {
+				default_locale: DEFAULT_LOCALE.to_string(),
+				translated: Vec::new(),
+			});
+
+		Ok(requested.negotiate(&content_locales))
+	}
+
+	/// Swaps the user's profile text for the negotiated translation, keeping untranslated fields as they are
+	pub async fn localize(&self, mut user: User, locale: &NegotiatedLocale) -> anyhow::Result<User> {
+		let Some(locale) = locale.translation() else {
+			return Ok(user);
+		};
+
+		let translation = sqlx::query_as!(
+			ProfileTranslation,
+			"SELECT locale, headline, bio FROM user_translations WHERE user_id = $1 AND locale = $2",
+			user.id,
+			locale
+		)
+		.fetch_optional(&*self.database.pool)
+		.await
+		.map_err(|error| anyhow!(error).context("Failed to get profile translation"))?;
+
+		if let Some(translation) = translation {
+			user.headline = translation.headline.or(user.headline);
+			user.bio = translation.bio.or(user.bio);
+		}
+
+		Ok(user)
+	}
+
+	pub async fn get_translations(&self, user_id: &i64) -> anyhow::Result<Vec<ProfileTranslation>> {
+		let translations = sqlx::query_as!(
+			ProfileTranslation,
+			"SELECT locale, headline, bio FROM user_translations WHERE user_id = $1 ORDER BY locale",
+			user_id
+		)
+		.fetch_all(&*self.database.pool)
+		.await
+		.map_err(|error| anyhow!(error).context("Failed to get profile translations"))?;
+
+		Ok(translations)
+	}
+
+	pub async fn upsert_translation(
+		executor: impl PgExecutor<'_>,
+		user_id: &i64,
+		locale: &str,
+		request: ProfileTranslationRequest,
+	) -> anyhow::Result<ProfileTranslation> {
+		let translation = sqlx::query_as!(
+			ProfileTranslation,
+			r#"
+				INSERT INTO user_translations (user_id, locale, headline, bio)
+				VALUES ($1, $2, $3, $4)
+				ON CONFLICT (user_id, locale) DO UPDATE
+				SET
+					headline = CASE
+						WHEN 'headline' = ANY($5) THEN NULL
+						ELSE COALESCE(EXCLUDED.headline, user_translations.headline)
+					END,
+					bio = CASE WHEN 'bio' = ANY($5) THEN NULL ELSE COALESCE(EXCLUDED.bio, user_translations.bio) END
+				RETURNING locale, headline, bio
+			"#,
+			user_id,
+			locale,
+			request.headline.clone().flatten(),
+			request.bio.clone().flatten(),
+			&request.cleared()
+		)
+		.fetch_one(executor)
+		.await
+		.map_err(|error| anyhow!(error).context("Failed to save profile translation"))?;
+
+		Ok(translation)
+	}
+
+	pub async fn delete_translation(
+		executor: impl PgExecutor<'_>,
+		user_id: &i64,
+		locale: &str,
+	) -> anyhow::Result<PgQueryResult> {
+		let query_result = sqlx::query!(
+			"DELETE FROM user_translations WHERE user_id = $1 AND locale = $2",
+			user_id,
+			locale
+		)
+		.execute(executor)
+		.await
+		.map_err(|error: sqlx::Error| anyhow!(error).context("Failed to delete profile translation"))?;
+
+		Ok(query_result)
+	}
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, sqlx::FromRow)]
@@ -139,6 +316,22 @@ pub struct User {
 	pub password: String,
 	pub is_verified: bool,
 	pub created_at: DateTime<Utc>,
+	pub headline: Option<String>,
+	pub bio: Option<String>,
+	pub default_locale: String,
+	pub avatar_url: Option<String>,
+	pub is_admin: bool,
+	pub location_name: Option<String>,
+	/// The city the location name resolved to
+	pub location_id: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileTranslation {
+	pub locale: String,
+	pub headline: Option<String>,
+	pub bio: Option<String>,
 }
 
 impl From<User> for UserResponse {
@@ -148,6 +341,13 @@ impl From<User> for UserResponse {
 			email: user.email,
 			username: user.username,
 			created_at: user.created_at,
+			headline: user.headline,
+			bio: user.bio,
+			default_locale: user.default_locale,
+			avatar_url: user.avatar_url,
+			location_name: user.location_name,
+			location_id: user.location_id,
+			completeness: None,
 		}
 	}
 }
@@ -2,33 +2,45 @@ use std::sync::Arc;
 
 use axum::{
 	extract::{Path, State},
-	http::StatusCode,
+	http::{header, StatusCode},
 	middleware,
 	response::{IntoResponse, Response},
-	routing::{get, patch, post},
+	routing::{delete, get, patch, post, put},
 	Extension, Json, Router,
 };
+use chrono::Utc;
 use thiserror::Error;
 use tower_cookies::{Cookie, Cookies};
 
 use crate::{
 	app::{ApiErrorResponse, ApiResult},
+	completeness::repository::CompletenessRepository,
 	database::Database,
-	services::email::EmailService,
+	experience::{repository::ExperienceRepository, tenure::Tenure},
+	history::repository::{HistoryRepository, Section},
+	i18n::{normalize_locale, RequestedLocales},
+	services::{email::EmailService, markdown::MarkdownService},
 	validation::ValidatedJson,
 };
 
 use super::{
 	auth::{self, authorize_jwt, CurrentUser},
-	dtos::{ChangePasswordRequest, CreateUserRequest, LoginUserRequest, PublicUserResponse, UserResponse},
+	dtos::{
+		ChangePasswordRequest, CreateUserRequest, LoginUserRequest, ProfileTranslationRequest, PublicUserResponse,
+		UpdateProfileRequest, UserResponse,
+	},
 	password,
-	repository::UsersRepostory,
+	repository::{ProfileTranslation, UsersRepostory},
 };
 
 #[derive(Clone)]
 pub struct UsersState {
 	pub users_repository: UsersRepostory,
 	pub email_service: EmailService,
+	pub history_repository: HistoryRepository,
+	pub completeness_repository: CompletenessRepository,
+	pub experience_repository: ExperienceRepository,
+	pub markdown_service: MarkdownService,
 }
 
 impl UsersState {
@@ -36,6 +48,10 @@ impl UsersState {
 		Self {
 			users_repository: UsersRepostory::new(database),
 			email_service: EmailService::new(),
+			history_repository: HistoryRepository::new(database),
+			completeness_repository: CompletenessRepository::new(database),
+			experience_repository: ExperienceRepository::new(database),
+			markdown_service: MarkdownService::new(database),
 		}
 	}
 }
@@ -44,6 +60,13 @@ pub fn init() -> Router<UsersState> {
 	Router::new()
 		.route("/users/me", get(get_me_route))
 		.route("/users/password/change", patch(password_change_route))
+		.route("/users/me/profile", patch(update_profile_route))
+		.route("/users/me/translations", get(get_profile_translations_route))
+		.route("/users/me/translations/{locale}", put(upsert_profile_translation_route))
+		.route(
+			"/users/me/translations/{locale}",
+			delete(delete_profile_translation_route),
+		)
 		.route_layer(middleware::from_fn(auth::middleware))
 		.route("/users/register", post(register_user_route))
 		.route("/users/login", post(login_user_route))
@@ -107,9 +130,10 @@ async fn logout_user_route(cookies: Cookies) -> ApiResult<()> {
 
 async fn get_me_route(
 	Extension(current_user): Extension<CurrentUser>,
-	State(UsersState { users_repository, .. }): State<UsersState>,
+	State(state): State<UsersState>,
 ) -> ApiResult<Json<UserResponse>> {
-	let user = users_repository
+	let user = state
+		.users_repository
 		.find_user_by_id(&current_user.user_id)
 		.await
 		.map_err(|_| UsersApiError::UserNotFound(current_user.user_id.to_string()))?;
@@ -118,20 +142,167 @@ async fn get_me_route(
 		return Err(UsersApiError::UserNotVerified(current_user.user_id.to_string()))?;
 	}
 
-	Ok((StatusCode::OK, Json(UserResponse::from(user))))
+	let completeness = state
+		.completeness_repository
+		.evaluate(&user.id)
+		.await
+		.map_err(|_| UsersApiError::FailedToLoadProfile())?;
+
+	let mut user_response = UserResponse::from(user);
+	user_response.completeness = Some(completeness);
+
+	Ok((StatusCode::OK, Json(user_response)))
 }
 
 async fn get_public_user_route(
 	Path(username): Path<String>,
+	requested_locales: RequestedLocales,
 	State(state): State<UsersState>,
-) -> ApiResult<Json<PublicUserResponse>> {
+) -> ApiResult<([(header::HeaderName, String); 1], Json<PublicUserResponse>)> {
 	let user = state
 		.users_repository
 		.find_user_by_username(&username)
 		.await
 		.map_err(|_| UsersApiError::UserNotFound(username.clone()))?;
 
-	Ok((StatusCode::OK, Json(PublicUserResponse::from_user(user))))
+	let locale = state
+		.users_repository
+		.negotiate_locale(&user.id, &requested_locales)
+		.await
+		.map_err(|_| UsersApiError::FailedToLoadProfile())?;
+
+	let user = state
+		.users_repository
+		.localize(user, &locale)
+		.await
+		.map_err(|_| UsersApiError::FailedToLoadProfile())?;
+
+	let experience = state
+		.experience_repository
+		.get_by_user_id(user.id, None)
+		.await
+		.map_err(|_| UsersApiError::FailedToLoadProfile())?;
+
+	let bio_html = match &user.bio {
+		Some(bio) => Some(
+			state
+				.markdown_service
+				.render(bio)
+				.await
+				.map_err(|_| UsersApiError::FailedToLoadProfile())?,
+		),
+		None => None,
+	};
+
+	let headers = [(header::CONTENT_LANGUAGE, locale.locale.clone())];
+
+	let mut user_response = PublicUserResponse::from_user(user, locale.locale);
+	user_response.bio_html = bio_html;
+	user_response.tenure = Some(Tenure::new(&experience, Utc::now().date_naive()));
+
+	Ok((StatusCode::OK, (headers, Json(user_response))))
+}
+
+async fn update_profile_route(
+	Extension(current_user): Extension<CurrentUser>,
+	State(state): State<UsersState>,
+	ValidatedJson(mut request): ValidatedJson<UpdateProfileRequest>,
+) -> ApiResult<Json<UserResponse>> {
+	request.default_locale = request.default_locale.as_deref().and_then(normalize_locale);
+	// A new location name without a picked city resolves again, rather than keeping the previous city
+	if request.location_name.is_some() && request.location_id.is_none() {
+		request.location_id = Some(None);
+	}
+
+	let mut transaction = state
+		.history_repository
+		.track(&current_user.user_id, &current_user.user_id, &[Section::Profile])
+		.await
+		.map_err(|_| UsersApiError::FailedToUpdateProfile())?;
+
+	let user = UsersRepostory::update_profile(&mut **transaction, &current_user.user_id, request)
+		.await
+		.map_err(|_| UsersApiError::FailedToUpdateProfile())?;
+
+	transaction
+		.commit()
+		.await
+		.map_err(|_| UsersApiError::FailedToUpdateProfile())?;
+
+	Ok((StatusCode::OK, Json(UserResponse::from(user))))
+}
+
+async fn get_profile_translations_route(
+	Extension(current_user): Extension<CurrentUser>,
+	State(state): State<UsersState>,
+) -> ApiResult<Json<Vec<ProfileTranslation>>> {
+	let translations = state
+		.users_repository
+		.get_translations(&current_user.user_id)
+		.await
+		.map_err(|_| UsersApiError::FailedToUpdateProfile())?;
+
+	Ok((StatusCode::OK, Json(translations)))
+}
+
+async fn upsert_profile_translation_route(
+	Extension(current_user): Extension<CurrentUser>,
+	Path(locale): Path<String>,
+	State(state): State<UsersState>,
+	ValidatedJson(request): ValidatedJson<ProfileTranslationRequest>,
+) -> ApiResult<Json<ProfileTranslation>> {
+	let user = state
+		.users_repository
+		.find_user_by_id(&current_user.user_id)
+		.await
+		.map_err(|_| UsersApiError::UserNotFound(current_user.user_id.to_string()))?;
+
+	// The untranslated profile text already is the default locale's variant
+	let locale = normalize_locale(&locale)
+		.filter(|locale| *locale != user.default_locale)
+		.ok_or(UsersApiError::InvalidLocale(locale))?;
+
+	let mut transaction = state
+		.history_repository
+		.track(&current_user.user_id, &current_user.user_id, &[Section::Translations])
+		.await
+		.map_err(|_| UsersApiError::FailedToUpdateProfile())?;
+
+	let translation = UsersRepostory::upsert_translation(&mut **transaction, &current_user.user_id, &locale, request)
+		.await
+		.map_err(|_| UsersApiError::FailedToUpdateProfile())?;
+
+	transaction
+		.commit()
+		.await
+		.map_err(|_| UsersApiError::FailedToUpdateProfile())?;
+
+	Ok((StatusCode::OK, Json(translation)))
+}
+
+async fn delete_profile_translation_route(
+	Extension(current_user): Extension<CurrentUser>,
+	Path(locale): Path<String>,
+	State(state): State<UsersState>,
+) -> ApiResult<()> {
+	let locale = normalize_locale(&locale).ok_or(UsersApiError::InvalidLocale(locale))?;
+
+	let mut transaction = state
+		.history_repository
+		.track(&current_user.user_id, &current_user.user_id, &[Section::Translations])
+		.await
+		.map_err(|_| UsersApiError::FailedToUpdateProfile())?;
+
+	UsersRepostory::delete_translation(&mut **transaction, &current_user.user_id, &locale)
+		.await
+		.map_err(|_| UsersApiError::FailedToUpdateProfile())?;
+
+	transaction
+		.commit()
+		.await
+		.map_err(|_| UsersApiError::FailedToUpdateProfile())?;
+
+	Ok((StatusCode::OK, ()))
 }
 
 async fn password_change_route(
@@ -191,17 +362,28 @@ pub enum UsersApiError {
 
 	#[error("Failed to change password")]
 	FailedToChangePassword(),
+
+	#[error("Invalid locale: {0}")]
+	InvalidLocale(String),
+
+	#[error("Failed to load profile")]
+	FailedToLoadProfile(),
+
+	#[error("Failed to update profile")]
+	FailedToUpdateProfile(),
 }
 
 impl IntoResponse for UsersApiError {
 	fn into_response(self) -> Response {
 		let status_code = match self {
-			Self::UserNotVerified(_) | Self::WrongPassword() => StatusCode::BAD_REQUEST,
+			Self::UserNotVerified(_) | Self::WrongPassword() | Self::InvalidLocale(_) => StatusCode::BAD_REQUEST,
 			Self::UserNotFound(_) => StatusCode::NOT_FOUND,
 			Self::EmailTaken(_) | Self::UsernameTaken(_) => StatusCode::CONFLICT,
-			Self::FailedToCreateUser() | Self::FailedToLoginUser() | Self::FailedToChangePassword() => {
-				StatusCode::INTERNAL_SERVER_ERROR
-			}
+			Self::FailedToCreateUser()
+			| Self::FailedToLoginUser()
+			| Self::FailedToChangePassword()
+			| Self::FailedToLoadProfile()
+			| Self::FailedToUpdateProfile() => StatusCode::INTERNAL_SERVER_ERROR,
 		};
 
 		log::error!("{self:?}");
@@ -1,11 +1,15 @@
+use std::collections::BTreeMap;
+
 use axum::{
 	extract::{rejection::JsonRejection, FromRequest, Json, Request},
 	http::StatusCode,
 	response::{IntoResponse, Response},
 };
-use serde::de::DeserializeOwned;
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize};
+use serde_json::Value;
 use thiserror::Error;
-use validator::{Validate, ValidationErrors};
+use url::Url;
+use validator::{Validate, ValidationError, ValidationErrors, ValidationErrorsKind};
 
 #[derive(Debug, Clone, Copy, Default)]
 pub struct ValidatedJson<T>(pub T);
@@ -39,9 +43,130 @@ impl IntoResponse for ServerError {
 		log::error!("{self}");
 
 		match self {
-			Self::ValidationError(_) => (StatusCode::BAD_REQUEST, self.to_string()),
-			Self::AxumJsonRejection(_) => (StatusCode::BAD_REQUEST, String::from("Server rejection")),
+			Self::ValidationError(errors) => {
+				(StatusCode::BAD_REQUEST, Json(ValidationErrorResponse::new(&errors))).into_response()
+			}
+			Self::AxumJsonRejection(_) => (StatusCode::BAD_REQUEST, String::from("Server rejection")).into_response(),
 		}
-		.into_response()
 	}
 }
+
+/// Attaches a struct level (`schema`) error to one of the struct's fields, so it's reported under that field
+/// instead of the whole object
+pub fn field_error(field: &'static str, mut error: ValidationError) -> ValidationError {
+	error.add_param("field".into(), &field);
+	error
+}
+
+/// For `#[serde(default, deserialize_with = "double_option")]` fields of partial updates, to tell a field left
+/// out (`None`, kept as is) from one sent as `null` (`Some(None)`, cleared)
+#[allow(clippy::option_option)]
+pub fn double_option<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+	T: Deserialize<'de>,
+	D: Deserializer<'de>,
+{
+	Option::<T>::deserialize(deserializer).map(Some)
+}
+
+/// Absolute `http` or `https` URLs only. These are rendered as links, and the `url` validator alone lets
+/// `javascript:` and `data:` URLs through.
+pub fn validate_http_url(url: &str) -> Result<(), ValidationError> {
+	validate_url_scheme(url, &["http", "https"], "URL must start with http:// or https://")
+}
+
+/// Absolute `https` URLs only, for images the site embeds, where a plain `http` one would be blocked as mixed content
+pub fn validate_https_url(url: &str) -> Result<(), ValidationError> {
+	validate_url_scheme(url, &["https"], "URL must start with https://")
+}
+
+fn validate_url_scheme(url: &str, schemes: &[&str], message: &'static str) -> Result<(), ValidationError> {
+	match Url::parse(url) {
+		Ok(url) if schemes.contains(&url.scheme()) && url.has_host() => Ok(()),
+		_ => Err(ValidationError::new("url").with_message(message.into())),
+	}
+}
+
+/// Validation failures keyed by the path of the offending field as the client sent it, e.g. `endDate`
+/// or `blocks[2].url`
+#[derive(Debug, Serialize)]
+pub struct ValidationErrorResponse {
+	message: String,
+	errors: BTreeMap<String, Vec<FieldError>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FieldError {
+	code: String,
+	message: String,
+}
+
+impl ValidationErrorResponse {
+	pub fn new(errors: &ValidationErrors) -> Self {
+		let mut response = Self {
+			message: String::from("Validation failed"),
+			errors: BTreeMap::new(),
+		};
+		response.collect("", errors);
+		response
+	}
+
+	fn collect(&mut self, path: &str, errors: &ValidationErrors) {
+		for (field, kind) in errors.errors() {
+			let field_path = if field == "__all__" {
+				path.to_string()
+			} else {
+				join_path(path, &to_camel_case(field))
+			};
+
+			match kind {
+				ValidationErrorsKind::Field(field_errors) => {
+					for error in field_errors {
+						let key = match error.params.get("field") {
+							Some(Value::String(field)) => join_path(path, field),
+							_ => field_path.clone(),
+						};
+
+						self.errors.entry(key).or_default().push(FieldError {
+							code: error.code.to_string(),
+							message: error.message.as_ref().unwrap_or(&error.code).to_string(),
+						});
+					}
+				}
+				ValidationErrorsKind::Struct(errors) => self.collect(&field_path, errors),
+				ValidationErrorsKind::List(items) => {
+					for (index, errors) in items {
+						self.collect(&format!("{field_path}[{index}]"), errors);
+					}
+				}
+			}
+		}
+	}
+}
+
+fn join_path(path: &str, field: &str) -> String {
+	if path.is_empty() {
+		field.to_string()
+	} else {
+		format!("{path}.{field}")
+	}
+}
+
+/// Field names come from the Rust structs, while clients see them renamed to camelCase
+fn to_camel_case(field: &str) -> String {
+	let mut camel_case = String::with_capacity(field.len());
+	let mut upper_next = false;
+
+	for char in field.chars() {
+		if char == '_' {
+			upper_next = true;
+		} else if upper_next {
+			camel_case.push(char.to_ascii_uppercase());
+			upper_next = false;
+		} else {
+			camel_case.push(char);
+		}
+	}
+
+	camel_case
+}
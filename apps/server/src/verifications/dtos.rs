@@ -0,0 +1,10 @@
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestVerificationDto {
+	/// A coworker, someone who lists the entry's company in their own experience or holds a confirmed work
+	/// email there
+	pub verifier_id: i64,
+}
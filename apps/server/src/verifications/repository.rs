@@ -0,0 +1,273 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::database::Database;
+
+#[derive(Clone)]
+pub struct VerificationsRepository {
+	pub(crate) database: Arc<Database>,
+}
+
+impl VerificationsRepository {
+	pub fn new(database: &Arc<Database>) -> Self {
+		Self {
+			database: Arc::clone(database),
+		}
+	}
+
+	/// Coworkers are verified users who list the entry's company, or hold a confirmed work email there.
+	/// Returns `None` when the entry doesn't exist or belongs to someone else.
+	pub async fn check_eligibility(
+		&self,
+		experience_id: &i64,
+		requester_id: &i64,
+		verifier_id: &i64,
+	) -> anyhow::Result<Option<Eligibility>> {
+		let eligibility = sqlx::query_as!(
+			Eligibility,
+			r#"
+				SELECT
+					e.company_id,
+					$3 <> e.user_id AND (
+						EXISTS (
+							SELECT 1
+							FROM experience c
+							JOIN users u ON u.id = c.user_id
+							WHERE c.user_id = $3 AND c.company_id = e.company_id AND u.is_verified
+						)
+						OR EXISTS (
+							SELECT 1
+							FROM work_emails w
+							WHERE w.user_id = $3 AND w.company_id = e.company_id AND w.valid_until > CURRENT_TIMESTAMP
+						)
+					) AS "is_coworker!"
+				FROM experience e
+				WHERE e.id = $1 AND e.user_id = $2
+			"#,
+			experience_id,
+			requester_id,
+			verifier_id
+		)
+		.fetch_optional(&*self.database.pool)
+		.await
+		.context("Failed to check verification eligibility")?;
+
+		Ok(eligibility)
+	}
+
+	pub async fn count_requests(&self, requester_id: &i64) -> anyhow::Result<RequestCounts> {
+		let counts = sqlx::query_as!(
+			RequestCounts,
+			r#"
+				SELECT
+					COUNT(*) FILTER (WHERE status = 'pending') AS "pending!",
+					COUNT(*) FILTER (WHERE created_at > CURRENT_TIMESTAMP - INTERVAL '1 day') AS "last_day!"
+				FROM experience_verifications
+				WHERE requester_id = $1
+			"#,
+			requester_id
+		)
+		.fetch_one(&*self.database.pool)
+		.await
+		.context("Failed to count verification requests")?;
+
+		Ok(counts)
+	}
+
+	/// Returns `None` when the verifier was already asked about the entry, whatever they answered
+	pub async fn create(
+		&self,
+		experience_id: &i64,
+		company_id: &i64,
+		requester_id: &i64,
+		verifier_id: &i64,
+	) -> anyhow::Result<Option<Verification>> {
+		let verification = sqlx::query_as!(
+			Verification,
+			r#"
+				INSERT INTO experience_verifications (experience_id, company_id, requester_id, verifier_id)
+				VALUES ($1, $2, $3, $4)
+				ON CONFLICT (experience_id, verifier_id) DO NOTHING
+				RETURNING
+					id, experience_id, company_id, requester_id, verifier_id,
+					status AS "status: VerificationStatus", created_at, responded_at
+			"#,
+			experience_id,
+			company_id,
+			requester_id,
+			verifier_id
+		)
+		.fetch_optional(&*self.database.pool)
+		.await
+		.context("Failed to create verification request")?;
+
+		Ok(verification)
+	}
+
+	/// Requests the user was asked to answer, newest first
+	pub async fn get_incoming(&self, verifier_id: &i64) -> anyhow::Result<Vec<VerificationRequest>> {
+		let requests = sqlx::query_as!(
+			VerificationRequest,
+			r#"
+				SELECT
+					v.id, v.experience_id, v.company_id, c.name AS company_name, e.occupation,
+					v.requester_id, requester.username AS requester_username,
+					v.verifier_id, verifier.username AS verifier_username,
+					v.status AS "status: VerificationStatus", v.created_at, v.responded_at
+				FROM experience_verifications v
+				JOIN experience e ON e.id = v.experience_id
+				JOIN companies c ON c.id = v.company_id
+				JOIN users requester ON requester.id = v.requester_id
+				JOIN users verifier ON verifier.id = v.verifier_id
+				WHERE v.verifier_id = $1
+				ORDER BY v.created_at DESC
+			"#,
+			verifier_id
+		)
+		.fetch_all(&*self.database.pool)
+		.await
+		.context("Failed to get incoming verification requests")?;
+
+		Ok(requests)
+	}
+
+	/// Requests the user sent, newest first
+	pub async fn get_outgoing(&self, requester_id: &i64) -> anyhow::Result<Vec<VerificationRequest>> {
+		let requests = sqlx::query_as!(
+			VerificationRequest,
+			r#"
+				SELECT
+					v.id, v.experience_id, v.company_id, c.name AS company_name, e.occupation,
+					v.requester_id, requester.username AS requester_username,
+					v.verifier_id, verifier.username AS verifier_username,
+					v.status AS "status: VerificationStatus", v.created_at, v.responded_at
+				FROM experience_verifications v
+				JOIN experience e ON e.id = v.experience_id
+				JOIN companies c ON c.id = v.company_id
+				JOIN users requester ON requester.id = v.requester_id
+				JOIN users verifier ON verifier.id = v.verifier_id
+				WHERE v.requester_id = $1
+				ORDER BY v.created_at DESC
+			"#,
+			requester_id
+		)
+		.fetch_all(&*self.database.pool)
+		.await
+		.context("Failed to get outgoing verification requests")?;
+
+		Ok(requests)
+	}
+
+	/// Moves one of the verifier's requests from `from` to `to`. Returns `None` when there's no such request
+	/// in the `from` status.
+	pub async fn transition(
+		&self,
+		id: &i64,
+		verifier_id: &i64,
+		from: VerificationStatus,
+		to: VerificationStatus,
+	) -> anyhow::Result<Option<Verification>> {
+		let verification = sqlx::query_as!(
+			Verification,
+			r#"
+				UPDATE experience_verifications
+				SET status = $4, responded_at = CURRENT_TIMESTAMP
+				WHERE id = $1 AND verifier_id = $2 AND status = $3
+				RETURNING
+					id, experience_id, company_id, requester_id, verifier_id,
+					status AS "status: VerificationStatus", created_at, responded_at
+			"#,
+			id,
+			verifier_id,
+			from as VerificationStatus,
+			to as VerificationStatus
+		)
+		.fetch_optional(&*self.database.pool)
+		.await
+		.context("Failed to update verification request")?;
+
+		Ok(verification)
+	}
+
+	/// Accepted verifications per entry of the user, counting only the ones given for the company the entry
+	/// still lists
+	pub async fn count_verifiers(&self, user_id: &i64) -> anyhow::Result<Vec<VerifierCount>> {
+		let counts = sqlx::query_as!(
+			VerifierCount,
+			r#"
+				SELECT v.experience_id, COUNT(*) AS "verifiers!"
+				FROM experience_verifications v
+				JOIN experience e ON e.id = v.experience_id AND e.company_id = v.company_id
+				WHERE e.user_id = $1 AND v.status = 'accepted'
+				GROUP BY v.experience_id
+			"#,
+			user_id
+		)
+		.fetch_all(&*self.database.pool)
+		.await
+		.context("Failed to count verifiers")?;
+
+		Ok(counts)
+	}
+}
+
+/// Like the `verification_status` enum in the database
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "verification_status", rename_all = "lowercase")]
+pub enum VerificationStatus {
+	Pending,
+	Accepted,
+	Declined,
+	/// Accepted at first, then withdrawn by the verifier
+	Revoked,
+}
+
+pub struct Eligibility {
+	/// `None` when the entry isn't linked to a company
+	pub company_id: Option<i64>,
+	pub is_coworker: bool,
+}
+
+pub struct RequestCounts {
+	pub pending: i64,
+	pub last_day: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Verification {
+	pub id: i64,
+	pub experience_id: i64,
+	pub company_id: i64,
+	pub requester_id: i64,
+	pub verifier_id: i64,
+	pub status: VerificationStatus,
+	pub created_at: DateTime<Utc>,
+	pub responded_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationRequest {
+	pub id: i64,
+	pub experience_id: i64,
+	pub company_id: i64,
+	pub company_name: String,
+	pub occupation: String,
+	pub requester_id: i64,
+	pub requester_username: String,
+	pub verifier_id: i64,
+	pub verifier_username: String,
+	pub status: VerificationStatus,
+	pub created_at: DateTime<Utc>,
+	pub responded_at: Option<DateTime<Utc>>,
+}
+
+pub struct VerifierCount {
+	pub experience_id: i64,
+	pub verifiers: i64,
+}
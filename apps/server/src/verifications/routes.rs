@@ -0,0 +1,228 @@
+use std::sync::Arc;
+
+use axum::{
+	extract::{Path, State},
+	http::StatusCode,
+	middleware,
+	response::{IntoResponse, Response},
+	routing::{delete, get, post},
+	Extension, Json, Router,
+};
+use thiserror::Error;
+
+use crate::{
+	app::{ApiErrorResponse, ApiResult},
+	database::Database,
+	users::auth::{self, CurrentUser},
+	validation::ValidatedJson,
+};
+
+use super::{
+	dtos::RequestVerificationDto,
+	repository::{Verification, VerificationRequest, VerificationStatus, VerificationsRepository},
+};
+
+/// Unanswered requests a user can have out at once, so coworkers can't be flooded
+const MAX_PENDING_REQUESTS: i64 = 10;
+/// Requests a user can send within 24 hours, answered or not
+const MAX_DAILY_REQUESTS: i64 = 20;
+
+#[derive(Clone)]
+pub struct VerificationsState {
+	pub verifications_repository: VerificationsRepository,
+}
+
+impl VerificationsState {
+	pub fn new(database: &Arc<Database>) -> Self {
+		Self {
+			verifications_repository: VerificationsRepository::new(database),
+		}
+	}
+}
+
+pub fn init() -> Router<VerificationsState> {
+	Router::new()
+		.route(
+			"/experience/{experience_id}/verifications",
+			post(request_verification_route),
+		)
+		.route("/verifications/incoming", get(incoming_verifications_route))
+		.route("/verifications/outgoing", get(outgoing_verifications_route))
+		.route(
+			"/verifications/{verification_id}/accept",
+			post(accept_verification_route),
+		)
+		.route(
+			"/verifications/{verification_id}/decline",
+			post(decline_verification_route),
+		)
+		.route("/verifications/{verification_id}", delete(revoke_verification_route))
+		.route_layer(middleware::from_fn(auth::middleware))
+}
+
+async fn request_verification_route(
+	Extension(current_user): Extension<CurrentUser>,
+	State(state): State<VerificationsState>,
+	Path(experience_id): Path<i64>,
+	ValidatedJson(request): ValidatedJson<RequestVerificationDto>,
+) -> ApiResult<Json<Verification>> {
+	let eligibility = state
+		.verifications_repository
+		.check_eligibility(&experience_id, &current_user.user_id, &request.verifier_id)
+		.await
+		.map_err(|_| VerificationsApiError::Request())?
+		.ok_or(VerificationsApiError::ExperienceNotFound(experience_id))?;
+
+	let company_id = eligibility
+		.company_id
+		.filter(|_| eligibility.is_coworker)
+		.ok_or(VerificationsApiError::NotCoworker(request.verifier_id))?;
+
+	let counts = state
+		.verifications_repository
+		.count_requests(&current_user.user_id)
+		.await
+		.map_err(|_| VerificationsApiError::Request())?;
+
+	if counts.pending >= MAX_PENDING_REQUESTS || counts.last_day >= MAX_DAILY_REQUESTS {
+		return Err(VerificationsApiError::TooManyRequests())?;
+	}
+
+	let verification = state
+		.verifications_repository
+		.create(&experience_id, &company_id, &current_user.user_id, &request.verifier_id)
+		.await
+		.map_err(|_| VerificationsApiError::Request())?
+		.ok_or(VerificationsApiError::AlreadyRequested(request.verifier_id))?;
+
+	Ok((StatusCode::CREATED, Json(verification)))
+}
+
+async fn incoming_verifications_route(
+	Extension(current_user): Extension<CurrentUser>,
+	State(state): State<VerificationsState>,
+) -> ApiResult<Json<Vec<VerificationRequest>>> {
+	let requests = state
+		.verifications_repository
+		.get_incoming(&current_user.user_id)
+		.await
+		.map_err(|_| VerificationsApiError::List())?;
+
+	Ok((StatusCode::OK, Json(requests)))
+}
+
+async fn outgoing_verifications_route(
+	Extension(current_user): Extension<CurrentUser>,
+	State(state): State<VerificationsState>,
+) -> ApiResult<Json<Vec<VerificationRequest>>> {
+	let requests = state
+		.verifications_repository
+		.get_outgoing(&current_user.user_id)
+		.await
+		.map_err(|_| VerificationsApiError::List())?;
+
+	Ok((StatusCode::OK, Json(requests)))
+}
+
+async fn accept_verification_route(
+	Extension(current_user): Extension<CurrentUser>,
+	State(state): State<VerificationsState>,
+	Path(verification_id): Path<i64>,
+) -> ApiResult<Json<Verification>> {
+	let verification = state
+		.verifications_repository
+		.transition(
+			&verification_id,
+			&current_user.user_id,
+			VerificationStatus::Pending,
+			VerificationStatus::Accepted,
+		)
+		.await
+		.map_err(|_| VerificationsApiError::Respond())?
+		.ok_or(VerificationsApiError::NotFound(verification_id))?;
+
+	Ok((StatusCode::OK, Json(verification)))
+}
+
+async fn decline_verification_route(
+	Extension(current_user): Extension<CurrentUser>,
+	State(state): State<VerificationsState>,
+	Path(verification_id): Path<i64>,
+) -> ApiResult<Json<Verification>> {
+	let verification = state
+		.verifications_repository
+		.transition(
+			&verification_id,
+			&current_user.user_id,
+			VerificationStatus::Pending,
+			VerificationStatus::Declined,
+		)
+		.await
+		.map_err(|_| VerificationsApiError::Respond())?
+		.ok_or(VerificationsApiError::NotFound(verification_id))?;
+
+	Ok((StatusCode::OK, Json(verification)))
+}
+
+/// Withdraws a verification the user gave; the entry's owner can't ask them again
+async fn revoke_verification_route(
+	Extension(current_user): Extension<CurrentUser>,
+	State(state): State<VerificationsState>,
+	Path(verification_id): Path<i64>,
+) -> ApiResult<Json<Verification>> {
+	let verification = state
+		.verifications_repository
+		.transition(
+			&verification_id,
+			&current_user.user_id,
+			VerificationStatus::Accepted,
+			VerificationStatus::Revoked,
+		)
+		.await
+		.map_err(|_| VerificationsApiError::Respond())?
+		.ok_or(VerificationsApiError::NotFound(verification_id))?;
+
+	Ok((StatusCode::OK, Json(verification)))
+}
+
+#[derive(Debug, Error)]
+pub enum VerificationsApiError {
+	#[error("Failed to request verification")]
+	Request(),
+
+	#[error("Failed to list verification requests")]
+	List(),
+
+	#[error("Failed to answer verification request")]
+	Respond(),
+
+	#[error("Experience not found: {0}")]
+	ExperienceNotFound(i64),
+
+	#[error("Verification request not found: {0}")]
+	NotFound(i64),
+
+	#[error("User {0} neither lists the entry's company nor holds a confirmed work email there")]
+	NotCoworker(i64),
+
+	#[error("User {0} was already asked to verify the entry")]
+	AlreadyRequested(i64),
+
+	#[error("Too many verification requests, wait for answers or try again tomorrow")]
+	TooManyRequests(),
+}
+
+impl IntoResponse for VerificationsApiError {
+	fn into_response(self) -> Response {
+		let status_code = match self {
+			Self::ExperienceNotFound(_) | Self::NotFound(_) => StatusCode::NOT_FOUND,
+			Self::NotCoworker(_) => StatusCode::BAD_REQUEST,
+			Self::AlreadyRequested(_) => StatusCode::CONFLICT,
+			Self::TooManyRequests() => StatusCode::TOO_MANY_REQUESTS,
+			Self::Request() | Self::List() | Self::Respond() => StatusCode::INTERNAL_SERVER_ERROR,
+		};
+
+		log::error!("{self:?}");
+		ApiErrorResponse::new(status_code, self.to_string()).into_response()
+	}
+}
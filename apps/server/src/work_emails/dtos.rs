@@ -0,0 +1,23 @@
+use serde::Deserialize;
+use validator::Validate;
+
+use crate::domains::validate_work_email;
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkEmailDto {
+	/// At one of the domains of the entry's company
+	#[validate(
+		email(message = "Must be a valid email address"),
+		length(max = 255),
+		custom(function = "validate_work_email")
+	)]
+	pub email: String,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfirmWorkEmailDto {
+	#[validate(length(min = 6, max = 6, message = "Code must be 6 characters"))]
+	pub code: String,
+}
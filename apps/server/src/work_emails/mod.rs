@@ -0,0 +1,4 @@
+pub mod dtos;
+pub mod repository;
+pub mod reverification;
+pub mod routes;
@@ -0,0 +1,341 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use serde::Serialize;
+
+use crate::database::Database;
+
+/// Wrong codes allowed before a new one has to be sent
+const MAX_FAILED_ATTEMPTS: i32 = 5;
+
+/// How long a confirmed address keeps its entry verified before it has to be confirmed again
+const VALIDITY_DAYS: i32 = 180;
+
+#[derive(Clone)]
+pub struct WorkEmailsRepository {
+	pub(crate) database: Arc<Database>,
+}
+
+impl WorkEmailsRepository {
+	pub fn new(database: &Arc<Database>) -> Self {
+		Self {
+			database: Arc::clone(database),
+		}
+	}
+
+	/// Returns `None` when the entry doesn't exist or belongs to someone else
+	pub async fn find_target(
+		&self,
+		experience_id: &i64,
+		user_id: &i64,
+		domain: &str,
+	) -> anyhow::Result<Option<WorkEmailTarget>> {
+		let target = sqlx::query_as!(
+			WorkEmailTarget,
+			r#"
+				SELECT
+					e.company_id,
+					c.name AS "company_name?",
+					(e.is_current OR e.end_date IS NULL) AS "is_current!",
+					EXISTS (
+						SELECT 1 FROM company_domains cd WHERE cd.company_id = e.company_id AND cd.domain = $3
+					) AS "domain_matches!"
+				FROM experience e
+				LEFT JOIN companies c ON c.id = e.company_id
+				WHERE e.id = $1 AND e.user_id = $2
+			"#,
+			experience_id,
+			user_id,
+			domain
+		)
+		.fetch_optional(&*self.database.pool)
+		.await
+		.context("Failed to find work email target")?;
+
+		Ok(target)
+	}
+
+	pub async fn get(&self, experience_id: &i64, user_id: &i64) -> anyhow::Result<Option<WorkEmail>> {
+		let work_email = sqlx::query_as!(
+			WorkEmail,
+			r#"
+				SELECT experience_id, company_id, email, code_sent_at, code_expires_at, verified_at, valid_until
+				FROM work_emails
+				WHERE experience_id = $1 AND user_id = $2
+			"#,
+			experience_id,
+			user_id
+		)
+		.fetch_optional(&*self.database.pool)
+		.await
+		.context("Failed to get work email")?;
+
+		Ok(work_email)
+	}
+
+	/// Stores a fresh code for the address and returns it. Confirming the same address again keeps the entry
+	/// verified in the meantime, a different one starts over. Returns `None` when a code went out less than a
+	/// minute ago.
+	pub async fn issue_code(
+		&self,
+		experience_id: &i64,
+		user_id: &i64,
+		company_id: &i64,
+		email: &str,
+		code_expires_at: DateTime<Utc>,
+	) -> anyhow::Result<Option<String>> {
+		let code = format!("{:06}", rand::rng().random_range(0..1_000_000));
+
+		let mut transaction = self
+			.database
+			.pool
+			.begin()
+			.await
+			.context("Failed to start transaction")?;
+
+		let issued = sqlx::query_scalar!(
+			r#"
+				INSERT INTO work_emails (experience_id, user_id, company_id, email, code, code_sent_at, code_expires_at)
+				VALUES ($1, $2, $3, $4, $5, CURRENT_TIMESTAMP, $6)
+				ON CONFLICT (experience_id) DO UPDATE
+				SET
+					company_id = EXCLUDED.company_id,
+					email = EXCLUDED.email,
+					code = EXCLUDED.code,
+					code_sent_at = EXCLUDED.code_sent_at,
+					code_expires_at = EXCLUDED.code_expires_at,
+					failed_attempts = 0,
+					verified_at = CASE
+						WHEN (work_emails.email, work_emails.company_id) = (EXCLUDED.email, EXCLUDED.company_id)
+						THEN work_emails.verified_at
+					END,
+					valid_until = CASE
+						WHEN (work_emails.email, work_emails.company_id) = (EXCLUDED.email, EXCLUDED.company_id)
+						THEN work_emails.valid_until
+					END
+				WHERE work_emails.code_sent_at IS NULL OR work_emails.code_sent_at < CURRENT_TIMESTAMP - INTERVAL '1 minute'
+				RETURNING code AS "code!"
+			"#,
+			experience_id,
+			user_id,
+			company_id,
+			email,
+			code,
+			code_expires_at
+		)
+		.fetch_optional(&mut *transaction)
+		.await
+		.context("Failed to issue work email code")?;
+
+		if issued.is_some() {
+			sqlx::query!(
+				"INSERT INTO work_email_events (user_id, email, event) VALUES ($1, $2, 'code_sent')",
+				user_id,
+				email
+			)
+			.execute(&mut *transaction)
+			.await
+			.context("Failed to record work email code")?;
+		}
+
+		transaction.commit().await.context("Failed to commit work email code")?;
+
+		Ok(issued)
+	}
+
+	/// Marks the address confirmed for another validity period when the code matches, otherwise counts the
+	/// attempt against the code. Returns `None` for a wrong, expired or exhausted code.
+	pub async fn confirm(&self, experience_id: &i64, user_id: &i64, code: &str) -> anyhow::Result<Option<WorkEmail>> {
+		let work_email = sqlx::query_as!(
+			WorkEmail,
+			r#"
+				UPDATE work_emails
+				SET
+					code = NULL,
+					code_expires_at = NULL,
+					failed_attempts = 0,
+					verified_at = CURRENT_TIMESTAMP,
+					valid_until = CURRENT_TIMESTAMP + MAKE_INTERVAL(days => $5)
+				WHERE
+					experience_id = $1 AND user_id = $2 AND code = $3
+					AND code_expires_at > CURRENT_TIMESTAMP AND failed_attempts < $4
+				RETURNING experience_id, company_id, email, code_sent_at, code_expires_at, verified_at, valid_until
+			"#,
+			experience_id,
+			user_id,
+			code,
+			MAX_FAILED_ATTEMPTS,
+			VALIDITY_DAYS
+		)
+		.fetch_optional(&*self.database.pool)
+		.await
+		.context("Failed to confirm work email")?;
+
+		if work_email.is_none() {
+			sqlx::query!(
+				r#"
+					WITH counted AS (
+						UPDATE work_emails
+						SET failed_attempts = failed_attempts + 1
+						WHERE experience_id = $1 AND user_id = $2 AND code IS NOT NULL
+						RETURNING user_id, email
+					)
+					INSERT INTO work_email_events (user_id, email, event)
+					SELECT user_id, email, 'code_failed' FROM counted
+				"#,
+				experience_id,
+				user_id
+			)
+			.execute(&*self.database.pool)
+			.await
+			.context("Failed to count work email attempt")?;
+		}
+
+		Ok(work_email)
+	}
+
+	/// Codes sent over the last day, to any address of the user's and to this address from anyone
+	pub async fn count_codes_sent(&self, user_id: &i64, email: &str) -> anyhow::Result<CodesSent> {
+		let codes_sent = sqlx::query_as!(
+			CodesSent,
+			r#"
+				SELECT
+					COUNT(*) FILTER (WHERE user_id = $1) AS "by_user!",
+					COUNT(*) FILTER (WHERE email = $2) AS "to_address!"
+				FROM work_email_events
+				WHERE
+					(user_id = $1 OR email = $2)
+					AND event = 'code_sent' AND created_at > CURRENT_TIMESTAMP - INTERVAL '1 day'
+			"#,
+			user_id,
+			email
+		)
+		.fetch_one(&*self.database.pool)
+		.await
+		.context("Failed to count work email codes")?;
+
+		Ok(codes_sent)
+	}
+
+	/// Wrong codes the user entered over the last day, across every code they were sent
+	pub async fn count_failed_attempts(&self, user_id: &i64) -> anyhow::Result<i64> {
+		let failed_attempts = sqlx::query_scalar!(
+			r#"
+				SELECT COUNT(*) AS "count!"
+				FROM work_email_events
+				WHERE user_id = $1 AND event = 'code_failed' AND created_at > CURRENT_TIMESTAMP - INTERVAL '1 day'
+			"#,
+			user_id
+		)
+		.fetch_one(&*self.database.pool)
+		.await
+		.context("Failed to count work email attempts")?;
+
+		Ok(failed_attempts)
+	}
+
+	/// Drops events too old to count towards any limit
+	pub async fn prune_events(&self) -> anyhow::Result<()> {
+		sqlx::query!("DELETE FROM work_email_events WHERE created_at <= CURRENT_TIMESTAMP - INTERVAL '1 day'")
+			.execute(&*self.database.pool)
+			.await
+			.context("Failed to prune work email events")?;
+
+		Ok(())
+	}
+
+	pub async fn delete(&self, experience_id: &i64, user_id: &i64) -> anyhow::Result<()> {
+		sqlx::query!(
+			"DELETE FROM work_emails WHERE experience_id = $1 AND user_id = $2",
+			experience_id,
+			user_id
+		)
+		.execute(&*self.database.pool)
+		.await
+		.context("Failed to delete work email")?;
+
+		Ok(())
+	}
+
+	/// The user's entries an address still vouches for: confirmed, not yet due again, and at the company the
+	/// entry lists
+	pub async fn get_verified_entries(&self, user_id: &i64) -> anyhow::Result<Vec<i64>> {
+		let experience_ids = sqlx::query_scalar!(
+			r#"
+				SELECT w.experience_id
+				FROM work_emails w
+				JOIN experience e ON e.id = w.experience_id AND e.company_id = w.company_id
+				WHERE w.user_id = $1 AND w.valid_until > CURRENT_TIMESTAMP
+			"#,
+			user_id
+		)
+		.fetch_all(&*self.database.pool)
+		.await
+		.context("Failed to get verified entries")?;
+
+		Ok(experience_ids)
+	}
+
+	/// Confirmed addresses of current roles that run out within the window and weren't sent a code for it yet
+	pub async fn get_due(&self, window_days: i32) -> anyhow::Result<Vec<DueWorkEmail>> {
+		let due = sqlx::query_as!(
+			DueWorkEmail,
+			r#"
+				SELECT w.experience_id, w.user_id, w.company_id, c.name AS company_name, w.email,
+					w.valid_until AS "valid_until!"
+				FROM work_emails w
+				JOIN experience e ON e.id = w.experience_id AND e.company_id = w.company_id
+				JOIN companies c ON c.id = w.company_id
+				WHERE
+					(e.is_current OR e.end_date IS NULL)
+					AND w.valid_until > CURRENT_TIMESTAMP
+					AND w.valid_until <= CURRENT_TIMESTAMP + MAKE_INTERVAL(days => $1)
+					AND w.code_sent_at < w.valid_until - MAKE_INTERVAL(days => $1)
+			"#,
+			window_days
+		)
+		.fetch_all(&*self.database.pool)
+		.await
+		.context("Failed to get work emails due for confirmation")?;
+
+		Ok(due)
+	}
+}
+
+pub struct WorkEmailTarget {
+	/// `None` when the entry isn't linked to a company
+	pub company_id: Option<i64>,
+	pub company_name: Option<String>,
+	pub is_current: bool,
+	/// Whether the address's domain is one of the company's
+	pub domain_matches: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkEmail {
+	pub experience_id: i64,
+	pub company_id: i64,
+	pub email: String,
+	pub code_sent_at: Option<DateTime<Utc>>,
+	pub code_expires_at: Option<DateTime<Utc>>,
+	pub verified_at: Option<DateTime<Utc>>,
+	/// The entry shows as verified until then
+	pub valid_until: Option<DateTime<Utc>>,
+}
+
+pub struct CodesSent {
+	pub by_user: i64,
+	pub to_address: i64,
+}
+
+pub struct DueWorkEmail {
+	pub experience_id: i64,
+	pub user_id: i64,
+	pub company_id: i64,
+	pub company_name: String,
+	pub email: String,
+	pub valid_until: DateTime<Utc>,
+}
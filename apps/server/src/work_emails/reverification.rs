@@ -0,0 +1,71 @@
+//! Background job asking users to confirm their work email again before its verification runs out, so an
+//! entry only stays verified while the address keeps working
+
+use std::{sync::Arc, time::Duration};
+
+use crate::{database::Database, services::email::EmailService};
+
+use super::repository::WorkEmailsRepository;
+
+/// How far ahead of the end of the validity period the new code goes out; it stays usable until the end
+const REVERIFICATION_WINDOW_DAYS: i32 = 14;
+
+const CHECK_INTERVAL: Duration = Duration::from_hours(1);
+
+pub fn spawn(database: &Arc<Database>) {
+	let work_emails_repository = WorkEmailsRepository::new(database);
+	let email_service = EmailService::new();
+
+	tokio::spawn(async move {
+		let mut interval = tokio::time::interval(CHECK_INTERVAL);
+
+		loop {
+			interval.tick().await;
+
+			if let Err(error) = send_codes(&work_emails_repository, &email_service).await {
+				log::error!("Failed to send work email reverification codes: {error:?}");
+			}
+
+			if let Err(error) = work_emails_repository.prune_events().await {
+				log::error!("{error:?}");
+			}
+		}
+	});
+}
+
+async fn send_codes(work_emails_repository: &WorkEmailsRepository, email_service: &EmailService) -> anyhow::Result<()> {
+	let due = work_emails_repository.get_due(REVERIFICATION_WINDOW_DAYS).await?;
+
+	for work_email in due {
+		let Some(code) = work_emails_repository
+			.issue_code(
+				&work_email.experience_id,
+				&work_email.user_id,
+				&work_email.company_id,
+				&work_email.email,
+				work_email.valid_until,
+			)
+			.await?
+		else {
+			continue;
+		};
+
+		let email_service = email_service.clone();
+		let (email, company_name) = (work_email.email, work_email.company_name);
+
+		// SMTP delivery blocks, keep it off the async workers
+		let sent =
+			tokio::task::spawn_blocking(move || email_service.send_work_email_code(&email, &company_name, &code))
+				.await?;
+
+		// The code is stored either way, and the user can still have a new one sent from their profile
+		if let Err(error) = sent {
+			log::error!(
+				"Failed to send reverification code for experience {}: {error}",
+				work_email.experience_id
+			);
+		}
+	}
+
+	Ok(())
+}
@@ -0,0 +1,238 @@
+use std::sync::Arc;
+
+use axum::{
+	extract::{Path, State},
+	http::StatusCode,
+	middleware,
+	response::{IntoResponse, Response},
+	routing::{delete, get, post},
+	Extension, Json, Router,
+};
+use chrono::{TimeDelta, Utc};
+use thiserror::Error;
+
+use crate::{
+	app::{ApiErrorResponse, ApiResult},
+	database::Database,
+	domains::email_domain,
+	services::email::EmailService,
+	users::auth::{self, CurrentUser},
+	validation::ValidatedJson,
+};
+
+use super::{
+	dtos::{ConfirmWorkEmailDto, WorkEmailDto},
+	repository::{WorkEmail, WorkEmailsRepository},
+};
+
+const CODE_LIFETIME: TimeDelta = TimeDelta::minutes(15);
+
+/// Codes a user can have sent in a day, over all their entries and addresses
+const MAX_DAILY_CODES_PER_USER: i64 = 10;
+
+/// Codes an address can be sent in a day, whoever asks, so it can't be flooded
+const MAX_DAILY_CODES_PER_ADDRESS: i64 = 3;
+
+/// Wrong codes a user can enter in a day. Each code allows a few tries, this caps them across resends.
+const MAX_DAILY_FAILED_ATTEMPTS: i64 = 10;
+
+#[derive(Clone)]
+pub struct WorkEmailsState {
+	pub work_emails_repository: WorkEmailsRepository,
+	pub email_service: EmailService,
+}
+
+impl WorkEmailsState {
+	pub fn new(database: &Arc<Database>) -> Self {
+		Self {
+			work_emails_repository: WorkEmailsRepository::new(database),
+			email_service: EmailService::new(),
+		}
+	}
+}
+
+pub fn init() -> Router<WorkEmailsState> {
+	Router::new()
+		.route("/experience/{experience_id}/work-email", get(get_work_email_route))
+		.route(
+			"/experience/{experience_id}/work-email",
+			post(send_work_email_code_route),
+		)
+		.route(
+			"/experience/{experience_id}/work-email",
+			delete(delete_work_email_route),
+		)
+		.route(
+			"/experience/{experience_id}/work-email/confirm",
+			post(confirm_work_email_route),
+		)
+		.route_layer(middleware::from_fn(auth::middleware))
+}
+
+async fn get_work_email_route(
+	Extension(current_user): Extension<CurrentUser>,
+	State(state): State<WorkEmailsState>,
+	Path(experience_id): Path<i64>,
+) -> ApiResult<Json<WorkEmail>> {
+	let work_email = state
+		.work_emails_repository
+		.get(&experience_id, &current_user.user_id)
+		.await
+		.map_err(|_| WorkEmailsApiError::Get())?
+		.ok_or(WorkEmailsApiError::NotFound(experience_id))?;
+
+	Ok((StatusCode::OK, Json(work_email)))
+}
+
+/// Sends a code to an address at the company's domain, proving a current role at the company once confirmed
+async fn send_work_email_code_route(
+	Extension(current_user): Extension<CurrentUser>,
+	State(state): State<WorkEmailsState>,
+	Path(experience_id): Path<i64>,
+	ValidatedJson(request): ValidatedJson<WorkEmailDto>,
+) -> ApiResult<()> {
+	let email = request.email.to_lowercase();
+	let domain = email_domain(&email).ok_or_else(|| WorkEmailsApiError::DomainMismatch(email.clone()))?;
+
+	let target = state
+		.work_emails_repository
+		.find_target(&experience_id, &current_user.user_id, &domain)
+		.await
+		.map_err(|_| WorkEmailsApiError::SendCode())?
+		.ok_or(WorkEmailsApiError::ExperienceNotFound(experience_id))?;
+
+	if !target.is_current {
+		return Err(WorkEmailsApiError::NotCurrent(experience_id))?;
+	}
+
+	let (Some(company_id), Some(company_name), true) = (target.company_id, target.company_name, target.domain_matches)
+	else {
+		return Err(WorkEmailsApiError::DomainMismatch(email))?;
+	};
+
+	let codes_sent = state
+		.work_emails_repository
+		.count_codes_sent(&current_user.user_id, &email)
+		.await
+		.map_err(|_| WorkEmailsApiError::SendCode())?;
+
+	if codes_sent.by_user >= MAX_DAILY_CODES_PER_USER || codes_sent.to_address >= MAX_DAILY_CODES_PER_ADDRESS {
+		return Err(WorkEmailsApiError::TooManyCodes())?;
+	}
+
+	let code = state
+		.work_emails_repository
+		.issue_code(
+			&experience_id,
+			&current_user.user_id,
+			&company_id,
+			&email,
+			Utc::now() + CODE_LIFETIME,
+		)
+		.await
+		.map_err(|_| WorkEmailsApiError::SendCode())?
+		.ok_or(WorkEmailsApiError::TooSoon())?;
+
+	let email_service = state.email_service.clone();
+
+	// SMTP delivery blocks, keep it off the async workers
+	tokio::task::spawn_blocking(move || email_service.send_work_email_code(&email, &company_name, &code))
+		.await
+		.map_err(|_| WorkEmailsApiError::SendCode())?
+		.map_err(|_| WorkEmailsApiError::SendCode())?;
+
+	Ok((StatusCode::ACCEPTED, ()))
+}
+
+async fn confirm_work_email_route(
+	Extension(current_user): Extension<CurrentUser>,
+	State(state): State<WorkEmailsState>,
+	Path(experience_id): Path<i64>,
+	ValidatedJson(request): ValidatedJson<ConfirmWorkEmailDto>,
+) -> ApiResult<Json<WorkEmail>> {
+	let failed_attempts = state
+		.work_emails_repository
+		.count_failed_attempts(&current_user.user_id)
+		.await
+		.map_err(|_| WorkEmailsApiError::Confirm())?;
+
+	if failed_attempts >= MAX_DAILY_FAILED_ATTEMPTS {
+		return Err(WorkEmailsApiError::TooManyAttempts())?;
+	}
+
+	let work_email = state
+		.work_emails_repository
+		.confirm(&experience_id, &current_user.user_id, &request.code)
+		.await
+		.map_err(|_| WorkEmailsApiError::Confirm())?
+		.ok_or(WorkEmailsApiError::InvalidCode())?;
+
+	Ok((StatusCode::OK, Json(work_email)))
+}
+
+async fn delete_work_email_route(
+	Extension(current_user): Extension<CurrentUser>,
+	State(state): State<WorkEmailsState>,
+	Path(experience_id): Path<i64>,
+) -> ApiResult<()> {
+	state
+		.work_emails_repository
+		.delete(&experience_id, &current_user.user_id)
+		.await
+		.map_err(|_| WorkEmailsApiError::Delete())?;
+
+	Ok((StatusCode::OK, ()))
+}
+
+#[derive(Debug, Error)]
+pub enum WorkEmailsApiError {
+	#[error("Failed to get work email")]
+	Get(),
+
+	#[error("Failed to send work email code")]
+	SendCode(),
+
+	#[error("Failed to confirm work email")]
+	Confirm(),
+
+	#[error("Failed to delete work email")]
+	Delete(),
+
+	#[error("Experience not found: {0}")]
+	ExperienceNotFound(i64),
+
+	#[error("No work email for experience {0}")]
+	NotFound(i64),
+
+	#[error("Only current roles can be verified with a work email: {0}")]
+	NotCurrent(i64),
+
+	#[error("Address isn't at one of the company's domains: {0}")]
+	DomainMismatch(String),
+
+	#[error("A code was sent less than a minute ago")]
+	TooSoon(),
+
+	#[error("Too many codes were sent today, try again tomorrow")]
+	TooManyCodes(),
+
+	#[error("Too many wrong codes were entered today, try again tomorrow")]
+	TooManyAttempts(),
+
+	#[error("Wrong or expired code")]
+	InvalidCode(),
+}
+
+impl IntoResponse for WorkEmailsApiError {
+	fn into_response(self) -> Response {
+		let status_code = match self {
+			Self::ExperienceNotFound(_) | Self::NotFound(_) => StatusCode::NOT_FOUND,
+			Self::NotCurrent(_) | Self::DomainMismatch(_) | Self::InvalidCode() => StatusCode::BAD_REQUEST,
+			Self::TooSoon() | Self::TooManyCodes() | Self::TooManyAttempts() => StatusCode::TOO_MANY_REQUESTS,
+			Self::Get() | Self::SendCode() | Self::Confirm() | Self::Delete() => StatusCode::INTERNAL_SERVER_ERROR,
+		};
+
+		log::error!("{self:?}");
+		ApiErrorResponse::new(status_code, self.to_string()).into_response()
+	}
+}